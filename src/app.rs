@@ -1,20 +1,42 @@
-use crate::git::diff::{DiffState, FileDiff};
-use crate::git::repository::{BranchInfo, CommitInfo, ReflogEntry, Repo};
-use crate::github::state::{GhFocusedPane, GitHubState};
-use crate::syntax::{HighlightCache, SyntaxHighlighter};
-use anyhow::Result;
+use crate::command::Command;
+use crate::config::Config;
+use crate::git::diff::{
+    compute_stats, forced_text_hunks, DiffHunk, DiffScope, DiffState, DiffStats, FileDiff, FileStatus,
+    LineType, SideBySideRow,
+};
+use crate::git::repository::{
+    BlameHunk, BranchInfo, CommitInfo, ReflogEntry, Repo, ResetMode, SignatureStatus,
+    SubmoduleInfo,
+};
+use crate::github::state::{GhDetailKind, GhDetailPane, GhFocusedPane, GitHubState};
+use crate::session::Session;
+use crate::syntax::{FileSources, HighlightCache, SyntaxHighlighter, DEFAULT_THEME};
+use crate::ui::cells::display_col;
+use crate::ui::color::ColorDepth;
+use crate::ui::diff_view::DiffPalette;
+use crate::ui::image_preview::{GraphicsProtocol, ImagePreview};
+use anyhow::{Context, Result};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::style::Color;
+use std::borrow::Cow;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::io::Write;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::sync::mpsc;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ViewMode {
     Git,
     GitHub,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FocusedPane {
     FileTree,
     BranchList,
@@ -33,6 +55,20 @@ pub struct GitLogState {
     pub selected_idx: usize,
     pub view_height: u16,
     pub ref_name: String,
+    /// Show "3 days ago" instead of the date and time
+    pub relative_dates: bool,
+    /// Show authors as `name <email>` rather than just the name
+    pub show_email: bool,
+    /// Other end of the range marked with `v`
+    pub anchor: Option<usize>,
+}
+
+impl GitLogState {
+    /// First and last index of the marked range, newest first.
+    pub fn range(&self) -> Option<(usize, usize)> {
+        let anchor = self.anchor?;
+        Some((anchor.min(self.selected_idx), anchor.max(self.selected_idx)))
+    }
 }
 
 pub struct ReflogState {
@@ -46,13 +82,15 @@ pub enum BranchAction {
     Switch,
     Delete,
     DiffBase,
+    MergeBase,
 }
 
 impl BranchAction {
-    pub const ALL: [BranchAction; 3] = [
+    pub const ALL: [BranchAction; 4] = [
         BranchAction::Switch,
         BranchAction::Delete,
         BranchAction::DiffBase,
+        BranchAction::MergeBase,
     ];
 
     pub fn label(self) -> &'static str {
@@ -60,6 +98,7 @@ impl BranchAction {
             BranchAction::Switch => "Switch",
             BranchAction::Delete => "Delete",
             BranchAction::DiffBase => "Set as diff base",
+            BranchAction::MergeBase => "Diff vs merge-base",
         }
     }
 
@@ -68,6 +107,7 @@ impl BranchAction {
             BranchAction::Switch => 's',
             BranchAction::Delete => 'd',
             BranchAction::DiffBase => 'b',
+            BranchAction::MergeBase => 'm',
         }
     }
 }
@@ -78,17 +118,91 @@ pub struct BranchActionMenuState {
     pub selected_idx: usize,
 }
 
+/// `R` — choose how to reset HEAD to a reflog entry or commit.
+pub struct ResetMenuState {
+    pub full_hash: String,
+    pub short_hash: String,
+    pub selected_idx: usize,
+}
+
+/// `Ctrl+p` — jump to a changed file by fuzzy-matching its path.
+pub struct FileFinderState {
+    pub query: String,
+    /// Matching files as (index into `diff_state.files`, matched char
+    /// positions), best first
+    pub matches: Vec<(usize, Vec<usize>)>,
+    pub selected: usize,
+}
+
+/// `D` — prompt for two refs to diff against each other.
+pub struct RefPickerState {
+    /// Old and new ref
+    pub fields: [String; 2],
+    /// Index of the field being edited
+    pub active: usize,
+    pub error: Option<String>,
+}
+
+/// `B` — pick the diff base from the repository's refs.
+pub struct BasePickerState {
+    pub query: String,
+    pub refs: Vec<String>,
+    /// Indices into `refs` that match the query, best first, with matched positions
+    pub matches: Vec<(usize, Vec<usize>)>,
+    pub selected: usize,
+    pub error: Option<String>,
+}
+
+/// External diff tool run requested with `E`. main.rs hands it the terminal.
+pub struct DiffToolLaunch {
+    pub program: String,
+    pub args: Vec<OsString>,
+    /// Private directory with the copies of revisions written for the tool,
+    /// removed once it exits
+    pub temp_dir: PathBuf,
+}
+
+/// Last commit to touch the line under the cursor, shown by `K`.
+pub struct BlamePopup {
+    pub line_no: u32,
+    /// None when the line isn't committed yet
+    pub commit: Option<CommitInfo>,
+}
+
+/// A blame run on a background thread for the popup of `line_no`.
+struct BlameDone {
+    key: (PathBuf, Option<String>),
+    line_no: u32,
+    result: Result<Vec<BlameHunk>>,
+}
+
 pub struct ErrorDialogState {
     pub title: String,
     pub message: String,
 }
 
+#[derive(Debug, Clone)]
+pub enum ConfirmAction {
+    Quit,
+    Reset { hash: String, mode: ResetMode },
+    /// Convert a PR to a draft (`draft`) or mark it ready for review
+    SetPrDraft { number: u64, draft: bool },
+}
+
+pub struct ConfirmDialogState {
+    pub title: String,
+    pub message: String,
+    pub action: ConfirmAction,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DiffViewMode {
     Scroll,
     Normal,
     Visual,
     VisualLine,
+    /// Rectangle between the anchor and the cursor (`Ctrl+v`)
+    VisualBlock,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -98,6 +212,8 @@ pub enum SearchOrigin {
     CommitLog,
     BranchList,
     Reflog,
+    GitHubDetail,
+    UnifiedView,
 }
 
 #[derive(Debug, Clone)]
@@ -112,8 +228,114 @@ pub enum SearchMatch {
     CommitEntry(usize),
     BranchEntry(usize),
     ReflogEntry(usize),
+    DetailLine {
+        pane: GhDetailPane,
+        line: usize,
+        col_start: usize,
+        col_end: usize,
+    },
+    UnifiedLine {
+        line: usize,
+        col_start: usize,
+        col_end: usize,
+    },
+}
+
+/// Scroll position and `/` filter of the help overlay.
+#[derive(Debug, Default)]
+pub struct HelpState {
+    pub scroll: u16,
+    /// Furthest useful scroll and visible rows, reported by the renderer
+    pub max_scroll: u16,
+    pub view_height: u16,
+    pub filter: String,
+    /// The filter is being typed
+    pub filtering: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnifiedLineKind {
+    /// `--- a/…` / `+++ b/…`
+    FileHeader,
+    HunkHeader,
+    Context,
+    Deleted,
+    Added,
+    /// Binary placeholder or the blank line between files
+    Note,
+}
+
+impl UnifiedLineKind {
+    /// Width of the ` ` / `-` / `+` prefix in front of the line's text.
+    pub fn prefix_len(self) -> usize {
+        match self {
+            UnifiedLineKind::Context | UnifiedLineKind::Deleted | UnifiedLineKind::Added => 1,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnifiedLine {
+    pub kind: UnifiedLineKind,
+    /// The line as in a unified diff, ` ` / `-` / `+` prefix included
+    pub text: String,
+    /// Syntax colors of the text after the prefix; empty when unhighlighted
+    pub colors: Vec<Color>,
+}
+
+/// Every file's unified diff in one scrollable buffer, opened with `U`.
+#[derive(Debug, Default)]
+pub struct UnifiedViewState {
+    pub lines: Vec<UnifiedLine>,
+    pub cursor: usize,
+    pub scroll: usize,
+    pub scroll_x: usize,
+    /// Line a `v` selection started from
+    pub anchor: Option<usize>,
+    /// Visible rows and text columns, reported by the renderer
+    pub view_height: usize,
+    pub view_width: usize,
+}
+
+impl UnifiedViewState {
+    /// Lines covered by the selection, or just the cursor line.
+    pub fn selected_range(&self) -> (usize, usize) {
+        let anchor = self.anchor.unwrap_or(self.cursor);
+        (anchor.min(self.cursor), anchor.max(self.cursor))
+    }
+
+    /// Scroll so the cursor line is on screen.
+    pub fn scroll_to_cursor(&mut self) {
+        let height = self.view_height.max(1);
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + height {
+            self.scroll = self.cursor + 1 - height;
+        }
+    }
+}
+
+/// What `gh` printed for the shown issue / PR, opened with `J` under
+/// `--debug`.
+#[derive(Debug, Default)]
+pub struct RawJsonView {
+    pub title: String,
+    /// Pretty-printed when it parses as JSON, as printed otherwise
+    pub text: String,
+    pub scroll: u16,
+    /// Furthest useful scroll and visible rows, reported by the renderer
+    pub max_scroll: u16,
+    pub view_height: u16,
 }
 
+/// Longest pattern the search prompt accepts, so a stray paste can't turn
+/// into a multi-kilobyte query.
+const SEARCH_INPUT_MAX: usize = 256;
+/// Matches collected per search; past this the search stops and asks the
+/// user to refine the pattern.
+const SEARCH_MATCH_LIMIT: usize = 10_000;
+
 #[derive(Debug, Clone)]
 pub struct SearchState {
     pub active: bool,
@@ -122,6 +344,12 @@ pub struct SearchState {
     pub origin: SearchOrigin,
     pub matches: Vec<SearchMatch>,
     pub current_match_idx: Option<usize>,
+    /// `matches` stopped at `SEARCH_MATCH_LIMIT`
+    pub truncated: bool,
+    /// Only match whole words (set by `*` / `#`)
+    pub whole_word: bool,
+    /// Visual selection (start, end, mode) that DiffView matches are limited to
+    pub within: Option<(CursorPos, CursorPos, DiffViewMode)>,
     /// Last confirmed query — preserved across clear() for n/N reuse
     pub last_query: Option<String>,
     /// Search history (oldest first)
@@ -130,6 +358,9 @@ pub struct SearchState {
     history_idx: Option<usize>,
     /// Saved input before browsing history
     saved_input: String,
+    /// Commits past the loaded Git Log that a history search turned up,
+    /// shown after it until the search is cleared
+    pub history_commits: Vec<CommitInfo>,
 }
 
 impl SearchState {
@@ -141,10 +372,14 @@ impl SearchState {
             origin: SearchOrigin::DiffView,
             matches: Vec::new(),
             current_match_idx: None,
+            truncated: false,
+            whole_word: false,
+            within: None,
             last_query: None,
             history: Vec::new(),
             history_idx: None,
             saved_input: String::new(),
+            history_commits: Vec::new(),
         }
     }
 
@@ -153,8 +388,9 @@ impl SearchState {
         self.input.clear();
         self.query = None;
         self.origin = origin;
-        self.matches.clear();
-        self.current_match_idx = None;
+        self.reset_matches();
+        self.whole_word = false;
+        self.within = None;
         self.history_idx = None;
         self.saved_input.clear();
     }
@@ -162,6 +398,19 @@ impl SearchState {
     pub fn reset_matches(&mut self) {
         self.matches.clear();
         self.current_match_idx = None;
+        self.truncated = false;
+        self.history_commits.clear();
+    }
+
+    /// Record a match unless `SEARCH_MATCH_LIMIT` is reached. Returns false
+    /// once it is, so the caller can stop scanning.
+    fn push_match(&mut self, m: SearchMatch) -> bool {
+        if self.matches.len() >= SEARCH_MATCH_LIMIT {
+            self.truncated = true;
+            return false;
+        }
+        self.matches.push(m);
+        true
     }
 
     /// Clear highlights but preserve last_query and history for n/N reuse
@@ -171,8 +420,7 @@ impl SearchState {
         if self.query.is_some() {
             self.last_query = self.query.take();
         }
-        self.matches.clear();
-        self.current_match_idx = None;
+        self.reset_matches();
     }
 
     /// Navigate to previous history entry
@@ -239,12 +487,293 @@ pub struct CursorPos {
     pub side: DiffSide,
 }
 
+/// Content strings of one side of the shown file, built only for a window of
+/// its display rows. Rows outside the window read as missing.
+#[derive(Debug, Default)]
+pub struct ContentLines {
+    path: String,
+    side: Option<DiffSide>,
+    /// Display row of `lines[0]`
+    start: usize,
+    /// Display rows in the whole file
+    total: usize,
+    lines: Vec<String>,
+}
+
+impl ContentLines {
+    /// Build the rows of `side` of `file` that fall in `rows`; context rows
+    /// are left out when `changes_only`, as the diff view does.
+    fn build(file: &FileDiff, side: DiffSide, changes_only: bool, rows: Range<usize>) -> Self {
+        let shown = |row: &&SideBySideRow| !changes_only || row.line_type != LineType::Context;
+        let mut lines = Vec::new();
+        let mut total = 0;
+        for hunk in &file.hunks {
+            let len = 1 + match changes_only {
+                true => hunk.rows.iter().filter(shown).count(),
+                false => hunk.rows.len(),
+            };
+            if total < rows.end && rows.start < total + len {
+                let texts = hunk.rows.iter().filter(shown).map(|row| {
+                    let line = match side {
+                        DiffSide::Left => row.left.as_ref(),
+                        DiffSide::Right => row.right.as_ref(),
+                    };
+                    line.map_or("", |l| l.content.as_str())
+                });
+                let skip = rows.start.saturating_sub(total);
+                let take = rows.end.saturating_sub(total.max(rows.start));
+                let texts = std::iter::once(hunk.header.as_str()).chain(texts);
+                lines.extend(texts.skip(skip).take(take).map(str::to_string));
+            }
+            total += len;
+        }
+        Self { path: file.path.clone(), side: Some(side), start: rows.start.min(total), total, lines }
+    }
+
+    /// Display rows in the whole file, built or not.
+    pub fn len(&self) -> usize {
+        self.total
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    pub fn get(&self, row: usize) -> Option<&String> {
+        self.lines.get(row.checked_sub(self.start)?)
+    }
+
+    fn covers(&self, rows: &Range<usize>) -> bool {
+        let end = rows.end.min(self.total);
+        rows.start >= end || (self.start <= rows.start && end <= self.start + self.lines.len())
+    }
+}
+
+impl std::ops::Index<usize> for ContentLines {
+    type Output = String;
+
+    fn index(&self, row: usize) -> &String {
+        self.get(row).expect("row outside the built content lines")
+    }
+}
+
+/// Last yank in the diff view, replayed by `.` in Normal mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LastYank {
+    /// `y{motion}` with its count
+    Motion { motion: KeyCode, count: usize },
+    /// Visual selection shape: extra rows below the start, and the end column
+    /// (a width relative to the start when the selection is single-line)
+    Visual { line_wise: bool, rows: usize, cols: usize },
+    /// Visual block: extra rows below and extra columns right of the start
+    Block { rows: usize, cols: usize },
+}
+
+/// Character classes used by word motions, mirroring vim's `iskeyword` split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Whitespace,
+    Word,
+    Punct,
+}
+
+/// Classify a char for word motions. With `big_word` (vim `W`/`B`/`E`),
+/// every non-whitespace char belongs to the same class.
+fn char_class(c: char, big_word: bool) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Whitespace
+    } else if big_word || c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punct
+    }
+}
+
+/// A new directory under the system temp dir that only the user can read,
+/// so other users can't read or swap the diff tool's copies.
+fn private_temp_dir() -> std::io::Result<PathBuf> {
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    let mut attempt = 0;
+    loop {
+        let dir = std::env::temp_dir().join(format!("vig-{}-{attempt}", std::process::id()));
+        match builder.create(&dir) {
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => attempt += 1,
+            result => return result.map(|()| dir),
+        }
+    }
+}
+
+fn hunk_headers(file: &FileDiff) -> Vec<String> {
+    file.hunks.iter().map(|h| h.header.clone()).collect()
+}
+
+/// Keyword under `col`, or the first one after it on the line (vim `*`).
+fn word_at(line: &str, col: usize) -> Option<String> {
+    let chars: Vec<char> = line.chars().collect();
+    let is_word = |c: char| char_class(c, false) == CharClass::Word;
+    let mut start = col.min(chars.len());
+    if !chars.get(start).is_some_and(|&c| is_word(c)) {
+        start = (start..chars.len()).find(|&i| is_word(chars[i]))?;
+    }
+    while start > 0 && is_word(chars[start - 1]) {
+        start -= 1;
+    }
+    let mut end = start;
+    while end < chars.len() && is_word(chars[end]) {
+        end += 1;
+    }
+    Some(chars[start..end].iter().collect())
+}
+
+/// What the two sides of the diff show, detached from the `App` so file
+/// contents can be read on a background thread with a `Repo` of its own.
+#[derive(Clone)]
+struct DiffSides {
+    compare_refs: Option<(String, String)>,
+    base_ref: Option<String>,
+    scope: DiffScope,
+}
+
+impl DiffSides {
+    /// Full old/new contents of a file on each side of the current diff.
+    fn file_bytes(&self, repo: &Repo, file: &FileDiff) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+        if let Some((old, new)) = &self.compare_refs {
+            let old_path = file.old_path.as_deref().map_or(file.raw_path.as_path(), Path::new);
+            let left = match file.status {
+                FileStatus::Added | FileStatus::Untracked => None,
+                _ => repo.base_file_bytes(Some(old), old_path),
+            };
+            let right = match file.status {
+                FileStatus::Deleted => None,
+                _ => repo.base_file_bytes(Some(new), &file.raw_path),
+            };
+            return (left, right);
+        }
+        let left = match (file.status, self.scope) {
+            (FileStatus::Added | FileStatus::Untracked, _) => None,
+            (_, DiffScope::Unstaged) => repo.index_file_bytes(&file.raw_path),
+            _ => repo.base_file_bytes(self.base_ref.as_deref(), &file.raw_path),
+        };
+        let right = match (file.status, self.scope) {
+            (FileStatus::Deleted, _) => None,
+            (_, DiffScope::Staged) => repo.index_file_bytes(&file.raw_path),
+            _ => std::fs::read(repo.workdir().join(&file.raw_path)).ok(),
+        };
+        (left, right)
+    }
+
+    /// Full old/new text of a file, used to seed highlighting at hunk starts.
+    fn file_sources(&self, repo: &Repo, file: &FileDiff) -> FileSources {
+        let (left, right) = self.file_bytes(repo, file);
+        let text = |bytes: Vec<u8>| String::from_utf8_lossy(&bytes).into_owned();
+        FileSources {
+            left: left.map(text),
+            right: right.map(text),
+            hunk_lines: file
+                .hunks
+                .iter()
+                .map(|h| (h.old_start as usize, h.new_start as usize))
+                .collect(),
+        }
+    }
+}
+
+/// Per-character syntax colors of each display row, old side then new side.
+type SideColors = (Vec<Vec<Color>>, Vec<Vec<Color>>);
+
+/// Old and new text of each display row of `file`, blank for hunk headers,
+/// and the rows where hunks start: the input to syntax highlighting.
+fn highlight_lines(file: &FileDiff) -> (Vec<String>, Vec<String>, Vec<usize>) {
+    let mut left_lines = Vec::new();
+    let mut right_lines = Vec::new();
+    let mut hunk_starts = Vec::new();
+    for hunk in &file.hunks {
+        hunk_starts.push(left_lines.len());
+        left_lines.push(String::new());
+        right_lines.push(String::new());
+        for row in &hunk.rows {
+            left_lines.push(row.left.as_ref().map(|s| s.content.clone()).unwrap_or_default());
+            right_lines.push(row.right.as_ref().map(|s| s.content.clone()).unwrap_or_default());
+        }
+    }
+    (left_lines, right_lines, hunk_starts)
+}
+
+/// Byte offsets of case-insensitive matches of `query_lower` in `text`.
+/// With `whole_word`, matches touching keyword chars on either side are skipped.
+fn find_matches(text: &str, query_lower: &str, whole_word: bool) -> Vec<usize> {
+    let lower = text.to_lowercase();
+    lower
+        .match_indices(query_lower)
+        .map(|(start, _)| start)
+        .filter(|&start| {
+            if !whole_word {
+                return true;
+            }
+            let is_word = |c: char| char_class(c, false) == CharClass::Word;
+            let before = lower[..start].chars().next_back();
+            let after = lower[start + query_lower.len()..].chars().next();
+            !before.is_some_and(is_word) && !after.is_some_and(is_word)
+        })
+        .collect()
+}
+
+/// Columns `start.col..=end.col` of each row from `start.row` to `end.row`,
+/// one line per row; rows too short for the block give an empty line.
+fn extract_block(lines: &ContentLines, start: CursorPos, end: CursorPos) -> String {
+    (start.row..=end.row)
+        .filter_map(|r| lines.get(r))
+        .map(|line| {
+            line.chars()
+                .skip(start.col)
+                .take(end.col + 1 - start.col)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Case-insensitive subsequence match of `query` in `candidate`, giving a
+/// score (higher is better) and the char positions matched. Consecutive
+/// characters, word starts and the file name score more; gaps cost.
+fn fuzzy_match(query: &str, candidate: &str) -> Option<(i64, Vec<usize>)> {
+    let chars: Vec<char> = candidate.chars().collect();
+    let name_start = chars.iter().rposition(|&c| c == '/').map_or(0, |i| i + 1);
+    let mut positions: Vec<usize> = Vec::new();
+    let mut score = 0i64;
+    let mut next = 0;
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let found = (next..chars.len()).find(|&i| chars[i].to_lowercase().eq(q.to_lowercase()))?;
+        score += 1;
+        if positions.last().is_some_and(|&prev| prev + 1 == found) {
+            score += 5;
+        } else if let Some(&prev) = positions.last() {
+            score -= (found - prev - 1).min(3) as i64;
+        }
+        if found == 0 || matches!(chars[found - 1], '/' | '_' | '-' | '.') {
+            score += 3;
+        }
+        if found >= name_start {
+            score += 2;
+        }
+        positions.push(found);
+        next = found + 1;
+    }
+    Some((score, positions))
+}
+
 #[derive(Debug, Clone)]
 pub enum TreeEntry {
     Dir {
         path: String,
         depth: usize,
         collapsed: bool,
+        /// Number of changed files under the directory
+        files: usize,
+        /// Lines added and deleted across those files
+        stats: DiffStats,
     },
     File {
         file_idx: usize,
@@ -253,11 +782,21 @@ pub enum TreeEntry {
 }
 
 pub struct App {
+    pub config: Config,
     pub should_quit: bool,
     pub view_mode: ViewMode,
+    /// Terminal color support; RGB output is quantized for 256-color terminals.
+    pub color_depth: ColorDepth,
+    /// Inline image previews for binary image diffs.
+    pub image_preview: ImagePreview,
     pub repo: Repo,
     pub diff_state: DiffState,
+    pub submodules: Vec<SubmoduleInfo>,
+    /// Commits HEAD's branch is ahead of / behind its upstream, if it has one
+    pub ahead_behind: Option<(usize, usize)>,
     pub collapsed_dirs: HashSet<String>,
+    /// Paths of binary files shown as text anyway, toggled with `T`
+    pub forced_text: HashSet<String>,
     pub selected_tree_idx: usize,
     pub focused_pane: FocusedPane,
     pub previous_pane: FocusedPane,
@@ -265,40 +804,124 @@ pub struct App {
     pub diff_scroll_x: u16,
     pub diff_total_lines: u16,
     pub diff_view_height: u16,
+    /// Visible text columns per diff side (narrower pane minus the line-number gutter).
+    pub diff_content_width: u16,
+    /// Share of the diff width given to the old (left) side, adjusted with `<` / `>`
+    pub diff_split_ratio: f32,
+    /// Give added and untracked files, whose old side is empty, the full
+    /// width; `|` turns this off to see the usual split
+    pub full_width_new_files: bool,
     pub show_help: bool,
+    pub help: HelpState,
+    pub unified_view: Option<UnifiedViewState>,
     pub status_message: Option<String>,
     pub diff_view_mode: DiffViewMode,
     pub cursor_pos: CursorPos,
     pub visual_anchor: Option<CursorPos>,
     pub pending_key: Option<char>,
     pub count: Option<usize>,
+    pub last_yank: Option<LastYank>,
+    /// Hide context rows in the diff view (added/deleted rows only).
+    pub changes_only: bool,
+    /// Show gutter numbers as distances from the cursor row (Normal/Visual).
+    pub relative_numbers: bool,
+    /// Show the line-number gutter; `L` hides it to give the text more room.
+    pub show_line_numbers: bool,
+    /// Colors of added / deleted lines, cycled with `P`
+    pub diff_palette: DiffPalette,
+    /// Diff view marks set with `m{a-z}`, valid for `marks_file` only.
+    pub marks: HashMap<char, CursorPos>,
+    marks_file: Option<String>,
+    /// Scroll offsets and cursor remembered per file path while moving through the tree.
+    per_file_view: HashMap<String, (u16, u16, CursorPos)>,
+    /// Cursor positions before big moves, for Ctrl-O / Ctrl-I.
+    jump_list: Vec<(String, CursorPos)>,
+    /// Position in `jump_list`; equals its length when not navigating it.
+    jump_idx: usize,
     pub highlighter: SyntaxHighlighter,
     pub highlight_cache: Option<HighlightCache>,
+    /// The visible rows aren't highlighted yet; shown as "highlighting…"
+    pub highlight_pending: bool,
     /// Cached content_lines result: (file_path, side, lines). Invalidated on file/side switch.
-    content_lines_cache: Option<(String, DiffSide, Vec<String>)>,
+    content_lines_cache: Option<Rc<ContentLines>>,
     /// Pre-computed highlight results from background thread, keyed by file path.
-    bg_highlights: HashMap<String, (Vec<Vec<Color>>, Vec<Vec<Color>>)>,
+    bg_highlights: HashMap<String, SideColors>,
     /// Receiver for background highlight results.
     bg_highlight_rx: Option<mpsc::Receiver<(String, Vec<Vec<Color>>, Vec<Vec<Color>>)>>,
+    /// Signature checks by full commit hash; None while one is running
+    pub signatures: HashMap<String, Option<SignatureStatus>>,
+    signature_rx: Option<mpsc::Receiver<(String, SignatureStatus)>>,
+    /// Commits found by a running full-history search, with its query.
+    history_search_rx: Option<mpsc::Receiver<(String, Vec<CommitInfo>)>>,
     pub diff_base_ref: Option<String>,
+    /// Header label for `diff_base_ref` when it isn't self-explanatory
+    /// (e.g. `main (merge-base)`).
+    pub diff_base_label: Option<String>,
+    /// Whether the diff shows all, unstaged or staged changes.
+    pub diff_scope: DiffScope,
+    /// Two refs diffed against each other (`old..new`) instead of the
+    /// working tree against `diff_base_ref`.
+    pub compare_refs: Option<(String, String)>,
+    /// A ref of the diff was missing on the last refresh; retried next tick.
+    diff_retry_pending: bool,
+    pub ref_picker: Option<RefPickerState>,
     pub branch_list: BranchListState,
     pub git_log: GitLogState,
     pub reflog: ReflogState,
     pub branch_action_menu: Option<BranchActionMenuState>,
+    pub reset_menu: Option<ResetMenuState>,
+    pub raw_json_view: Option<RawJsonView>,
+    pub file_finder: Option<FileFinderState>,
+    pub base_picker: Option<BasePickerState>,
     pub error_dialog: Option<ErrorDialogState>,
+    pub blame_popup: Option<BlamePopup>,
+    pub diff_tool_launch: Option<DiffToolLaunch>,
+    /// Blame per (path, revision), revision None for the working tree.
+    /// Cleared on refresh.
+    blame_cache: HashMap<(PathBuf, Option<String>), Rc<Vec<BlameHunk>>>,
+    blame_rx: Option<mpsc::Receiver<BlameDone>>,
+    pub confirm_dialog: Option<ConfirmDialogState>,
     pub search: SearchState,
+    /// Text typed on the `:` command line while it is open
+    pub command_line: Option<String>,
     pub github: GitHubState,
 }
 
 impl App {
-    pub fn new(repo: Repo) -> Result<Self> {
-        let diff_state = repo.diff_workdir(None)?;
+    pub fn new(
+        repo: Repo,
+        config: Config,
+        base_ref: Option<String>,
+        diff_scope: DiffScope,
+    ) -> Result<Self> {
+        let diff_state = repo.diff_workdir(base_ref.as_deref(), diff_scope)?;
+        let diff_base_label = base_ref.as_deref().map(|r| repo.describe_ref(r));
+        let submodules = repo.submodule_status();
+        let ahead_behind = repo.upstream_ahead_behind();
+        let image_preview =
+            ImagePreview::new(config.image_preview.then(GraphicsProtocol::detect).flatten());
+        let session = Session::load(repo.workdir());
+        let view_mode = session.view.unwrap_or(config.view);
+        let diff_palette = config.diff_colors;
+        let mut github = GitHubState::new(config.issue_limit, config.pr_limit);
+        github.pinned_issues = session.pinned_issues.into_iter().collect();
+        github.pinned_prs = session.pinned_prs.into_iter().collect();
+        let mut highlighter = SyntaxHighlighter::new();
+        if let Some(theme) = &config.theme {
+            highlighter.set_theme(theme).context("Invalid `theme` in config")?;
+        }
         let mut app = Self {
+            config,
             should_quit: false,
-            view_mode: ViewMode::Git,
+            view_mode,
+            color_depth: ColorDepth::detect(),
+            image_preview,
             repo,
             diff_state,
-            collapsed_dirs: HashSet::new(),
+            submodules,
+            ahead_behind,
+            collapsed_dirs: session.collapsed_dirs.into_iter().collect(),
+            forced_text: HashSet::new(),
             selected_tree_idx: 0,
             focused_pane: FocusedPane::FileTree,
             previous_pane: FocusedPane::FileTree,
@@ -306,19 +929,43 @@ impl App {
             diff_scroll_x: 0,
             diff_total_lines: 0,
             diff_view_height: 0,
+            diff_content_width: 0,
+            diff_split_ratio: 0.5,
+            full_width_new_files: true,
             show_help: false,
+            help: HelpState::default(),
+            unified_view: None,
             status_message: None,
             diff_view_mode: DiffViewMode::Scroll,
             cursor_pos: CursorPos { row: 0, col: 0, side: DiffSide::Left },
             visual_anchor: None,
             pending_key: None,
             count: None,
-            highlighter: SyntaxHighlighter::new(),
+            last_yank: None,
+            changes_only: false,
+            relative_numbers: false,
+            show_line_numbers: true,
+            diff_palette,
+            marks: HashMap::new(),
+            marks_file: None,
+            per_file_view: HashMap::new(),
+            jump_list: Vec::new(),
+            jump_idx: 0,
+            highlighter,
             highlight_cache: None,
+            highlight_pending: false,
             content_lines_cache: None,
             bg_highlights: HashMap::new(),
             bg_highlight_rx: None,
-            diff_base_ref: None,
+            signatures: HashMap::new(),
+            signature_rx: None,
+            history_search_rx: None,
+            diff_base_ref: base_ref,
+            compare_refs: None,
+            diff_retry_pending: false,
+            ref_picker: None,
+            diff_scope,
+            diff_base_label,
             branch_list: BranchListState {
                 branches: Vec::new(),
                 selected_idx: 0,
@@ -328,6 +975,9 @@ impl App {
                 selected_idx: 0,
                 view_height: 0,
                 ref_name: String::new(),
+                relative_dates: false,
+                show_email: false,
+                anchor: None,
             },
             reflog: ReflogState {
                 entries: Vec::new(),
@@ -335,13 +985,27 @@ impl App {
                 view_height: 0,
             },
             branch_action_menu: None,
+            reset_menu: None,
+            raw_json_view: None,
+            file_finder: None,
+            base_picker: None,
             error_dialog: None,
+            blame_popup: None,
+            diff_tool_launch: None,
+            blame_cache: HashMap::new(),
+            blame_rx: None,
+            confirm_dialog: None,
             search: SearchState::new(),
-            github: GitHubState::new(),
+            command_line: None,
+            github,
         };
         app.load_branches();
         app.load_reflog();
+        app.restore_focus(session.focused_pane, session.gh_focused_pane);
         app.spawn_bg_highlight();
+        if app.view_mode == ViewMode::GitHub {
+            app.github.initialize();
+        }
         Ok(app)
     }
 
@@ -354,9 +1018,32 @@ impl App {
         }
     }
 
+    /// The selected file as the diff view shows it: without context rows
+    /// while `c` hides them. Cursor rows index into this; anything that needs
+    /// whole hunks (copying, staging) uses `selected_file`.
+    pub fn shown_file(&self) -> Option<Cow<'_, FileDiff>> {
+        let file = self.selected_file()?;
+        Some(match self.changes_only {
+            true => Cow::Owned(file.changes_only()),
+            false => Cow::Borrowed(file),
+        })
+    }
+
+    /// Whether the file exceeds the configured size for syntax highlighting.
+    pub fn is_large_file(&self, file: &FileDiff) -> bool {
+        file.row_count() > self.config.large_file_threshold
+    }
+
     /// Ensure syntax highlighting is available up to `up_to` rows for the given file.
     /// Uses pre-computed background results if available, otherwise falls back to on-demand.
+    /// Large files are left unhighlighted.
     pub fn ensure_file_highlight(&mut self, file: &FileDiff, up_to: usize) {
+        self.highlight_pending = false;
+        if self.is_large_file(file) {
+            self.highlight_cache = None;
+            return;
+        }
+
         let needs_init = self
             .highlight_cache
             .as_ref()
@@ -365,49 +1052,78 @@ impl App {
 
         if needs_init {
             // Check for pre-computed background highlight results first
-            if let Some((lc, rc)) = self.bg_highlights.remove(&file.path) {
+            // Copied, not taken: the unified view reads the same results
+            if let Some((lc, rc)) = self.bg_highlights.get(&file.path).cloned() {
                 self.highlight_cache =
                     Some(HighlightCache::from_precomputed(file.path.clone(), lc, rc));
                 return;
             }
 
             // Fall back to on-demand highlighting
-            let mut left_lines = Vec::new();
-            let mut right_lines = Vec::new();
-            let mut hunk_starts = Vec::new();
-            for hunk in &file.hunks {
-                hunk_starts.push(left_lines.len());
-                left_lines.push(String::new());
-                right_lines.push(String::new());
-                for row in &hunk.rows {
-                    left_lines.push(
-                        row.left.as_ref().map(|s| s.content.clone()).unwrap_or_default(),
-                    );
-                    right_lines.push(
-                        row.right.as_ref().map(|s| s.content.clone()).unwrap_or_default(),
-                    );
-                }
-            }
-            self.highlight_cache =
-                self.highlighter
-                    .create_cache(&file.path, left_lines, right_lines, hunk_starts);
+            let (left_lines, right_lines, hunk_starts) = highlight_lines(file);
+            let sources = self.file_sources(file);
+            self.highlight_cache = self.highlighter.create_cache(
+                &file.path, left_lines, right_lines, hunk_starts, sources,
+            );
         }
 
         if let Some(ref mut cache) = self.highlight_cache {
-            self.highlighter.extend_cache(cache, up_to);
+            self.highlight_pending = !self.highlighter.extend_cache(cache, up_to);
+        }
+    }
+
+    /// Reload a diff whose ref was missing on the last refresh.
+    pub fn retry_refresh_diff(&mut self) -> Result<()> {
+        if self.diff_retry_pending {
+            self.refresh_diff()?;
         }
+        Ok(())
     }
 
     pub fn refresh_diff(&mut self) -> Result<()> {
         let old_path = self.selected_file().map(|f| f.path.clone());
-        match self.repo.diff_workdir(self.diff_base_ref.as_deref()) {
+        let old_hunks = self.selected_file().map(hunk_headers);
+        let base = self.diff_base_ref.clone();
+        let compare = self.compare_refs.clone();
+        let load = |app: &Self| match &compare {
+            Some((old, new)) => app.repo.diff_refs(old, new),
+            None => app.repo.diff_workdir(base.as_deref(), app.diff_scope),
+        };
+        let mut message = None;
+        let retrying = std::mem::take(&mut self.diff_retry_pending);
+        let state = load(self);
+        let refs: Vec<&String> = match &compare {
+            Some((old, new)) => vec![old, new],
+            None => base.iter().collect(),
+        };
+        match state {
             Ok(state) => self.diff_state = state,
-            Err(e) => {
+            Err(e) if refs.is_empty() => return Err(e),
+            Err(e) if !retrying && refs.iter().any(|r| self.repo.ref_is_missing(r)) => {
+                // Refs being rewritten (e.g. during a branch switch) can fail to
+                // resolve for a moment, so retry on the next tick before giving
+                // up on the base
+                self.diff_retry_pending = true;
+                self.status_message = Some(format!("Diff error: {e}"));
+                return Ok(());
+            }
+            Err(e) if refs.iter().any(|r| self.repo.ref_is_invalid(r)) => {
+                self.compare_refs = None;
                 self.diff_base_ref = None;
-                self.diff_state = self.repo.diff_workdir(None)?;
-                self.status_message = Some(format!("Invalid ref, fell back to HEAD: {e}"));
+                self.diff_base_label = None;
+                self.diff_state = self.repo.diff_workdir(None, self.diff_scope)?;
+                message = Some(format!("Invalid ref, fell back to HEAD: {e}"));
+            }
+            Err(e) => {
+                // Keep the base and the current diff; the next refresh retries
+                self.status_message = Some(format!("Diff error: {e}"));
+                return Ok(());
             }
         }
+        self.apply_forced_text();
+        self.submodules = self.repo.submodule_status();
+        self.ahead_behind = self.repo.upstream_ahead_behind();
+        self.image_preview.generation += 1;
         // Preserve selection by path
         if let Some(path) = old_path {
             let entries = self.build_tree_entries();
@@ -422,56 +1138,298 @@ impl App {
         }
         self.diff_scroll_y = 0;
         self.diff_scroll_x = 0;
-        self.status_message = None;
+        self.status_message = message;
         self.highlight_cache = None;
         self.content_lines_cache = None;
+        self.blame_cache.clear();
+        self.blame_rx = None;
         self.bg_highlights.clear();
         self.bg_highlight_rx = None; // Drop old receiver, stops old thread
         self.search.reset_matches();
+        if self.diff_state.files.is_empty() {
+            self.unified_view = None;
+        }
+        if self.unified_view.is_some() {
+            let lines = self.unified_lines();
+            if let Some(view) = self.unified_view.as_mut() {
+                view.cursor = view.cursor.min(lines.len().saturating_sub(1));
+                view.anchor = None;
+                view.lines = lines;
+            }
+        }
+        let files = &self.diff_state.files;
+        self.per_file_view
+            .retain(|path, _| files.iter().any(|f| &f.path == path));
+        // Marks only survive while the selected file's hunks are unchanged
+        if self.selected_file().map(hunk_headers) != old_hunks {
+            self.marks.clear();
+            self.marks_file = None;
+        }
         self.spawn_bg_highlight();
         Ok(())
     }
 
-    /// Spawn a background thread to pre-highlight all files.
-    fn spawn_bg_highlight(&mut self) {
-        let mut file_data: Vec<(String, Vec<String>, Vec<String>, Vec<usize>)> = Vec::new();
-        for file in &self.diff_state.files {
+    /// `T` — show the selected binary file as a line diff anyway, or back as
+    /// binary. For files git only took for binary, e.g. over a stray NUL.
+    fn toggle_forced_text(&mut self) {
+        let Some((path, is_binary)) = self.selected_file().map(|f| (f.path.clone(), f.is_binary)) else {
+            return;
+        };
+        let forced = !self.forced_text.remove(&path);
+        if forced {
+            if !is_binary {
+                self.status_message = Some("Not a binary file".to_string());
+                return;
+            }
+            self.forced_text.insert(path.clone());
+        }
+        if let Err(e) = self.refresh_diff() {
+            self.status_message = Some(format!("Refresh error: {e}"));
+            return;
+        }
+        self.status_message = Some(if forced {
+            format!("Showing {path} as text; it may not be meant to be read this way")
+        } else {
+            format!("Showing {path} as binary")
+        });
+    }
+
+    /// Replace the binary placeholder of files in `forced_text` with a line
+    /// diff of their contents.
+    fn apply_forced_text(&mut self) {
+        if self.forced_text.is_empty() {
+            return;
+        }
+        let ignore_whitespace = self.repo.ignores_whitespace();
+        // A file that still can't be diffed stays binary
+        let forced: Vec<(usize, Vec<DiffHunk>)> = self
+            .diff_state
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.is_binary && self.forced_text.contains(&f.path))
+            .filter_map(|(idx, f)| {
+                let (old, new) = self.file_bytes(f);
+                let old = old.unwrap_or_default();
+                let new = new.unwrap_or_default();
+                Some((idx, forced_text_hunks(&old, &new, &f.raw_path, ignore_whitespace).ok()?))
+            })
+            .collect();
+        if forced.is_empty() {
+            return;
+        }
+        for (idx, hunks) in forced {
+            let file = &mut self.diff_state.files[idx];
+            file.hunks = hunks;
+            file.is_binary = false;
+        }
+        self.diff_state.stats = compute_stats(&self.diff_state.files);
+    }
+
+    /// What the two sides of the current diff show.
+    fn diff_sides(&self) -> DiffSides {
+        DiffSides {
+            compare_refs: self.compare_refs.clone(),
+            base_ref: self.diff_base_ref.clone(),
+            scope: self.diff_scope,
+        }
+    }
+
+    /// Full old/new contents of a file on each side of the current diff.
+    pub fn file_bytes(&self, file: &FileDiff) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+        self.diff_sides().file_bytes(&self.repo, file)
+    }
+
+    /// `E` — prepare the configured `diff_tool` for the selected file. The old
+    /// side is always a temp copy; the new side is the working-tree file when
+    /// the diff shows it, so edits made in the tool are kept.
+    fn request_diff_tool(&mut self) {
+        let Some(template) = self.config.diff_tool.clone() else {
+            self.status_message = Some("Set diff_tool in the config to use E".to_string());
+            return;
+        };
+        if template.trim().is_empty() {
+            self.status_message = Some("diff_tool is empty".to_string());
+            return;
+        }
+        let Some(file) = self.selected_file().cloned() else {
+            return;
+        };
+        let (left, right) = self.file_bytes(&file);
+        let name = file
+            .raw_path
+            .file_name()
+            .map_or_else(|| "file".into(), |n| n.to_string_lossy().into_owned());
+        let temp_dir = match private_temp_dir() {
+            Ok(dir) => dir,
+            Err(e) => {
+                self.status_message = Some(format!("Failed to create temp dir: {e}"));
+                return;
+            }
+        };
+        let write_temp = |side: &str, bytes: Option<Vec<u8>>| -> std::io::Result<PathBuf> {
+            let path = temp_dir.join(format!("{side}-{name}"));
+            // A side the file doesn't exist on is passed as an empty file
+            std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)?
+                .write_all(&bytes.unwrap_or_default())?;
+            Ok(path)
+        };
+        let shows_worktree = self.compare_refs.is_none()
+            && self.diff_scope != DiffScope::Staged
+            && file.status != FileStatus::Deleted;
+        let paths = write_temp("old", left).and_then(|old| {
+            let new = if shows_worktree {
+                self.repo.workdir().join(&file.raw_path)
+            } else {
+                write_temp("new", right)?
+            };
+            Ok((old, new))
+        });
+        let (old, new) = match paths {
+            Ok(paths) => paths,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&temp_dir);
+                self.status_message = Some(format!("Failed to write temp file: {e}"));
+                return;
+            }
+        };
+
+        // Placeholders are substituted per word, so paths with spaces stay whole
+        let mut args: Vec<OsString> = template.split_whitespace().map(|word| {
+            let mut arg = OsString::new();
+            let mut rest = word;
+            while let Some(start) = rest.find('{') {
+                let Some(len) = rest[start..].find('}') else {
+                    break;
+                };
+                arg.push(&rest[..start]);
+                match &rest[start..=start + len] {
+                    "{old}" => arg.push(&old),
+                    "{new}" => arg.push(&new),
+                    "{path}" => arg.push(&file.raw_path),
+                    other => arg.push(other),
+                }
+                rest = &rest[start + len + 1..];
+            }
+            arg.push(rest);
+            arg
+        }).collect();
+        let program = args.remove(0).to_string_lossy().into_owned();
+        self.diff_tool_launch = Some(DiffToolLaunch { program, args, temp_dir });
+    }
+
+    /// Full old/new text of a file, used to seed highlighting at hunk starts.
+    fn file_sources(&self, file: &FileDiff) -> FileSources {
+        self.diff_sides().file_sources(&self.repo, file)
+    }
+
+    /// Per-row syntax colors of both sides of `file` from the background
+    /// results. None until they are in, and for large files and unrecognized
+    /// file types.
+    fn file_syntax_colors(&self, file: &FileDiff) -> Option<SideColors> {
+        self.bg_highlights.get(&file.path).cloned()
+    }
+
+    /// Every file's unified diff, file headers included, one after another.
+    /// Rows keep the syntax colors of their file, once highlighted.
+    fn unified_lines(&self) -> Vec<UnifiedLine> {
+        use UnifiedLineKind::*;
+        let line = |kind, text: String, colors: Vec<Color>| UnifiedLine { kind, text, colors };
+        let mut out = Vec::new();
+        for (idx, file) in self.diff_state.files.iter().enumerate() {
+            if idx > 0 {
+                out.push(line(Note, String::new(), Vec::new()));
+            }
+            let old = file.old_path.as_deref().unwrap_or(&file.path);
+            out.push(line(FileHeader, format!("--- a/{old}"), Vec::new()));
+            out.push(line(FileHeader, format!("+++ b/{}", file.path), Vec::new()));
             if file.is_binary {
+                let text = format!("Binary files a/{old} and b/{} differ", file.path);
+                out.push(line(Note, text, Vec::new()));
                 continue;
             }
-            let mut left_lines = Vec::new();
-            let mut right_lines = Vec::new();
-            let mut hunk_starts = Vec::new();
+            let (left_colors, right_colors) = self.file_syntax_colors(file).unwrap_or_default();
+            let colors = |side: &[Vec<Color>], row: usize| side.get(row).cloned().unwrap_or_default();
+            let mut row_idx = 0;
             for hunk in &file.hunks {
-                hunk_starts.push(left_lines.len());
-                left_lines.push(String::new());
-                right_lines.push(String::new());
+                out.push(line(HunkHeader, hunk.header.clone(), Vec::new()));
+                row_idx += 1;
+                // Deletions before additions, as in `DiffHunk::to_unified_lines`
+                let mut added = Vec::new();
                 for row in &hunk.rows {
-                    left_lines.push(
-                        row.left.as_ref().map(|s| s.content.clone()).unwrap_or_default(),
-                    );
-                    right_lines.push(
-                        row.right.as_ref().map(|s| s.content.clone()).unwrap_or_default(),
-                    );
+                    if row.line_type == LineType::Context {
+                        out.append(&mut added);
+                        if let Some(ref l) = row.left {
+                            out.push(line(Context, format!(" {}", l.content), colors(&left_colors, row_idx)));
+                        }
+                    } else {
+                        if let Some(ref l) = row.left {
+                            out.push(line(Deleted, format!("-{}", l.content), colors(&left_colors, row_idx)));
+                        }
+                        if let Some(ref r) = row.right {
+                            added.push(line(Added, format!("+{}", r.content), colors(&right_colors, row_idx)));
+                        }
+                    }
+                    row_idx += 1;
                 }
+                out.append(&mut added);
             }
-            file_data.push((file.path.clone(), left_lines, right_lines, hunk_starts));
         }
+        out
+    }
+
+    /// `U` — show every file's diff in one scrollable buffer.
+    fn open_unified_view(&mut self) {
+        if self.diff_state.files.is_empty() {
+            self.status_message = Some("No changes".to_string());
+            return;
+        }
+        self.unified_view = Some(UnifiedViewState {
+            lines: self.unified_lines(),
+            ..Default::default()
+        });
+    }
+
+    /// Spawn a background thread to pre-highlight all files. File contents
+    /// for seeding hunk starts are read there too, with its own `Repo`.
+    fn spawn_bg_highlight(&mut self) {
+        let files: Vec<FileDiff> = self
+            .diff_state
+            .files
+            .iter()
+            .filter(|file| !file.is_binary && !self.is_large_file(file))
+            .cloned()
+            .collect();
 
-        if file_data.is_empty() {
+        if files.is_empty() {
             return;
         }
 
         let (tx, rx) = mpsc::channel();
         self.bg_highlight_rx = Some(rx);
+        let theme = self.highlighter.theme_name().to_string();
+        let sides = self.diff_sides();
+        let workdir = self.repo.workdir().to_path_buf();
 
         std::thread::spawn(move || {
-            let highlighter = SyntaxHighlighter::new();
-            for (path, left_lines, right_lines, hunk_starts) in file_data {
+            let repo = Repo::discover(&workdir).ok();
+            let mut highlighter = SyntaxHighlighter::new();
+            if highlighter.theme_name() != theme {
+                let _ = highlighter.set_theme(&theme);
+            }
+            for file in files {
+                let (left_lines, right_lines, hunk_starts) = highlight_lines(&file);
+                let sources = match &repo {
+                    Some(repo) => sides.file_sources(repo, &file),
+                    None => FileSources::default(),
+                };
                 if let Some((lc, rc)) = highlighter.highlight_all_lines(
-                    &path, &left_lines, &right_lines, &hunk_starts,
+                    &file.path, &left_lines, &right_lines, &hunk_starts, sources,
                 ) {
-                    if tx.send((path, lc, rc)).is_err() {
+                    if tx.send((file.path, lc, rc)).is_err() {
                         break; // Receiver dropped
                     }
                 }
@@ -479,45 +1437,346 @@ impl App {
         });
     }
 
-    /// Drain completed background highlight results into the local cache.
+    /// Drain completed background highlight results into the local cache,
+    /// and color an open unified view with them.
     pub fn drain_bg_highlights(&mut self) {
+        let mut received = false;
         if let Some(ref rx) = self.bg_highlight_rx {
             while let Ok((path, left, right)) = rx.try_recv() {
                 self.bg_highlights.insert(path, (left, right));
+                received = true;
             }
         }
-    }
-
-    pub fn load_branches(&mut self) {
-        self.branch_list.branches = self.repo.list_local_branches();
-        if self.branch_list.selected_idx >= self.branch_list.branches.len() {
-            self.branch_list.selected_idx = 0;
+        if received && self.unified_view.is_some() {
+            let lines = self.unified_lines();
+            if let Some(view) = self.unified_view.as_mut() {
+                view.lines = lines;
+            }
         }
-        self.update_branch_log();
-    }
-
-    fn set_focus(&mut self, pane: FocusedPane) {
-        self.previous_pane = self.focused_pane;
-        self.focused_pane = pane;
     }
 
-    pub fn update_branch_log(&mut self) {
-        if let Some(branch) = self
-            .branch_list
-            .branches
+    /// Collect finished signature checks, then check the commits around the
+    /// Git Log selection and the one in the blame popup on a background
+    /// thread. Verifying can take a while per commit, so only the commits in
+    /// view are checked, one batch at a time.
+    pub fn update_signatures(&mut self) {
+        if let Some(rx) = &self.signature_rx {
+            loop {
+                match rx.try_recv() {
+                    Ok((hash, status)) => {
+                        self.signatures.insert(hash, Some(status));
+                    }
+                    Err(mpsc::TryRecvError::Empty) => return,
+                    Err(mpsc::TryRecvError::Disconnected) => break,
+                }
+            }
+            self.signature_rx = None;
+        }
+        let log_shown = self.view_mode == ViewMode::Git
+            && matches!(
+                self.focused_pane,
+                FocusedPane::BranchList | FocusedPane::GitLog | FocusedPane::Reflog
+            );
+        let around: Vec<&CommitInfo> = if log_shown {
+            // Covers every row on screen, wherever the table scrolled to
+            let height = self.git_log.view_height as usize;
+            let start = self.git_log.selected_idx.saturating_sub(height);
+            let end = self.git_log.selected_idx + height + 1;
+            self.log_commits().take(end).skip(start).collect()
+        } else {
+            Vec::new()
+        };
+        let blamed = self.blame_popup.as_ref().and_then(|popup| popup.commit.as_ref());
+        let mut hashes: Vec<String> = around
+            .into_iter()
+            .chain(blamed)
+            .map(|commit| commit.full_hash.clone())
+            .filter(|hash| !self.signatures.contains_key(hash))
+            .collect();
+        hashes.dedup();
+        if hashes.is_empty() {
+            return;
+        }
+        for hash in &hashes {
+            self.signatures.insert(hash.clone(), None);
+        }
+        let (tx, rx) = mpsc::channel();
+        self.signature_rx = Some(rx);
+        let workdir = self.repo.workdir().to_path_buf();
+        std::thread::spawn(move || {
+            let Ok(repo) = Repo::discover(&workdir) else {
+                return;
+            };
+            for hash in hashes {
+                let status = repo.signature_status(&hash);
+                if tx.send((hash, status)).is_err() {
+                    break; // Receiver dropped
+                }
+            }
+        });
+    }
+
+    /// Signature check result for `hash`, if it's in.
+    pub fn signature_of(&self, hash: &str) -> Option<SignatureStatus> {
+        self.signatures.get(hash).copied().flatten()
+    }
+
+    pub fn load_branches(&mut self) {
+        self.branch_list.branches = self.repo.list_local_branches();
+        if self.branch_list.selected_idx >= self.branch_list.branches.len() {
+            self.branch_list.selected_idx = 0;
+        }
+        self.update_branch_log();
+    }
+
+    /// Whether the diff shows only the new side: the selected file has no old
+    /// content and the split isn't forced with `|`.
+    pub fn single_column(&self) -> bool {
+        self.full_width_new_files
+            && self
+                .selected_file()
+                .is_some_and(|f| matches!(f.status, FileStatus::Added | FileStatus::Untracked))
+    }
+
+    /// `|` — switch between the full-width and split layout for new files.
+    fn toggle_full_width_new_files(&mut self) {
+        self.full_width_new_files = !self.full_width_new_files;
+        self.pin_cursor_side();
+        self.status_message = Some(
+            if self.full_width_new_files {
+                "New files use the full width"
+            } else {
+                "New files keep the side-by-side split"
+            }
+            .to_string(),
+        );
+    }
+
+    /// Keep the cursor off the hidden old side.
+    fn pin_cursor_side(&mut self) {
+        if self.single_column() {
+            self.cursor_pos.side = DiffSide::Right;
+            if let Some(anchor) = self.visual_anchor.as_mut() {
+                anchor.side = DiffSide::Right;
+            }
+        }
+    }
+
+    /// Move the divider between the old and new side by `steps` × 5% of the width.
+    fn shift_diff_split(&mut self, steps: f32) {
+        self.diff_split_ratio = (self.diff_split_ratio + steps * 0.05).clamp(0.1, 0.9);
+        self.status_message = Some(format!(
+            "Split {:.0}% / {:.0}%",
+            self.diff_split_ratio * 100.0,
+            (1.0 - self.diff_split_ratio) * 100.0
+        ));
+    }
+
+    fn toggle_relative_numbers(&mut self) {
+        self.relative_numbers = !self.relative_numbers;
+        self.status_message = Some(
+            if self.relative_numbers { "Relative line numbers" } else { "Absolute line numbers" }
+                .to_string(),
+        );
+    }
+
+    fn toggle_line_numbers(&mut self) {
+        self.show_line_numbers = !self.show_line_numbers;
+        self.status_message = Some(
+            if self.show_line_numbers { "Line numbers shown" } else { "Line numbers hidden" }
+                .to_string(),
+        );
+    }
+
+    /// `P` — switch to the next diff color palette.
+    fn cycle_diff_palette(&mut self) {
+        self.diff_palette = self.diff_palette.next();
+        self.status_message = Some(format!("Diff colors: {}", self.diff_palette.name()));
+    }
+
+    /// `O` — open the selected file with the system's default application
+    /// (images, PDFs and other files the diff can't show).
+    fn open_in_default_app(&mut self) {
+        let path = match self.selected_file() {
+            Some(file) => file.worktree_path().map(|p| self.repo.workdir().join(p)),
+            None => return,
+        };
+        let Some(path) = path else {
+            self.status_message = Some("File was deleted".to_string());
+            return;
+        };
+        self.status_message = Some(match crate::github::client::open_path(&path) {
+            Ok(()) => format!("Opening {}...", path.display()),
+            Err(e) => e,
+        });
+    }
+
+    /// `s` — cycle the diff between all, unstaged and staged changes.
+    fn cycle_diff_scope(&mut self) {
+        if self.working_tree_only() {
+            return;
+        }
+        self.diff_scope = self.diff_scope.next();
+        // Row indices change, so remembered positions no longer apply
+        self.per_file_view.clear();
+        if let Err(e) = self.refresh_diff() {
+            self.status_message = Some(format!("Refresh error: {e}"));
+            return;
+        }
+        self.cursor_pos.row = 0;
+        self.cursor_pos.col = 0;
+        self.re_search_on_file_change();
+        self.status_message = Some(format!("Showing {} changes", self.diff_scope.label()));
+    }
+
+    /// Toggle between the full diff and added/deleted rows only.
+    fn toggle_changes_only(&mut self) {
+        self.changes_only = !self.changes_only;
+        // Row indices change, so remembered positions no longer apply
+        self.per_file_view.clear();
+        self.marks.clear();
+        self.content_lines_cache = None;
+        self.diff_scroll_y = 0;
+        self.cursor_pos.row = 0;
+        self.cursor_pos.col = 0;
+        self.re_search_on_file_change();
+        self.status_message = Some(
+            if self.changes_only { "Showing changes only" } else { "Showing full context" }
+                .to_string(),
+        );
+    }
+
+    /// Move the tree selection to the next / previous file, wrapping at the ends.
+    fn select_adjacent_file(&mut self, forward: bool) {
+        let entries = self.build_tree_entries();
+        let files: Vec<usize> = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| matches!(e, TreeEntry::File { .. }))
+            .map(|(i, _)| i)
+            .collect();
+        if files.is_empty() {
+            return;
+        }
+        let cur = self.selected_tree_idx;
+        let (next, wrapped) = if forward {
+            match files.iter().find(|&&i| i > cur) {
+                Some(&i) => (i, false),
+                None => (files[0], true),
+            }
+        } else {
+            match files.iter().rev().find(|&&i| i < cur) {
+                Some(&i) => (i, false),
+                None => (files[files.len() - 1], true),
+            }
+        };
+        self.save_file_view();
+        self.selected_tree_idx = next;
+        self.restore_file_view();
+        self.re_search_on_file_change();
+        if wrapped {
+            self.status_message = Some(
+                if forward { "Wrapped to first file" } else { "Wrapped to last file" }.to_string(),
+            );
+        }
+    }
+
+    /// Remember the diff view position of the selected file.
+    fn save_file_view(&mut self) {
+        if let Some(path) = self.selected_file().map(|f| f.path.clone()) {
+            self.per_file_view
+                .insert(path, (self.diff_scroll_y, self.diff_scroll_x, self.cursor_pos));
+        }
+    }
+
+    /// Restore the remembered position of the selected file, or start at the top.
+    fn restore_file_view(&mut self) {
+        let saved = self.shown_file().and_then(|f| {
+            let (scroll_y, scroll_x, cursor) = *self.per_file_view.get(&f.path)?;
+            let rows = f.row_count();
+            (cursor.row < rows && (scroll_y as usize) < rows).then_some((scroll_y, scroll_x, cursor))
+        });
+        let (scroll_y, scroll_x, cursor) =
+            saved.unwrap_or((0, 0, CursorPos { row: 0, col: 0, side: DiffSide::Left }));
+        self.diff_scroll_y = scroll_y;
+        self.diff_scroll_x = scroll_x;
+        self.cursor_pos = cursor;
+        self.content_lines_cache = None;
+        self.pin_cursor_side();
+    }
+
+    /// `Tab` / `BackTab` — move to the next / previous pane, skipping lists
+    /// with nothing in them. The file tree and diff view are always reachable.
+    fn cycle_pane(&mut self, forward: bool) {
+        let mut pane = self.focused_pane;
+        loop {
+            pane = if forward {
+                match pane {
+                    FocusedPane::FileTree => FocusedPane::BranchList,
+                    FocusedPane::BranchList => FocusedPane::Reflog,
+                    FocusedPane::Reflog => FocusedPane::GitLog,
+                    FocusedPane::GitLog => FocusedPane::DiffView,
+                    FocusedPane::DiffView => FocusedPane::FileTree,
+                }
+            } else {
+                match pane {
+                    FocusedPane::FileTree => FocusedPane::DiffView,
+                    FocusedPane::BranchList => FocusedPane::FileTree,
+                    FocusedPane::Reflog => FocusedPane::BranchList,
+                    FocusedPane::GitLog => FocusedPane::Reflog,
+                    FocusedPane::DiffView => FocusedPane::GitLog,
+                }
+            };
+            let empty = match pane {
+                FocusedPane::BranchList => self.branch_list.branches.is_empty(),
+                FocusedPane::Reflog => self.reflog.entries.is_empty(),
+                FocusedPane::GitLog => self.git_log.commits.is_empty(),
+                FocusedPane::FileTree | FocusedPane::DiffView => false,
+            };
+            if !empty {
+                break;
+            }
+        }
+        self.set_focus(pane);
+    }
+
+    fn set_focus(&mut self, pane: FocusedPane) {
+        self.count = None;
+        self.previous_pane = self.focused_pane;
+        self.focused_pane = pane;
+    }
+
+    pub fn update_branch_log(&mut self) {
+        if let Some(branch) = self
+            .branch_list
+            .branches
             .get(self.branch_list.selected_idx)
         {
             self.git_log.ref_name = branch.name.clone();
-            self.git_log.commits = self.repo.log_for_ref(&branch.name, 100);
+            self.git_log.commits = self.repo.log_for_ref(&branch.name, self.config.log_limit);
             self.git_log.selected_idx = 0;
+            self.git_log.anchor = None;
         } else {
             self.git_log.commits.clear();
             self.git_log.ref_name.clear();
         }
     }
 
+    /// Reload what depends on refs: the branch list, its log and the reflog.
+    pub fn refresh_refs(&mut self) {
+        self.load_branches();
+        self.load_reflog();
+    }
+
+    /// Reload the diff and everything that depends on refs.
+    pub fn refresh_all(&mut self) -> Result<()> {
+        self.refresh_diff()?;
+        self.refresh_refs();
+        Ok(())
+    }
+
     pub fn load_reflog(&mut self) {
-        self.reflog.entries = self.repo.reflog(500);
+        self.reflog.entries = self.repo.reflog(self.config.reflog_limit);
         if self.reflog.selected_idx >= self.reflog.entries.len() {
             self.reflog.selected_idx = 0;
         }
@@ -531,9 +1790,12 @@ impl App {
         {
             if branch.is_head {
                 self.diff_base_ref = None;
+                self.diff_base_label = None;
             } else {
                 self.diff_base_ref = Some(branch.name.clone());
+                self.diff_base_label = Some(format!("{} (tip)", branch.name));
             }
+            self.compare_refs = None;
             if let Err(e) = self.refresh_diff() {
                 self.status_message = Some(format!("Diff error: {e}"));
             }
@@ -554,8 +1816,10 @@ impl App {
             KeyCode::Esc => {
                 if self.search.query.is_some() {
                     self.search.clear();
-                } else if self.diff_base_ref.is_some() {
+                } else if self.diff_base_ref.is_some() || self.compare_refs.is_some() {
                     self.diff_base_ref = None;
+                    self.diff_base_label = None;
+                    self.compare_refs = None;
                     if let Err(e) = self.refresh_diff() {
                         self.status_message = Some(format!("Diff error: {e}"));
                     }
@@ -592,6 +1856,11 @@ impl App {
     }
 
     fn handle_git_log_key(&mut self, key: KeyEvent) {
+        if self.accumulate_count(key.code) {
+            return;
+        }
+        let n = self.take_count();
+        self.clamp_log_selection();
         match key.code {
             KeyCode::Char('h') => {
                 self.set_focus(FocusedPane::Reflog);
@@ -599,48 +1868,55 @@ impl App {
             KeyCode::Esc => {
                 if self.search.query.is_some() {
                     self.search.clear();
+                } else if self.git_log.anchor.is_some() {
+                    self.git_log.anchor = None;
                 } else {
                     self.set_focus(self.previous_pane);
                 }
             }
             KeyCode::Char('j') | KeyCode::Down => {
-                if !self.git_log.commits.is_empty()
-                    && self.git_log.selected_idx + 1 < self.git_log.commits.len()
-                {
-                    self.git_log.selected_idx += 1;
-                }
+                let last = self.log_len().saturating_sub(1);
+                self.git_log.selected_idx = (self.git_log.selected_idx + n).min(last);
             }
             KeyCode::Char('k') | KeyCode::Up => {
-                if self.git_log.selected_idx > 0 {
-                    self.git_log.selected_idx -= 1;
-                }
+                self.git_log.selected_idx = self.git_log.selected_idx.saturating_sub(n);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let half = (self.git_log.view_height / 2).max(1) as usize;
+                let half = (self.git_log.view_height / 2).max(1) as usize * n;
                 let new_idx = self.git_log.selected_idx.saturating_add(half);
                 self.git_log.selected_idx =
-                    new_idx.min(self.git_log.commits.len().saturating_sub(1));
+                    new_idx.min(self.log_len().saturating_sub(1));
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let half = (self.git_log.view_height / 2).max(1) as usize;
+                let half = (self.git_log.view_height / 2).max(1) as usize * n;
                 self.git_log.selected_idx = self.git_log.selected_idx.saturating_sub(half);
             }
             KeyCode::Char('g') => {
                 self.git_log.selected_idx = 0;
             }
             KeyCode::Char('G') => {
-                if !self.git_log.commits.is_empty() {
-                    self.git_log.selected_idx = self.git_log.commits.len() - 1;
-                }
+                self.git_log.selected_idx = self.log_len().saturating_sub(1);
+            }
+            KeyCode::Char('v') => {
+                self.git_log.anchor = match self.git_log.anchor {
+                    Some(_) => None,
+                    None => (self.log_len() > 0).then_some(self.git_log.selected_idx),
+                };
+            }
+            KeyCode::Char('y') if self.git_log.anchor.is_some() => {
+                self.yank_commit_range(false);
             }
             KeyCode::Char('y') => {
-                if let Some(commit) = self.git_log.commits.get(self.git_log.selected_idx) {
+                if let Some(commit) = self.log_commit(self.git_log.selected_idx) {
                     let hash = commit.full_hash.clone();
                     self.copy_to_clipboard(&hash);
                 }
             }
+            KeyCode::Char('Y') => {
+                self.yank_commit_range(true);
+            }
             KeyCode::Char('o') => {
-                if let Some(commit) = self.git_log.commits.get(self.git_log.selected_idx) {
+                if let Some(commit) = self.log_commit(self.git_log.selected_idx) {
                     let hash = commit.full_hash.clone();
                     if let Some(nwo) = crate::github::client::repo_nwo() {
                         let url = format!("https://github.com/{nwo}/commit/{hash}");
@@ -660,6 +1936,26 @@ impl App {
                     }
                 }
             }
+            KeyCode::Char('t') => {
+                self.git_log.relative_dates = !self.git_log.relative_dates;
+                self.status_message = Some(
+                    if self.git_log.relative_dates { "Relative dates" } else { "Absolute dates" }
+                        .to_string(),
+                );
+            }
+            KeyCode::Char('a') => {
+                self.git_log.show_email = !self.git_log.show_email;
+                self.status_message = Some(
+                    if self.git_log.show_email { "Author emails shown" } else { "Author emails hidden" }
+                        .to_string(),
+                );
+            }
+            KeyCode::Char('R') => {
+                if let Some(commit) = self.log_commit(self.git_log.selected_idx) {
+                    let (full_hash, short_hash) = (commit.full_hash.clone(), commit.short_hash.clone());
+                    self.open_reset_menu(full_hash, short_hash);
+                }
+            }
             KeyCode::Char('/') => {
                 self.search.start(SearchOrigin::CommitLog);
             }
@@ -673,7 +1969,42 @@ impl App {
         }
     }
 
+    /// `y` / `Y` over a range marked with `v` — copy it as `oldest^..newest`,
+    /// or as its hashes oldest first (the order `git cherry-pick` wants).
+    /// `Y` without a range copies just the selected hash.
+    fn yank_commit_range(&mut self, hashes: bool) {
+        let (first, last) = self
+            .git_log
+            .range()
+            .unwrap_or((self.git_log.selected_idx, self.git_log.selected_idx));
+        let commits: Vec<&CommitInfo> = self.log_commits().take(last + 1).skip(first).collect();
+        if commits.is_empty() {
+            return;
+        }
+        let count = commits.len();
+        let text = if hashes {
+            commits.iter().rev().map(|c| c.full_hash.as_str()).collect::<Vec<_>>().join(" ")
+        } else {
+            let (newest, oldest) = (&commits[0].full_hash, &commits[count - 1].full_hash);
+            // A range reaching the root commit has no `^` to start from
+            if self.repo.ref_is_invalid(&format!("{oldest}^")) {
+                newest.clone()
+            } else {
+                format!("{oldest}^..{newest}")
+            }
+        };
+        let what = if hashes { "hashes" } else { "range" };
+        self.copy_with_message(
+            &text,
+            format!("Copied {what} of {count} commit{}", if count == 1 { "" } else { "s" }),
+        );
+    }
+
     fn handle_reflog_key(&mut self, key: KeyEvent) {
+        if self.accumulate_count(key.code) {
+            return;
+        }
+        let n = self.take_count();
         match key.code {
             KeyCode::Char('h') => {
                 self.set_focus(FocusedPane::BranchList);
@@ -689,25 +2020,20 @@ impl App {
                 }
             }
             KeyCode::Char('j') | KeyCode::Down => {
-                if !self.reflog.entries.is_empty()
-                    && self.reflog.selected_idx + 1 < self.reflog.entries.len()
-                {
-                    self.reflog.selected_idx += 1;
-                }
+                let last = self.reflog.entries.len().saturating_sub(1);
+                self.reflog.selected_idx = (self.reflog.selected_idx + n).min(last);
             }
             KeyCode::Char('k') | KeyCode::Up => {
-                if self.reflog.selected_idx > 0 {
-                    self.reflog.selected_idx -= 1;
-                }
+                self.reflog.selected_idx = self.reflog.selected_idx.saturating_sub(n);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let half = (self.reflog.view_height / 2).max(1) as usize;
+                let half = (self.reflog.view_height / 2).max(1) as usize * n;
                 let new_idx = self.reflog.selected_idx.saturating_add(half);
                 self.reflog.selected_idx =
                     new_idx.min(self.reflog.entries.len().saturating_sub(1));
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let half = (self.reflog.view_height / 2).max(1) as usize;
+                let half = (self.reflog.view_height / 2).max(1) as usize * n;
                 self.reflog.selected_idx = self.reflog.selected_idx.saturating_sub(half);
             }
             KeyCode::Char('g') => {
@@ -721,11 +2047,18 @@ impl App {
             KeyCode::Enter => {
                 if let Some(entry) = self.reflog.entries.get(self.reflog.selected_idx) {
                     self.diff_base_ref = Some(entry.full_hash.clone());
+                    self.diff_base_label = Some(self.repo.describe_ref(&entry.full_hash));
+                    self.compare_refs = None;
                     if let Err(e) = self.refresh_diff() {
                         self.status_message = Some(format!("Diff error: {e}"));
                     }
                 }
             }
+            KeyCode::Char('R') => {
+                if let Some(entry) = self.reflog.entries.get(self.reflog.selected_idx) {
+                    self.open_reset_menu(entry.full_hash.clone(), entry.short_hash.clone());
+                }
+            }
             KeyCode::Char('/') => {
                 self.search.start(SearchOrigin::Reflog);
             }
@@ -782,10 +2115,79 @@ impl App {
             KeyCode::Char('b') => {
                 self.execute_branch_action(BranchAction::DiffBase);
             }
+            KeyCode::Char('m') => {
+                self.execute_branch_action(BranchAction::MergeBase);
+            }
             _ => {}
         }
     }
 
+    fn open_reset_menu(&mut self, full_hash: String, short_hash: String) {
+        self.reset_menu = Some(ResetMenuState { full_hash, short_hash, selected_idx: 0 });
+    }
+
+    fn handle_reset_menu_key(&mut self, key: KeyEvent) {
+        let menu = match self.reset_menu.as_mut() {
+            Some(m) => m,
+            None => return,
+        };
+
+        let mode = match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.reset_menu = None;
+                return;
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                if menu.selected_idx + 1 < ResetMode::ALL.len() {
+                    menu.selected_idx += 1;
+                }
+                return;
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                menu.selected_idx = menu.selected_idx.saturating_sub(1);
+                return;
+            }
+            KeyCode::Enter => ResetMode::ALL[menu.selected_idx],
+            KeyCode::Char(c) => match ResetMode::ALL.into_iter().find(|m| m.key() == c) {
+                Some(mode) => mode,
+                None => return,
+            },
+            _ => return,
+        };
+        let Some(menu) = self.reset_menu.take() else {
+            return;
+        };
+        let branch = &self.diff_state.branch_name;
+        let message = format!("Reset {branch} to {}?", menu.short_hash);
+        self.confirm_dialog = Some(ConfirmDialogState {
+            title: match mode {
+                ResetMode::Soft => "Reset --soft?",
+                ResetMode::Mixed => "Reset --mixed?",
+            }
+            .to_string(),
+            message,
+            action: ConfirmAction::Reset { hash: menu.full_hash, mode },
+        });
+    }
+
+    /// Reset HEAD and reload everything that depends on it.
+    fn reset_to(&mut self, hash: &str, mode: ResetMode) {
+        if let Err(e) = self.repo.reset(hash, mode) {
+            self.error_dialog = Some(ErrorDialogState {
+                title: "Reset failed".to_string(),
+                message: format!("{e:#}"),
+            });
+            return;
+        }
+        self.load_branches();
+        self.load_reflog();
+        if let Err(e) = self.refresh_diff() {
+            self.status_message = Some(format!("Diff error: {e}"));
+            return;
+        }
+        self.status_message = Some(format!("Reset to {}", &hash[..hash.len().min(7)]));
+    }
+
     fn execute_branch_action(&mut self, action: BranchAction) {
         let menu = match self.branch_action_menu.take() {
             Some(m) => m,
@@ -838,6 +2240,30 @@ impl App {
             BranchAction::DiffBase => {
                 self.select_branch();
             }
+            BranchAction::MergeBase => {
+                if menu.is_head {
+                    self.status_message =
+                        Some("Merge-base with the current branch is HEAD".to_string());
+                    return;
+                }
+                match self.repo.merge_base_with_head(&menu.branch_name) {
+                    Ok(oid) => {
+                        self.diff_base_ref = Some(oid);
+                        self.diff_base_label =
+                            Some(format!("{} (merge-base)", menu.branch_name));
+                        self.compare_refs = None;
+                        if let Err(e) = self.refresh_diff() {
+                            self.status_message = Some(format!("Diff error: {e}"));
+                        }
+                    }
+                    Err(e) => {
+                        self.error_dialog = Some(ErrorDialogState {
+                            title: "Merge-base failed".to_string(),
+                            message: format!("{e}"),
+                        });
+                    }
+                }
+            }
         }
     }
 
@@ -847,10 +2273,14 @@ impl App {
             return Vec::new();
         }
 
-        // Count files per directory to detect single-file directories
+        // Count files per directory to detect single-file directories, and
+        // sum their stats into every ancestor directory
         let mut dir_file_count: std::collections::HashMap<String, usize> =
             std::collections::HashMap::new();
+        let mut dir_stats: std::collections::HashMap<String, DiffStats> =
+            std::collections::HashMap::new();
         for file in files {
+            let stats = file.stats();
             let parts: Vec<&str> = file.path.rsplitn(2, '/').collect();
             if parts.len() == 2 {
                 // Has a directory component
@@ -863,6 +2293,7 @@ impl App {
                     }
                     current.push_str(segment);
                     *dir_file_count.entry(current.clone()).or_insert(0) += 1;
+                    dir_stats.entry(current.clone()).or_default().add(stats);
                 }
             }
         }
@@ -904,6 +2335,8 @@ impl App {
                     let is_collapsed = self.collapsed_dirs.contains(&dir_path);
                     if !collapsed_ancestor {
                         entries.push(TreeEntry::Dir {
+                            files: dir_file_count.get(&dir_path).copied().unwrap_or(0),
+                            stats: dir_stats.get(&dir_path).copied().unwrap_or_default(),
                             path: dir_path.clone(),
                             depth: i,
                             collapsed: is_collapsed,
@@ -951,7 +2384,7 @@ impl App {
 
     pub fn handle_key(&mut self, key: KeyEvent) -> Result<bool> {
         if self.show_help {
-            self.show_help = false;
+            self.handle_help_key(key);
             return Ok(false);
         }
 
@@ -961,11 +2394,45 @@ impl App {
             return Ok(false);
         }
 
+        // Blame popup: any key dismisses it, and all but Esc still act
+        if self.blame_popup.take().is_some() && key.code == KeyCode::Esc {
+            return Ok(false);
+        }
+
+        // Confirm dialog: y / Enter confirms, Ctrl+c still quits, anything else cancels
+        if let Some(dialog) = self.confirm_dialog.take() {
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                self.should_quit = true;
+            } else if matches!(key.code, KeyCode::Char('y') | KeyCode::Enter) {
+                match dialog.action {
+                    ConfirmAction::Quit => self.should_quit = true,
+                    ConfirmAction::Reset { hash, mode } => self.reset_to(&hash, mode),
+                    ConfirmAction::SetPrDraft { number, draft } => {
+                        self.github.set_pr_draft(number, draft);
+                        self.status_message = Some(if draft {
+                            format!("Converting PR #{number} to draft...")
+                        } else {
+                            format!("Marking PR #{number} as ready...")
+                        });
+                    }
+                }
+            }
+            return Ok(false);
+        }
+
         // Action menu intercepts all keys when open
         if self.branch_action_menu.is_some() {
             self.handle_branch_action_menu_key(key);
             return Ok(false);
         }
+        if self.reset_menu.is_some() {
+            self.handle_reset_menu_key(key);
+            return Ok(false);
+        }
+        if self.raw_json_view.is_some() {
+            self.handle_raw_json_key(key);
+            return Ok(false);
+        }
 
         // Search input mode intercepts all keys
         if self.search.active {
@@ -973,12 +2440,71 @@ impl App {
             return Ok(false);
         }
 
+        // So does the command line
+        if self.command_line.is_some() {
+            self.handle_command_line_key(key)?;
+            return Ok(false);
+        }
+
+        if self.unified_view.is_some() {
+            self.handle_unified_view_key(key);
+            return Ok(false);
+        }
+
+        if self.ref_picker.is_some() {
+            self.handle_ref_picker_key(key);
+            return Ok(false);
+        }
+
+        if self.file_finder.is_some() {
+            self.handle_file_finder_key(key);
+            return Ok(false);
+        }
+        if self.base_picker.is_some() {
+            self.handle_base_picker_key(key);
+            return Ok(false);
+        }
+
         // Ctrl+c always quits
         if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
             self.should_quit = true;
             return Ok(false);
         }
 
+        // `:` opens the command line from any pane outside Visual mode
+        let in_visual = self.focused_pane == FocusedPane::DiffView
+            && matches!(
+                self.diff_view_mode,
+                DiffViewMode::Visual | DiffViewMode::VisualLine | DiffViewMode::VisualBlock
+            );
+        if self.view_mode == ViewMode::Git
+            && key.code == KeyCode::Char(':')
+            && self.pending_key.is_none()
+            && !in_visual
+        {
+            self.count = None;
+            self.command_line = Some(String::new());
+            return Ok(false);
+        }
+
+        if self.view_mode == ViewMode::Git
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+            && key.code == KeyCode::Char('p')
+            && !in_visual
+        {
+            self.open_file_finder();
+            return Ok(false);
+        }
+
+        if self.view_mode == ViewMode::Git
+            && self.focused_pane == FocusedPane::DiffView
+            && self.diff_view_mode == DiffViewMode::Normal
+            && self.pending_key.is_none()
+            && key.code == KeyCode::Enter
+        {
+            return Ok(true); // Signal to open editor at the cursor line
+        }
+
         // In Normal/Visual modes, keys are handled by the mode handler exclusively
         if self.view_mode == ViewMode::Git
             && self.focused_pane == FocusedPane::DiffView
@@ -988,15 +2514,23 @@ impl App {
             return Ok(false);
         }
 
-        // View switching (Scroll mode only — Normal/Visual already returned above)
+        // View switching (Scroll mode only — Normal/Visual already returned above).
+        // Panes that take a count prefix use digits for that instead. Every
+        // GitHub pane does, so that view switches with `gt` instead.
+        let takes_count = match self.view_mode {
+            ViewMode::Git => matches!(
+                self.focused_pane,
+                FocusedPane::DiffView | FocusedPane::GitLog | FocusedPane::Reflog
+            ),
+            ViewMode::GitHub => true,
+        };
         match key.code {
-            KeyCode::Char('1') => {
-                self.view_mode = ViewMode::Git;
+            KeyCode::Char('1') if !takes_count => {
+                self.set_view_mode(ViewMode::Git);
                 return Ok(false);
             }
-            KeyCode::Char('2') => {
-                self.view_mode = ViewMode::GitHub;
-                self.github.initialize();
+            KeyCode::Char('2') if !takes_count => {
+                self.set_view_mode(ViewMode::GitHub);
                 return Ok(false);
             }
             _ => {}
@@ -1006,11 +2540,11 @@ impl App {
             ViewMode::Git => {
                 match key.code {
                     KeyCode::Char('q') => {
-                        self.should_quit = true;
+                        self.request_quit();
                         return Ok(false);
                     }
                     KeyCode::Char('?') => {
-                        self.show_help = true;
+                        self.open_help();
                     }
                     KeyCode::Char('/') => {
                         let origin = match self.focused_pane {
@@ -1028,33 +2562,44 @@ impl App {
                     KeyCode::Char('N') => {
                         self.jump_to_match(false);
                     }
+                    KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.refresh_all()?;
+                    }
                     KeyCode::Char('r') => {
                         self.refresh_diff()?;
-                        self.load_branches();
-                        self.load_reflog();
                     }
                     KeyCode::Char('e') => {
                         return Ok(true); // Signal to open editor
                     }
+                    KeyCode::Char('s') => {
+                        self.cycle_diff_scope();
+                    }
+                    KeyCode::Char('D') => {
+                        self.open_ref_picker();
+                    }
+                    KeyCode::Char('B') => {
+                        self.open_base_picker(String::new());
+                    }
+                    KeyCode::Char('O') => {
+                        self.open_in_default_app();
+                    }
+                    KeyCode::Char('E') => {
+                        self.request_diff_tool();
+                    }
+                    KeyCode::Char('P') => {
+                        self.cycle_diff_palette();
+                    }
+                    KeyCode::Char('T') => {
+                        self.toggle_forced_text();
+                    }
+                    KeyCode::Char('U') => {
+                        self.open_unified_view();
+                    }
                     KeyCode::Tab => {
-                        let next = match self.focused_pane {
-                            FocusedPane::FileTree => FocusedPane::BranchList,
-                            FocusedPane::BranchList => FocusedPane::Reflog,
-                            FocusedPane::Reflog => FocusedPane::GitLog,
-                            FocusedPane::GitLog => FocusedPane::DiffView,
-                            FocusedPane::DiffView => FocusedPane::FileTree,
-                        };
-                        self.set_focus(next);
+                        self.cycle_pane(true);
                     }
                     KeyCode::BackTab => {
-                        let prev = match self.focused_pane {
-                            FocusedPane::FileTree => FocusedPane::DiffView,
-                            FocusedPane::BranchList => FocusedPane::FileTree,
-                            FocusedPane::Reflog => FocusedPane::BranchList,
-                            FocusedPane::GitLog => FocusedPane::Reflog,
-                            FocusedPane::DiffView => FocusedPane::GitLog,
-                        };
-                        self.set_focus(prev);
+                        self.cycle_pane(false);
                     }
                     _ => match self.focused_pane {
                         FocusedPane::FileTree => self.handle_file_tree_key(key),
@@ -1072,16 +2617,183 @@ impl App {
         Ok(false)
     }
 
-    // ── GitHub View key handlers ──────────────────────────────
+    fn open_help(&mut self) {
+        self.show_help = true;
+        self.help = HelpState::default();
+    }
 
-    fn handle_github_key(&mut self, key: KeyEvent) -> Result<bool> {
+    /// Keys while the help overlay is open: scroll and filter it. Only `?`,
+    /// `q` and `Esc` close it, so it can be read and scrolled at leisure.
+    fn handle_help_key(&mut self, key: KeyEvent) {
+        let help = &mut self.help;
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        if help.filtering {
+            match key.code {
+                KeyCode::Esc => {
+                    help.filter.clear();
+                    help.filtering = false;
+                }
+                KeyCode::Enter => help.filtering = false,
+                KeyCode::Backspace => {
+                    help.filter.pop();
+                }
+                KeyCode::Char(c) => {
+                    help.filter.push(c);
+                    help.scroll = 0;
+                }
+                _ => {}
+            }
+            return;
+        }
+        let half = (help.view_height / 2).max(1);
+        match key.code {
+            KeyCode::Char('c') if ctrl => self.should_quit = true,
+            KeyCode::Char('d') if ctrl => help.scroll = help.scroll.saturating_add(half),
+            KeyCode::Char('u') if ctrl => help.scroll = help.scroll.saturating_sub(half),
+            KeyCode::Char('j') | KeyCode::Down => help.scroll = help.scroll.saturating_add(1),
+            KeyCode::Char('k') | KeyCode::Up => help.scroll = help.scroll.saturating_sub(1),
+            KeyCode::Char('g') => help.scroll = 0,
+            KeyCode::Char('G') => help.scroll = help.max_scroll,
+            KeyCode::Char('/') => {
+                help.filter.clear();
+                help.filtering = true;
+                help.scroll = 0;
+            }
+            KeyCode::Esc if !help.filter.is_empty() => {
+                help.filter.clear();
+                help.scroll = 0;
+            }
+            KeyCode::Char('?' | 'q') | KeyCode::Esc => self.show_help = false,
+            _ => {}
+        }
+    }
+
+    /// Keys while the unified view is open. `v` starts a line selection for
+    /// `y`; `q`, `U` and `Esc` close it.
+    fn handle_unified_view_key(&mut self, key: KeyEvent) {
+        let Some(view) = self.unified_view.as_mut() else {
+            return;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let last = view.lines.len().saturating_sub(1);
+        let half = (view.view_height / 2).max(1);
+        match key.code {
+            KeyCode::Char('c') if ctrl => self.should_quit = true,
+            KeyCode::Char('d') if ctrl => view.cursor = (view.cursor + half).min(last),
+            KeyCode::Char('u') if ctrl => view.cursor = view.cursor.saturating_sub(half),
+            KeyCode::Char('j') | KeyCode::Down => view.cursor = (view.cursor + 1).min(last),
+            KeyCode::Char('k') | KeyCode::Up => view.cursor = view.cursor.saturating_sub(1),
+            KeyCode::Char('g') => view.cursor = 0,
+            KeyCode::Char('G') => view.cursor = last,
+            KeyCode::Char('l') | KeyCode::Right => view.scroll_x += 4,
+            KeyCode::Char('h') | KeyCode::Left => view.scroll_x = view.scroll_x.saturating_sub(4),
+            KeyCode::Char(c @ (']' | '[')) => {
+                // File headers come in `--- ` / `+++ ` pairs; land on the first
+                let is_file_start = |l: &UnifiedLine| {
+                    l.kind == UnifiedLineKind::FileHeader && l.text.starts_with("--- ")
+                };
+                let target = if c == ']' {
+                    (view.cursor + 1..view.lines.len()).find(|&i| is_file_start(&view.lines[i]))
+                } else {
+                    (0..view.cursor).rev().find(|&i| is_file_start(&view.lines[i]))
+                };
+                if let Some(line) = target {
+                    view.cursor = line;
+                    view.scroll = line;
+                }
+            }
+            KeyCode::Char('v') => {
+                view.anchor = match view.anchor {
+                    Some(_) => None,
+                    None => Some(view.cursor),
+                };
+            }
+            KeyCode::Char('y') => {
+                let (start, end) = view.selected_range();
+                let text = view.lines[start..=end]
+                    .iter()
+                    .map(|l| l.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                view.anchor = None;
+                self.copy_to_clipboard(&text);
+            }
+            KeyCode::Char('Y') => {
+                let text = view.lines.iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join("\n");
+                self.copy_to_clipboard(&text);
+            }
+            KeyCode::Char('/') => self.search.start(SearchOrigin::UnifiedView),
+            KeyCode::Char('n') => self.jump_to_match(true),
+            KeyCode::Char('N') => self.jump_to_match(false),
+            KeyCode::Esc if view.anchor.is_some() => view.anchor = None,
+            KeyCode::Esc
+                if self.search.origin == SearchOrigin::UnifiedView && self.search.query.is_some() =>
+            {
+                self.search.clear();
+            }
+            KeyCode::Char('q' | 'U') | KeyCode::Esc => {
+                self.unified_view = None;
+                if self.search.origin == SearchOrigin::UnifiedView {
+                    self.search.clear();
+                }
+            }
+            _ => {}
+        }
+        if let Some(view) = self.unified_view.as_mut() {
+            view.scroll_to_cursor();
+        }
+    }
+
+    /// `q` — quit, asking first when `confirm_quit` is set and files are changed.
+    fn request_quit(&mut self) {
+        let changed = self.diff_state.files.len();
+        if self.config.confirm_quit && changed > 0 {
+            self.confirm_dialog = Some(ConfirmDialogState {
+                title: "Quit vig?".to_string(),
+                message: format!(
+                    "Quit with {changed} changed file{}?",
+                    if changed == 1 { "" } else { "s" }
+                ),
+                action: ConfirmAction::Quit,
+            });
+        } else {
+            self.should_quit = true;
+        }
+    }
+
+    // ── GitHub View key handlers ──────────────────────────────
+
+    fn handle_github_key(&mut self, key: KeyEvent) -> Result<bool> {
+        // `gt` / `gT` switch views (`1gt` / `2gt` pick one); `gg` goes to the top
+        if self.pending_key == Some('g') {
+            self.pending_key = None;
+            match key.code {
+                KeyCode::Char('t' | 'T') => {
+                    // With two views, the next and the previous one are both Git
+                    match self.count.take() {
+                        Some(1) | None => self.set_view_mode(ViewMode::Git),
+                        Some(2) => {}
+                        Some(_) => self.status_message = Some("No such view".to_string()),
+                    }
+                    return Ok(false);
+                }
+                KeyCode::Char('g') => {}
+                _ => {
+                    self.count = None;
+                    return Ok(false);
+                }
+            }
+        } else if key.code == KeyCode::Char('g') {
+            self.pending_key = Some('g');
+            return Ok(false);
+        }
         match key.code {
             KeyCode::Char('q') => {
-                self.should_quit = true;
+                self.request_quit();
                 return Ok(false);
             }
             KeyCode::Char('?') => {
-                self.show_help = true;
+                self.open_help();
                 return Ok(false);
             }
             KeyCode::Char('r') => {
@@ -1094,6 +2806,13 @@ impl App {
             }
             _ => {}
         }
+        // Detail matches refer to the shown issue/PR; drop them once back in the lists
+        if self.github.focused_pane != GhFocusedPane::Detail
+            && self.search.origin == SearchOrigin::GitHubDetail
+            && self.search.query.is_some()
+        {
+            self.search.clear();
+        }
         match self.github.focused_pane {
             GhFocusedPane::IssueList => self.handle_gh_issue_list_key(key),
             GhFocusedPane::PrList => self.handle_gh_pr_list_key(key),
@@ -1103,18 +2822,23 @@ impl App {
     }
 
     fn handle_gh_issue_list_key(&mut self, key: KeyEvent) {
+        if self.accumulate_count(key.code) {
+            return;
+        }
+        let n = self.take_count();
         match key.code {
             KeyCode::Char('j') | KeyCode::Down => {
                 if !self.github.issues.is_empty()
                     && self.github.issue_selected_idx + 1 < self.github.issues.len()
                 {
-                    self.github.issue_selected_idx += 1;
+                    self.github.issue_selected_idx =
+                        (self.github.issue_selected_idx + n).min(self.github.issues.len() - 1);
                     self.github.load_selected_issue_detail();
                 }
             }
             KeyCode::Char('k') | KeyCode::Up => {
                 if self.github.issue_selected_idx > 0 {
-                    self.github.issue_selected_idx -= 1;
+                    self.github.issue_selected_idx = self.github.issue_selected_idx.saturating_sub(n);
                     self.github.load_selected_issue_detail();
                 }
             }
@@ -1139,6 +2863,21 @@ impl App {
                     self.github.load_selected_issue_detail();
                 }
             }
+            KeyCode::Char('p') => {
+                if let Some((number, pinned)) = self.github.toggle_issue_pin() {
+                    self.status_message = Some(if pinned {
+                        format!("Pinned issue #{number}")
+                    } else {
+                        format!("Unpinned issue #{number}")
+                    });
+                }
+            }
+            KeyCode::Char(c @ ('y' | 'Y')) => {
+                if let Some(issue) = self.github.issues.get(self.github.issue_selected_idx) {
+                    let number = issue.number;
+                    self.yank_gh_link(GhDetailKind::Issue, number, c == 'Y');
+                }
+            }
             KeyCode::Char('o') => {
                 if let Some(issue) = self.github.issues.get(self.github.issue_selected_idx) {
                     let number = issue.number;
@@ -1158,18 +2897,23 @@ impl App {
     }
 
     fn handle_gh_pr_list_key(&mut self, key: KeyEvent) {
+        if self.accumulate_count(key.code) {
+            return;
+        }
+        let n = self.take_count();
         match key.code {
             KeyCode::Char('j') | KeyCode::Down => {
                 if !self.github.prs.is_empty()
                     && self.github.pr_selected_idx + 1 < self.github.prs.len()
                 {
-                    self.github.pr_selected_idx += 1;
+                    self.github.pr_selected_idx =
+                        (self.github.pr_selected_idx + n).min(self.github.prs.len() - 1);
                     self.github.load_selected_pr_detail();
                 }
             }
             KeyCode::Char('k') | KeyCode::Up => {
                 if self.github.pr_selected_idx > 0 {
-                    self.github.pr_selected_idx -= 1;
+                    self.github.pr_selected_idx = self.github.pr_selected_idx.saturating_sub(n);
                     self.github.load_selected_pr_detail();
                 }
             }
@@ -1187,6 +2931,24 @@ impl App {
                 self.github.focused_pane = GhFocusedPane::IssueList;
                 self.github.load_selected_issue_detail();
             }
+            KeyCode::Char('m') => {
+                self.github.toggle_prs_mine_only();
+            }
+            KeyCode::Char('p') => {
+                if let Some((number, pinned)) = self.github.toggle_pr_pin() {
+                    self.status_message = Some(if pinned {
+                        format!("Pinned PR #{number}")
+                    } else {
+                        format!("Unpinned PR #{number}")
+                    });
+                }
+            }
+            KeyCode::Char('D') => {
+                if let Some(pr) = self.github.prs.get(self.github.pr_selected_idx) {
+                    let (number, is_draft) = (pr.number, pr.is_draft);
+                    self.request_pr_draft_toggle(number, is_draft);
+                }
+            }
             KeyCode::Char('i') | KeyCode::Enter => {
                 if !self.github.prs.is_empty() {
                     self.github.previous_pane = GhFocusedPane::PrList;
@@ -1194,6 +2956,12 @@ impl App {
                     self.github.load_selected_pr_detail();
                 }
             }
+            KeyCode::Char(c @ ('y' | 'Y')) => {
+                if let Some(pr) = self.github.prs.get(self.github.pr_selected_idx) {
+                    let number = pr.number;
+                    self.yank_gh_link(GhDetailKind::Pr, number, c == 'Y');
+                }
+            }
             KeyCode::Char('o') => {
                 if let Some(pr) = self.github.prs.get(self.github.pr_selected_idx) {
                     let number = pr.number;
@@ -1241,22 +3009,28 @@ impl App {
         };
         let selectable = pane != GhDetailPane::Body;
 
+        if self.accumulate_count(key.code) {
+            return;
+        }
+        let n = self.take_count();
+        let n16 = n.min(u16::MAX as usize) as u16;
         match key.code {
             KeyCode::Char('j') | KeyCode::Down => {
+                let max_scroll = self.github.detail_max_scroll;
                 if selectable && item_count > 0 {
                     let idx = self.github.active_selected_idx_mut();
                     if *idx + 1 < item_count {
-                        *idx += 1;
+                        *idx = (*idx + n).min(item_count - 1);
                         // Reset intra-item scroll when selection moves
                         *self.github.active_detail_scroll_mut() = 0;
                     } else {
                         // At last item — scroll within
                         let scroll = self.github.active_detail_scroll_mut();
-                        *scroll = scroll.saturating_add(1);
+                        *scroll = scroll.saturating_add(n16).min(max_scroll);
                     }
                 } else if !selectable {
                     let scroll = self.github.active_detail_scroll_mut();
-                    *scroll = scroll.saturating_add(1);
+                    *scroll = scroll.saturating_add(n16).min(max_scroll);
                 }
             }
             KeyCode::Char('k') | KeyCode::Up => {
@@ -1264,20 +3038,34 @@ impl App {
                     let scroll_val = *self.github.active_detail_scroll_mut();
                     if scroll_val > 0 {
                         // Scroll back within current item first
-                        *self.github.active_detail_scroll_mut() = scroll_val - 1;
+                        *self.github.active_detail_scroll_mut() = scroll_val.saturating_sub(n16);
                     } else {
                         let idx = self.github.active_selected_idx_mut();
-                        *idx = idx.saturating_sub(1);
+                        *idx = idx.saturating_sub(n);
                     }
                 } else {
                     let scroll = self.github.active_detail_scroll_mut();
-                    *scroll = scroll.saturating_sub(1);
+                    *scroll = scroll.saturating_sub(n16);
                 }
             }
+            KeyCode::Char(c @ (']' | '[')) if pane == GhDetailPane::Status && item_count > 0 => {
+                self.jump_to_flagged_check(c == ']');
+            }
+            KeyCode::Char(']') | KeyCode::Char('[') if selectable && item_count > 0 => {
+                // Jump straight to the next/previous item's header
+                let idx = self.github.active_selected_idx_mut();
+                *idx = if key.code == KeyCode::Char(']') {
+                    (*idx + n).min(item_count - 1)
+                } else {
+                    idx.saturating_sub(n)
+                };
+                *self.github.active_detail_scroll_mut() = 0;
+            }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 let half = (self.github.detail_view_height / 2).max(1);
+                let max_scroll = self.github.detail_max_scroll;
                 let scroll = self.github.active_detail_scroll_mut();
-                *scroll = scroll.saturating_add(half);
+                *scroll = scroll.saturating_add(half).min(max_scroll);
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 let half = (self.github.detail_view_height / 2).max(1);
@@ -1291,11 +3079,14 @@ impl App {
                 *self.github.active_detail_scroll_mut() = 0;
             }
             KeyCode::Char('G') => {
-                if selectable && item_count > 0 {
-                    *self.github.active_selected_idx_mut() = item_count - 1;
-                }
-                if !selectable || item_count > 0 {
-                    *self.github.active_detail_scroll_mut() = u16::MAX / 2;
+                if selectable {
+                    // Last item with its header at the top; a further `j` scrolls within it
+                    if item_count > 0 {
+                        *self.github.active_selected_idx_mut() = item_count - 1;
+                    }
+                    *self.github.active_detail_scroll_mut() = 0;
+                } else {
+                    *self.github.active_detail_scroll_mut() = self.github.detail_max_scroll;
                 }
             }
             KeyCode::Char('h') => {
@@ -1347,13 +3138,188 @@ impl App {
             KeyCode::Char('o') => {
                 self.open_gh_detail_item();
             }
+            KeyCode::Char('D') => {
+                if let GhDetailContent::Pr(detail) = &self.github.detail {
+                    let (number, is_draft) = (detail.number, detail.is_draft);
+                    self.request_pr_draft_toggle(number, is_draft);
+                }
+            }
+            KeyCode::Char('y') if pane == GhDetailPane::Status && item_count > 0 => {
+                self.yank_selected_check();
+            }
+            KeyCode::Char(c @ ('y' | 'Y')) => {
+                let item = match &self.github.detail {
+                    GhDetailContent::Issue(detail) => Some((GhDetailKind::Issue, detail.number)),
+                    GhDetailContent::Pr(detail) => Some((GhDetailKind::Pr, detail.number)),
+                    GhDetailContent::Loading { kind, number } => Some((*kind, *number)),
+                    _ => None,
+                };
+                if let Some((kind, number)) = item {
+                    self.yank_gh_link(kind, number, c == 'Y');
+                }
+            }
+            KeyCode::Char('J') if self.github.debug => self.open_raw_json(),
+            KeyCode::Char('/') => {
+                self.search.start(SearchOrigin::GitHubDetail);
+            }
+            KeyCode::Char('n') => {
+                self.jump_to_match(true);
+            }
+            KeyCode::Char('N') => {
+                self.jump_to_match(false);
+            }
             KeyCode::Esc => {
-                self.github.focused_pane = self.github.previous_pane;
+                if self.search.origin == SearchOrigin::GitHubDetail && self.search.query.is_some() {
+                    self.search.clear();
+                } else {
+                    self.github.focused_pane = self.github.previous_pane;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// `J` in the GitHub detail (`--debug`) — show the JSON `gh` returned for
+    /// the issue / PR, to see why a field doesn't render.
+    fn open_raw_json(&mut self) {
+        let Some((kind, number)) = self.github.shown_item() else {
+            return;
+        };
+        let Some(raw) = self.github.raw_json.get(&(kind, number)) else {
+            self.status_message = Some(format!("No JSON kept for #{number} yet; press r to re-fetch"));
+            return;
+        };
+        let text = serde_json::from_str::<serde_json::Value>(raw)
+            .and_then(|value| serde_json::to_string_pretty(&value))
+            .unwrap_or_else(|_| raw.clone());
+        let what = match kind {
+            GhDetailKind::Issue => "issue",
+            GhDetailKind::Pr => "pr",
+        };
+        self.raw_json_view = Some(RawJsonView {
+            title: format!(" gh {what} view {number} --json "),
+            text,
+            ..Default::default()
+        });
+    }
+
+    fn handle_raw_json_key(&mut self, key: KeyEvent) {
+        let Some(view) = self.raw_json_view.as_mut() else {
+            return;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let half = (view.view_height / 2).max(1);
+        match key.code {
+            KeyCode::Char('c') if ctrl => self.should_quit = true,
+            KeyCode::Char('d') if ctrl => view.scroll = view.scroll.saturating_add(half),
+            KeyCode::Char('u') if ctrl => view.scroll = view.scroll.saturating_sub(half),
+            KeyCode::Char('j') | KeyCode::Down => view.scroll = view.scroll.saturating_add(1),
+            KeyCode::Char('k') | KeyCode::Up => view.scroll = view.scroll.saturating_sub(1),
+            KeyCode::Char('g') => view.scroll = 0,
+            KeyCode::Char('G') => view.scroll = view.max_scroll,
+            KeyCode::Char('y') => {
+                let text = view.text.clone();
+                self.copy_to_clipboard(&text);
             }
+            KeyCode::Char('J' | 'q') | KeyCode::Esc => self.raw_json_view = None,
             _ => {}
         }
     }
 
+    /// `D` in the GitHub view — ask before flipping a PR between draft and
+    /// ready for review.
+    fn request_pr_draft_toggle(&mut self, number: u64, is_draft: bool) {
+        let (title, message) = if is_draft {
+            ("Mark ready?", format!("Mark PR #{number} as ready for review?"))
+        } else {
+            ("Convert to draft?", format!("Convert PR #{number} back to a draft?"))
+        };
+        self.confirm_dialog = Some(ConfirmDialogState {
+            title: title.to_string(),
+            message,
+            action: ConfirmAction::SetPrDraft { number, draft: !is_draft },
+        });
+    }
+
+    /// `y` / `Y` in the GitHub view — copy an issue/PR URL, or just `#<number>`.
+    /// The URL is built from the repository's remote.
+    fn yank_gh_link(&mut self, kind: GhDetailKind, number: u64, number_only: bool) {
+        if number_only {
+            let text = format!("#{number}");
+            self.copy_with_message(&text, format!("Copied {text}"));
+            return;
+        }
+        let base = self
+            .repo
+            .remote_url()
+            .and_then(|url| crate::github::client::web_url_from_remote(&url));
+        let Some(base) = base else {
+            self.status_message = Some("No remote to build the URL from".to_string());
+            return;
+        };
+        let path = match kind {
+            GhDetailKind::Issue => "issues",
+            GhDetailKind::Pr => "pull",
+        };
+        let url = format!("{base}/{path}/{number}");
+        self.copy_with_message(&url, format!("Copied {url}"));
+    }
+
+    /// `]` / `[` in the Status pane — select the next / previous failed or
+    /// running check, wrapping around the list.
+    fn jump_to_flagged_check(&mut self, forward: bool) {
+        use crate::github::state::GhDetailContent;
+        use crate::ui::github::detail_view::{check_needs_attention, sorted_checks};
+
+        let GhDetailContent::Pr(detail) = &self.github.detail else {
+            return;
+        };
+        let checks = sorted_checks(detail);
+        let len = checks.len();
+        let current = self.github.detail_check_idx.min(len.saturating_sub(1));
+        let target = (1..=len)
+            .map(|step| {
+                if forward {
+                    (current + step) % len
+                } else {
+                    (current + len - step) % len
+                }
+            })
+            .find(|&idx| check_needs_attention(checks[idx]));
+        match target {
+            Some(idx) => {
+                self.github.detail_check_idx = idx;
+                self.github.detail_scroll_status = 0;
+            }
+            None => self.status_message = Some("No failed or running checks".to_string()),
+        }
+    }
+
+    /// `y` in the Status pane — copy the selected check as
+    /// `<workflow> / <name>: <conclusion>`, e.g. to paste a failure into chat.
+    fn yank_selected_check(&mut self) {
+        use crate::github::state::GhDetailContent;
+
+        let GhDetailContent::Pr(detail) = &self.github.detail else {
+            return;
+        };
+        let checks = crate::ui::github::detail_view::sorted_checks(detail);
+        let Some(check) = checks.get(self.github.detail_check_idx) else {
+            return;
+        };
+        // Running checks have no conclusion yet
+        let state = check
+            .conclusion
+            .as_deref()
+            .filter(|c| !c.is_empty())
+            .unwrap_or(&check.status);
+        let text = match check.workflow_name.as_deref().filter(|w| !w.is_empty()) {
+            Some(workflow) => format!("{workflow} / {}: {state}", check.name),
+            None => format!("{}: {state}", check.name),
+        };
+        self.copy_with_message(&text, format!("Copied {text}"));
+    }
+
     fn open_gh_detail_item(&mut self) {
         use crate::github::state::{GhDetailContent, GhDetailPane};
 
@@ -1472,17 +3438,17 @@ impl App {
         match key.code {
             KeyCode::Char('j') | KeyCode::Down => {
                 if self.selected_tree_idx + 1 < entries.len() {
+                    self.save_file_view();
                     self.selected_tree_idx += 1;
-                    self.diff_scroll_y = 0;
-                    self.diff_scroll_x = 0;
+                    self.restore_file_view();
                     self.re_search_on_file_change();
                 }
             }
             KeyCode::Char('k') | KeyCode::Up => {
                 if self.selected_tree_idx > 0 {
+                    self.save_file_view();
                     self.selected_tree_idx -= 1;
-                    self.diff_scroll_y = 0;
-                    self.diff_scroll_x = 0;
+                    self.restore_file_view();
                     self.re_search_on_file_change();
                 }
             }
@@ -1508,8 +3474,6 @@ impl App {
                     }
                     Some(TreeEntry::File { .. }) => {
                         self.set_focus(FocusedPane::DiffView);
-                        self.diff_scroll_y = 0;
-                        self.diff_scroll_x = 0;
                     }
                     None => {}
                 }
@@ -1528,28 +3492,44 @@ impl App {
     }
 
     fn handle_diff_view_key(&mut self, key: KeyEvent) {
+        // ]f / [f — next / previous file (Scroll and Normal mode)
+        if let Some(c @ (']' | '[')) = self.pending_key {
+            self.pending_key = None;
+            self.count = None;
+            if key.code == KeyCode::Char('f') {
+                self.select_adjacent_file(c == ']');
+            }
+            return;
+        }
         match self.diff_view_mode {
             DiffViewMode::Scroll => self.handle_diff_scroll_key(key),
             DiffViewMode::Normal => self.handle_diff_normal_key(key),
-            DiffViewMode::Visual | DiffViewMode::VisualLine => self.handle_diff_visual_key(key),
+            DiffViewMode::Visual | DiffViewMode::VisualLine | DiffViewMode::VisualBlock => {
+                self.handle_diff_visual_key(key)
+            }
         }
+        self.pin_cursor_side();
     }
 
     fn handle_diff_scroll_key(&mut self, key: KeyEvent) {
+        if self.accumulate_count(key.code) {
+            return;
+        }
+        let n = self.take_count().min(u16::MAX as usize) as u16;
         let max_scroll = self.diff_total_lines.saturating_sub(self.diff_view_height);
         match key.code {
             KeyCode::Char('j') | KeyCode::Down => {
-                self.diff_scroll_y = (self.diff_scroll_y + 1).min(max_scroll);
+                self.diff_scroll_y = self.diff_scroll_y.saturating_add(n).min(max_scroll);
             }
             KeyCode::Char('k') | KeyCode::Up => {
-                self.diff_scroll_y = self.diff_scroll_y.saturating_sub(1);
+                self.diff_scroll_y = self.diff_scroll_y.saturating_sub(n);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let half = self.diff_view_height / 2;
-                self.diff_scroll_y = (self.diff_scroll_y + half).min(max_scroll);
+                let half = (self.diff_view_height / 2).saturating_mul(n);
+                self.diff_scroll_y = self.diff_scroll_y.saturating_add(half).min(max_scroll);
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let half = self.diff_view_height / 2;
+                let half = (self.diff_view_height / 2).saturating_mul(n);
                 self.diff_scroll_y = self.diff_scroll_y.saturating_sub(half);
             }
             KeyCode::Char('g') => {
@@ -1559,7 +3539,7 @@ impl App {
                 self.diff_scroll_y = max_scroll;
             }
             KeyCode::Char('h') | KeyCode::Left => {
-                self.diff_scroll_x = self.diff_scroll_x.saturating_sub(4);
+                self.diff_scroll_x = self.diff_scroll_x.saturating_sub(4u16.saturating_mul(n));
             }
             KeyCode::Esc => {
                 if self.search.query.is_some() {
@@ -1569,7 +3549,7 @@ impl App {
                 }
             }
             KeyCode::Char('l') | KeyCode::Right => {
-                self.diff_scroll_x = self.diff_scroll_x.saturating_add(4);
+                self.diff_scroll_x = self.diff_scroll_x.saturating_add(4u16.saturating_mul(n));
             }
             KeyCode::Char('/') => {
                 self.search.start(SearchOrigin::DiffView);
@@ -1581,9 +3561,33 @@ impl App {
             KeyCode::Char('N') => {
                 self.jump_to_match(false);
             }
+            KeyCode::Char(c @ (']' | '[')) => {
+                self.pending_key = Some(c);
+            }
+            KeyCode::Char('c') => {
+                self.toggle_changes_only();
+            }
+            KeyCode::Char('Y') if self.search.origin == SearchOrigin::DiffView
+                && self.search.query.is_some() =>
+            {
+                self.yank_search_matches();
+            }
+            KeyCode::Char('Y') => {
+                self.yank_file_diff();
+            }
+            KeyCode::Char(c @ ('<' | '>')) => {
+                let steps = n as f32;
+                self.shift_diff_split(if c == '<' { -steps } else { steps });
+            }
+            KeyCode::Char('|') => {
+                self.toggle_full_width_new_files();
+            }
+            KeyCode::Char('L') => {
+                self.toggle_line_numbers();
+            }
             KeyCode::Char('i') => {
                 // Enter Normal mode with cursor at top-left of visible area
-                let lines = self.content_lines();
+                let lines = self.content_lines(0..0);
                 if !lines.is_empty() {
                     self.diff_view_mode = DiffViewMode::Normal;
                     self.cursor_pos = CursorPos {
@@ -1604,6 +3608,18 @@ impl App {
             return;
         }
 
+        // Ctrl-O / Ctrl-I (Tab) — walk the jump list
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('o') {
+            self.jump_back();
+            return;
+        }
+        if key.code == KeyCode::Tab
+            || (key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('i'))
+        {
+            self.jump_forward();
+            return;
+        }
+
         // Handle pending key sequences
         if let Some(pending) = self.pending_key {
             self.pending_key = None;
@@ -1618,16 +3634,17 @@ impl App {
                     return;
                 }
                 'y' => {
-                    let lines = self.content_lines();
                     let n = self.take_count();
+                    let lines = self.content_lines_near_cursor(n);
                     self.execute_yank_motion(key.code, &lines, n);
                     return;
                 }
                 'g' => {
-                    let lines = self.content_lines();
+                    let lines = self.content_lines_near_cursor(0);
                     match key.code {
                         KeyCode::Char('g') => {
                             // gg or {count}gg — go to line
+                            self.push_jump();
                             if let Some(n) = self.count.take() {
                                 self.cursor_pos.row = (n.saturating_sub(1)).min(lines.len().saturating_sub(1));
                             } else {
@@ -1642,6 +3659,21 @@ impl App {
                     self.scroll_to_cursor();
                     return;
                 }
+                'm' => {
+                    if let KeyCode::Char(c @ 'a'..='z') = key.code {
+                        self.set_mark(c);
+                    }
+                    self.count = None;
+                    return;
+                }
+                '\'' | '`' => {
+                    if let KeyCode::Char(c @ 'a'..='z') = key.code {
+                        self.jump_to_mark(c, pending == '`');
+                    }
+                    self.count = None;
+                    self.scroll_to_cursor();
+                    return;
+                }
                 _ => {}
             }
             self.count = None;
@@ -1663,7 +3695,7 @@ impl App {
         }
 
         let n = self.take_count();
-        let lines = self.content_lines();
+        let lines = self.content_lines_near_cursor(n);
         let total = lines.len();
         if total == 0 {
             return;
@@ -1685,19 +3717,27 @@ impl App {
                 self.cursor_pos.row = self.cursor_pos.row.saturating_sub(n);
                 self.clamp_col(&lines);
             }
-            KeyCode::Char('w') => {
+            KeyCode::Char(c @ ('w' | 'W')) => {
                 for _ in 0..n {
-                    self.move_word_forward(&lines);
+                    self.move_word_forward(&lines, c == 'W');
                 }
             }
-            KeyCode::Char('b') => {
+            KeyCode::Char(c @ ('b' | 'B')) => {
                 for _ in 0..n {
-                    self.move_word_backward(&lines);
+                    self.move_word_backward(&lines, c == 'B');
                 }
             }
-            KeyCode::Char('e') => {
+            KeyCode::Char(c @ ('e' | 'E')) => {
                 for _ in 0..n {
-                    self.move_word_end(&lines);
+                    self.move_word_end(&lines, c == 'E');
+                }
+            }
+            KeyCode::Char(c @ ('{' | '}')) => {
+                self.push_jump();
+                // Paragraphs can run anywhere in the file
+                let lines = self.content_lines(0..total);
+                for _ in 0..n {
+                    self.move_paragraph(&lines, c == '}');
                 }
             }
             KeyCode::Char('0') => {
@@ -1713,6 +3753,7 @@ impl App {
             KeyCode::Char('G') => {
                 // G or {count}G — go to last line or specific line
                 // Note: count was already consumed, but if n > 1, user typed {n}G
+                self.push_jump();
                 if n > 1 {
                     self.cursor_pos.row = (n - 1).min(total - 1);
                 } else {
@@ -1724,6 +3765,56 @@ impl App {
             KeyCode::Char('y') => {
                 self.pending_key = Some('y');
             }
+            KeyCode::Char(c @ ('m' | '\'' | '`' | ']' | '[')) => {
+                self.pending_key = Some(c);
+            }
+            KeyCode::Char('c') => {
+                self.toggle_changes_only();
+            }
+            KeyCode::Char('Y') if self.search.origin == SearchOrigin::DiffView
+                && self.search.query.is_some() =>
+            {
+                self.yank_search_matches();
+            }
+            KeyCode::Char('Y') => {
+                self.yank_file_diff();
+            }
+            KeyCode::Char(c @ ('C' | 'S')) => {
+                self.yank_hunk(c == 'S');
+            }
+            KeyCode::Char('R') => {
+                self.toggle_relative_numbers();
+            }
+            KeyCode::Char('L') => {
+                self.toggle_line_numbers();
+            }
+            KeyCode::Char('K') => {
+                self.show_line_blame();
+            }
+            KeyCode::Char(c @ ('a' | 'u')) => {
+                self.stage_hunk(c == 'u');
+            }
+            KeyCode::Char(c @ ('<' | '>')) => {
+                let steps = n as f32;
+                self.shift_diff_split(if c == '<' { -steps } else { steps });
+            }
+            KeyCode::Char('|') => {
+                self.toggle_full_width_new_files();
+            }
+            KeyCode::Char('.') => {
+                // {count}. overrides the count of the repeated yank
+                let reach = match self.last_yank {
+                    Some(LastYank::Motion { count, .. }) => count.max(n),
+                    Some(LastYank::Visual { rows, .. } | LastYank::Block { rows, .. }) => rows,
+                    None => 0,
+                };
+                let lines = self.content_lines_near_cursor(reach);
+                self.repeat_last_yank(&lines, (n > 1).then_some(n));
+            }
+            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.diff_view_mode = DiffViewMode::VisualBlock;
+                self.visual_anchor = Some(self.cursor_pos);
+            }
             KeyCode::Char('v') => {
                 self.diff_view_mode = DiffViewMode::Visual;
                 self.visual_anchor = Some(self.cursor_pos);
@@ -1737,6 +3828,9 @@ impl App {
                 self.pending_key = None;
                 self.count = None;
             }
+            KeyCode::Char(c @ ('*' | '#')) => {
+                self.search_word_under_cursor(&lines, c == '*');
+            }
             KeyCode::Char('n') => {
                 self.jump_to_match(true);
             }
@@ -1757,25 +3851,46 @@ impl App {
         self.scroll_to_cursor();
     }
 
+    /// Accumulate a digit count prefix (1-9 start, 0 appends). Returns true if
+    /// the key was consumed as part of the count.
+    fn accumulate_count(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char(c @ '1'..='9') => {
+                let digit = (c as usize) - ('0' as usize);
+                self.count = Some(self.count.unwrap_or(0).saturating_mul(10) + digit);
+                true
+            }
+            KeyCode::Char('0') if self.count.is_some() => {
+                self.count = self.count.map(|c| c.saturating_mul(10));
+                true
+            }
+            _ => false,
+        }
+    }
+
     fn take_count(&mut self) -> usize {
         self.count.take().unwrap_or(1)
     }
 
-    /// Execute y + motion (yy, yw, y$, y0, yb, ye) with count
-    fn execute_yank_motion(&mut self, motion: KeyCode, lines: &[String], count: usize) {
+    /// Execute y + motion (yy, yw, yW, y$, y0, yb, yB, ye, yE) with count
+    fn execute_yank_motion(&mut self, motion: KeyCode, lines: &ContentLines, count: usize) {
+        if !matches!(motion, KeyCode::Char('y' | 'w' | 'W' | 'e' | 'E' | 'b' | 'B' | '$' | '0')) {
+            return;
+        }
+        self.last_yank = Some(LastYank::Motion { motion, count });
         let text = match motion {
             // yy or {n}yy — yank current line(s)
             KeyCode::Char('y') => {
                 let start = self.cursor_pos.row;
                 let end = (start + count).min(lines.len());
-                let yanked: Vec<&str> = lines[start..end].iter().map(|s| s.as_str()).collect();
+                let yanked: Vec<&str> = (start..end).filter_map(|r| lines.get(r)).map(|s| s.as_str()).collect();
                 yanked.join("\n")
             }
-            // yw — yank from cursor to next word start
-            KeyCode::Char('w') => {
+            // yw / yW — yank from cursor to next word start
+            KeyCode::Char(c @ ('w' | 'W')) => {
                 let saved = self.cursor_pos;
                 for _ in 0..count {
-                    self.move_word_forward(lines);
+                    self.move_word_forward(lines, c == 'W');
                 }
                 let end = self.cursor_pos;
                 self.cursor_pos = saved;
@@ -1808,21 +3923,21 @@ impl App {
                 };
                 self.extract_range(lines, saved, adjusted_end)
             }
-            // ye — yank from cursor to end of word
-            KeyCode::Char('e') => {
+            // ye / yE — yank from cursor to end of word
+            KeyCode::Char(c @ ('e' | 'E')) => {
                 let saved = self.cursor_pos;
                 for _ in 0..count {
-                    self.move_word_end(lines);
+                    self.move_word_end(lines, c == 'E');
                 }
                 let end = self.cursor_pos;
                 self.cursor_pos = saved;
                 self.extract_range(lines, saved, end)
             }
-            // yb — yank from previous word start to cursor
-            KeyCode::Char('b') => {
+            // yb / yB — yank from previous word start to cursor
+            KeyCode::Char(c @ ('b' | 'B')) => {
                 let saved = self.cursor_pos;
                 for _ in 0..count {
-                    self.move_word_backward(lines);
+                    self.move_word_backward(lines, c == 'B');
                 }
                 let start = self.cursor_pos;
                 self.cursor_pos = saved;
@@ -1853,56 +3968,194 @@ impl App {
         self.copy_to_clipboard(&text);
     }
 
-    /// Extract text between two positions (inclusive)
-    fn extract_range(&self, lines: &[String], start: CursorPos, end: CursorPos) -> String {
-        if start.row == end.row {
-            if let Some(line) = lines.get(start.row) {
-                let chars: Vec<char> = line.chars().collect();
-                let s = start.col.min(chars.len());
-                let e = (end.col + 1).min(chars.len());
-                return chars[s..e].iter().collect();
-            }
-            return String::new();
+    /// Record the cursor position before a big move. Drops any positions
+    /// ahead of the current one, as a new jump starts a new branch.
+    fn push_jump(&mut self) {
+        const MAX_JUMPS: usize = 100;
+        let Some(path) = self.selected_file().map(|f| f.path.clone()) else {
+            return;
+        };
+        self.jump_list.truncate(self.jump_idx);
+        let entry = (path, self.cursor_pos);
+        if self.jump_list.last() != Some(&entry) {
+            self.jump_list.push(entry);
         }
-        let mut result = String::new();
-        for r in start.row..=end.row {
-            if let Some(line) = lines.get(r) {
-                let chars: Vec<char> = line.chars().collect();
-                if r == start.row {
-                    let s = start.col.min(chars.len());
-                    result.extend(&chars[s..]);
-                } else if r == end.row {
-                    result.push('\n');
-                    let e = (end.col + 1).min(chars.len());
-                    result.extend(&chars[..e]);
-                } else {
-                    result.push('\n');
-                    result.extend(&chars[..]);
-                }
+        if self.jump_list.len() > MAX_JUMPS {
+            self.jump_list.remove(0);
+        }
+        self.jump_idx = self.jump_list.len();
+    }
+
+    /// Ctrl-O — go to the previous position in the jump list
+    fn jump_back(&mut self) {
+        if self.jump_idx == 0 || self.selected_file().is_none() {
+            return;
+        }
+        if self.jump_idx == self.jump_list.len() {
+            // Remember where we are so Ctrl-I can come back
+            self.push_jump();
+            self.jump_idx = self.jump_list.len() - 1;
+            if self.jump_idx == 0 {
+                return;
             }
         }
-        result
+        self.jump_idx -= 1;
+        self.goto_jump(self.jump_idx);
     }
 
-    fn handle_diff_visual_key(&mut self, key: KeyEvent) {
-        // Handle pending key sequences
-        if let Some(prefix) = self.pending_key {
+    /// Ctrl-I — go to the next position in the jump list
+    fn jump_forward(&mut self) {
+        if self.jump_idx + 1 >= self.jump_list.len() {
+            return;
+        }
+        self.jump_idx += 1;
+        self.goto_jump(self.jump_idx);
+    }
+
+    fn goto_jump(&mut self, idx: usize) {
+        let (path, pos) = self.jump_list[idx].clone();
+        if self.selected_file().map(|f| &f.path) != Some(&path) {
+            let entries = self.build_tree_entries();
+            let Some(tree_idx) = entries.iter().position(|e| matches!(e, TreeEntry::File { file_idx, .. } if self.diff_state.files.get(*file_idx).map(|f| &f.path) == Some(&path))) else {
+                self.status_message = Some(format!("{path} is no longer in the diff"));
+                return;
+            };
+            self.save_file_view();
+            self.selected_tree_idx = tree_idx;
+            self.restore_file_view();
+            self.re_search_on_file_change();
+        }
+        self.cursor_pos.side = pos.side;
+        self.content_lines_cache = None;
+        let lines = self.content_lines(pos.row..pos.row + 1);
+        self.cursor_pos.row = pos.row.min(lines.len().saturating_sub(1));
+        self.cursor_pos.col = pos.col;
+        self.clamp_col(&lines);
+        self.scroll_to_cursor();
+    }
+
+    /// `m{a-z}` — remember the cursor position in the current file
+    fn set_mark(&mut self, mark: char) {
+        let path = self.selected_file().map(|f| f.path.clone());
+        if self.marks_file != path {
+            self.marks.clear();
+            self.marks_file = path;
+        }
+        self.marks.insert(mark, self.cursor_pos);
+        self.status_message = Some(format!("Mark '{mark}' set"));
+    }
+
+    /// `'{a-z}` jumps to the marked row start, `` `{a-z} `` to the exact position
+    fn jump_to_mark(&mut self, mark: char, exact: bool) {
+        let path = self.selected_file().map(|f| f.path.clone());
+        let pos = match self.marks.get(&mark) {
+            Some(pos) if self.marks_file == path => *pos,
+            _ => {
+                self.status_message = Some(format!("Mark '{mark}' not set"));
+                return;
+            }
+        };
+        self.push_jump();
+        // The mark may be on the other side, so fetch its lines after switching
+        self.cursor_pos.side = pos.side;
+        let lines = self.content_lines(pos.row..pos.row + 1);
+        self.cursor_pos.row = pos.row.min(lines.len().saturating_sub(1));
+        self.cursor_pos.col = if exact { pos.col } else { 0 };
+        self.clamp_col(&lines);
+    }
+
+    /// `.` — replay the last yank at the current cursor position
+    fn repeat_last_yank(&mut self, lines: &ContentLines, count: Option<usize>) {
+        if lines.is_empty() {
+            return;
+        }
+        match self.last_yank {
+            Some(LastYank::Motion { motion, count: last }) => {
+                self.execute_yank_motion(motion, lines, count.unwrap_or(last));
+            }
+            Some(LastYank::Visual { line_wise, rows, cols }) => {
+                let start = self.cursor_pos;
+                let end_row = (start.row + rows).min(lines.len().saturating_sub(1));
+                let text = if line_wise {
+                    let rows: Vec<&str> = (start.row..=end_row).filter_map(|r| lines.get(r)).map(|s| s.as_str()).collect();
+                    rows.join("\n")
+                } else {
+                    let end_col = if rows == 0 { start.col + cols } else { cols };
+                    let end = CursorPos { row: end_row, col: end_col, side: start.side };
+                    self.extract_range(lines, start, end)
+                };
+                self.copy_to_clipboard(&text);
+            }
+            Some(LastYank::Block { rows, cols }) => {
+                let start = self.cursor_pos;
+                let end = CursorPos {
+                    row: (start.row + rows).min(lines.len().saturating_sub(1)),
+                    col: start.col + cols,
+                    side: start.side,
+                };
+                let text = extract_block(lines, start, end);
+                self.copy_to_clipboard(&text);
+            }
+            None => {
+                self.status_message = Some("No previous yank to repeat".to_string());
+            }
+        }
+    }
+
+    /// Extract text between two positions (inclusive)
+    fn extract_range(&self, lines: &ContentLines, start: CursorPos, end: CursorPos) -> String {
+        if start.row == end.row {
+            if let Some(line) = lines.get(start.row) {
+                let chars: Vec<char> = line.chars().collect();
+                let s = start.col.min(chars.len());
+                let e = (end.col + 1).min(chars.len());
+                return chars[s..e].iter().collect();
+            }
+            return String::new();
+        }
+        let mut result = String::new();
+        for r in start.row..=end.row {
+            if let Some(line) = lines.get(r) {
+                let chars: Vec<char> = line.chars().collect();
+                if r == start.row {
+                    let s = start.col.min(chars.len());
+                    result.extend(&chars[s..]);
+                } else if r == end.row {
+                    result.push('\n');
+                    let e = (end.col + 1).min(chars.len());
+                    result.extend(&chars[..e]);
+                } else {
+                    result.push('\n');
+                    result.extend(&chars[..]);
+                }
+            }
+        }
+        result
+    }
+
+    fn handle_diff_visual_key(&mut self, key: KeyEvent) {
+        // Handle pending key sequences
+        if let Some(prefix) = self.pending_key {
             self.pending_key = None;
             match prefix {
                 'i' | 'a' => {
-                    let lines = self.content_lines();
+                    let lines = self.content_lines_near_cursor(0);
                     self.apply_text_object(prefix, key.code, &lines);
                 }
                 'g' => {
-                    let lines = self.content_lines();
-                    if key.code == KeyCode::Char('g') {
-                        if let Some(n) = self.count.take() {
-                            self.cursor_pos.row = (n.saturating_sub(1)).min(lines.len().saturating_sub(1));
-                        } else {
-                            self.cursor_pos.row = 0;
+                    let lines = self.content_lines_near_cursor(0);
+                    match key.code {
+                        KeyCode::Char('g') => {
+                            if let Some(n) = self.count.take() {
+                                self.cursor_pos.row = (n.saturating_sub(1)).min(lines.len().saturating_sub(1));
+                            } else {
+                                self.cursor_pos.row = 0;
+                            }
+                            self.cursor_pos.col = 0;
+                            self.clamp_col(&lines);
                         }
-                        self.cursor_pos.col = 0;
-                        self.clamp_col(&lines);
+                        KeyCode::Char('y') => self.yank_selection_with_location(&lines),
+                        _ => {}
                     }
                     self.count = None;
                 }
@@ -1926,7 +4179,7 @@ impl App {
         }
 
         let n = self.take_count();
-        let lines = self.content_lines();
+        let lines = self.content_lines_near_cursor(n);
         let total = lines.len();
         if total == 0 {
             return;
@@ -1948,19 +4201,25 @@ impl App {
                 self.cursor_pos.row = self.cursor_pos.row.saturating_sub(n);
                 self.clamp_col(&lines);
             }
-            KeyCode::Char('w') => {
+            KeyCode::Char(c @ ('w' | 'W')) => {
                 for _ in 0..n {
-                    self.move_word_forward(&lines);
+                    self.move_word_forward(&lines, c == 'W');
                 }
             }
-            KeyCode::Char('b') => {
+            KeyCode::Char(c @ ('b' | 'B')) => {
+                for _ in 0..n {
+                    self.move_word_backward(&lines, c == 'B');
+                }
+            }
+            KeyCode::Char(c @ ('e' | 'E')) => {
                 for _ in 0..n {
-                    self.move_word_backward(&lines);
+                    self.move_word_end(&lines, c == 'E');
                 }
             }
-            KeyCode::Char('e') => {
+            KeyCode::Char(c @ ('{' | '}')) => {
+                let lines = self.content_lines(0..total);
                 for _ in 0..n {
-                    self.move_word_end(&lines);
+                    self.move_paragraph(&lines, c == '}');
                 }
             }
             KeyCode::Char('0') => {
@@ -1988,11 +4247,33 @@ impl App {
                 }
             }
             KeyCode::Char('y') => {
+                if let Some(anchor) = self.visual_anchor {
+                    let (start, end) = self.ordered_selection(anchor);
+                    let rows = end.row - start.row;
+                    self.last_yank = Some(if self.diff_view_mode == DiffViewMode::VisualBlock {
+                        LastYank::Block { rows, cols: anchor.col.abs_diff(self.cursor_pos.col) }
+                    } else {
+                        LastYank::Visual {
+                            line_wise: self.diff_view_mode == DiffViewMode::VisualLine,
+                            rows,
+                            cols: if rows == 0 { end.col - start.col } else { end.col },
+                        }
+                    });
+                }
                 let text = self.yank_selection(&lines);
                 self.copy_to_clipboard(&text);
                 self.diff_view_mode = DiffViewMode::Normal;
                 self.visual_anchor = None;
             }
+            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if self.diff_view_mode == DiffViewMode::VisualBlock {
+                    self.diff_view_mode = DiffViewMode::Normal;
+                    self.visual_anchor = None;
+                } else {
+                    self.diff_view_mode = DiffViewMode::VisualBlock;
+                    self.visual_anchor = Some(self.cursor_pos);
+                }
+            }
             KeyCode::Char('v') => {
                 if self.diff_view_mode == DiffViewMode::Visual {
                     self.diff_view_mode = DiffViewMode::Normal;
@@ -2013,6 +4294,14 @@ impl App {
             }
             KeyCode::Char('/') => {
                 self.search.start(SearchOrigin::DiffView);
+                // Limit matches to the current selection
+                if let Some(anchor) = self.visual_anchor {
+                    let (start, end) = match self.diff_view_mode {
+                        DiffViewMode::VisualBlock => self.block_corners(anchor),
+                        _ => self.ordered_selection(anchor),
+                    };
+                    self.search.within = Some((start, end, self.diff_view_mode));
+                }
                 self.pending_key = None;
                 self.count = None;
             }
@@ -2034,17 +4323,22 @@ impl App {
     }
 
     fn copy_to_clipboard(&mut self, text: &str) {
+        let line_count = text.lines().count().max(1);
+        self.copy_with_message(
+            text,
+            format!("Yanked {line_count} line{}", if line_count == 1 { "" } else { "s" }),
+        );
+    }
+
+    /// Copy `text`, reporting `done` in the status bar on success.
+    fn copy_with_message(&mut self, text: &str, done: String) {
         if text.is_empty() {
             return;
         }
-        let line_count = text.lines().count().max(1);
         match arboard::Clipboard::new() {
             Ok(mut clip) => {
                 if clip.set_text(text).is_ok() {
-                    self.status_message = Some(format!(
-                        "Yanked {line_count} line{}",
-                        if line_count == 1 { "" } else { "s" }
-                    ));
+                    self.status_message = Some(done);
                 } else {
                     self.status_message = Some("Clipboard error".to_string());
                 }
@@ -2055,68 +4349,391 @@ impl App {
         }
     }
 
-    /// Build flat list of content strings for the current side of the diff.
-    /// Results are cached and reused until the file or side changes.
-    pub fn content_lines(&mut self) -> Vec<String> {
-        let file = match self.selected_file() {
-            Some(f) => f.clone(),
-            None => return Vec::new(),
+    /// `Y` — copy the selected file's diff as unified text
+    fn yank_file_diff(&mut self) {
+        let text = match self.selected_file() {
+            Some(f) if f.is_binary => {
+                self.status_message = Some("Binary file, nothing to copy".to_string());
+                return;
+            }
+            Some(f) => f.to_unified(),
+            None => return,
+        };
+        self.copy_to_clipboard(&text);
+    }
+
+    /// `Y` while a diff search is active — copy every line with a match, in
+    /// order. A context line matching on both sides is copied once.
+    fn yank_search_matches(&mut self) {
+        let Some(file) = self.shown_file() else {
+            return;
+        };
+        let mut left = Vec::with_capacity(file.row_count());
+        let mut right = Vec::with_capacity(file.row_count());
+        for hunk in &file.hunks {
+            left.push(Some(hunk.header.as_str()));
+            right.push(None);
+            for row in &hunk.rows {
+                left.push(row.left.as_ref().map(|l| l.content.as_str()));
+                right.push(row.right.as_ref().map(|l| l.content.as_str()));
+            }
+        }
+        let mut seen = HashSet::new();
+        let lines: Vec<&str> = self
+            .search
+            .matches
+            .iter()
+            .filter_map(|m| match m {
+                SearchMatch::DiffLine { row, side: DiffSide::Left, .. } => Some((*row, left.get(*row)?)),
+                SearchMatch::DiffLine { row, side: DiffSide::Right, .. } => Some((*row, right.get(*row)?)),
+                _ => None,
+            })
+            .filter_map(|(row, text)| text.filter(|text| seen.insert((row, *text))))
+            .collect();
+        if lines.is_empty() {
+            self.status_message = Some("No matching lines".to_string());
+            return;
+        }
+        let count = lines.len();
+        let text = lines.join("\n");
+        self.copy_with_message(
+            &text,
+            format!("Copied {count} matching line{}", if count == 1 { "" } else { "s" }),
+        );
+    }
+
+    /// `C` / `S` — copy the hunk under the cursor for pasting into a review
+    /// comment: as a fenced diff quote, or as a suggestion block of its new side.
+    fn yank_hunk(&mut self, suggestion: bool) {
+        let text = match self.selected_file() {
+            Some(f) if f.is_binary => {
+                self.status_message = Some("Binary file, nothing to copy".to_string());
+                return;
+            }
+            Some(_) => match self.cursor_hunk() {
+                Some(hunk) if suggestion => {
+                    format!("```suggestion\n{}\n```", hunk.new_side_lines().join("\n"))
+                }
+                Some(hunk) => format!("```diff\n{}\n```", hunk.to_unified_lines().join("\n")),
+                None => return,
+            },
+            None => return,
+        };
+        self.copy_to_clipboard(&text);
+    }
+
+    /// The whole hunk under the cursor, context rows included even while
+    /// they are hidden.
+    fn cursor_hunk(&self) -> Option<&DiffHunk> {
+        let idx = self.shown_file()?.hunk_index_at_row(self.cursor_pos.row)?;
+        self.selected_file()?.hunks.get(idx)
+    }
+
+    /// The selected file's path and the hunk under the cursor.
+    fn hunk_under_cursor(&self) -> Option<(PathBuf, DiffHunk)> {
+        let file = self.selected_file()?;
+        let hunk = self.cursor_hunk()?;
+        Some((file.raw_path.clone(), hunk.clone()))
+    }
+
+    /// `a` / `u` — stage the hunk under the cursor, or unstage it in the
+    /// staged view.
+    fn stage_hunk(&mut self, unstage: bool) {
+        if self.working_tree_only()
+            || self.conflict_under_cursor()
+            || self.forced_text_under_cursor()
+            || self.whitespace_hidden()
+            || self.index_diff_hidden()
+        {
+            return;
+        }
+        let Some((path, hunk)) = self.hunk_under_cursor() else {
+            return;
+        };
+        let result = match (unstage, self.diff_scope) {
+            (false, DiffScope::Staged) => {
+                self.status_message = Some("Hunk is already staged".to_string());
+                return;
+            }
+            (true, DiffScope::Staged) => self.repo.apply_hunk_to_index(&path, &hunk, true),
+            (true, _) => {
+                self.status_message = Some("Switch to the staged view (s) to unstage".to_string());
+                return;
+            }
+            (false, _) => self.repo.apply_hunk_to_index(&path, &hunk, false),
+        };
+        let done = if unstage { "Unstaged hunk" } else { "Staged hunk" };
+        if let Err(e) = self.after_hunk_apply(result, done) {
+            self.status_message = Some(format!("Refresh error: {e}"));
+        }
+    }
+
+    /// Report the outcome of staging or unstaging and reload the diff, keeping
+    /// the cursor near where it was.
+    fn after_hunk_apply(&mut self, result: Result<()>, done: &str) -> Result<()> {
+        if let Err(e) = result {
+            self.error_dialog = Some(ErrorDialogState {
+                title: "Cannot apply hunk".to_string(),
+                message: format!("{e:#}"),
+            });
+            return Ok(());
+        }
+        let cursor = self.cursor_pos;
+        self.refresh_diff()?;
+        let total = self.content_lines(0..0).len();
+        if total == 0 {
+            // Normal mode ignores every key on an empty diff, Esc included
+            self.diff_view_mode = DiffViewMode::Scroll;
+        }
+        let last = total.saturating_sub(1);
+        self.cursor_pos.row = cursor.row.min(last);
+        self.cursor_pos.col = 0;
+        self.scroll_to_cursor();
+        self.status_message = Some(done.to_string());
+        Ok(())
+    }
+
+    /// `K` in Normal mode — show who last changed the line under the cursor,
+    /// as of the revision that side of the diff shows.
+    fn show_line_blame(&mut self) {
+        let Some(file) = self.shown_file() else {
+            return;
         };
         let side = self.cursor_pos.side;
+        let line_no = file
+            .hunks
+            .iter()
+            .flat_map(|h| std::iter::once(None).chain(h.rows.iter().map(Some)))
+            .nth(self.cursor_pos.row)
+            .flatten()
+            .and_then(|row| match side {
+                DiffSide::Left => row.left.as_ref(),
+                DiffSide::Right => row.right.as_ref(),
+            })
+            .map(|line| line.line_no);
+        let Some(line_no) = line_no else {
+            self.status_message = Some("No line to blame here".to_string());
+            return;
+        };
+        let (path, rev) = match side {
+            DiffSide::Left => {
+                let old = match &self.compare_refs {
+                    Some((old, _)) => old.clone(),
+                    None => self.diff_base_ref.clone().unwrap_or_else(|| "HEAD".to_string()),
+                };
+                (PathBuf::from(file.old_path.as_deref().unwrap_or(&file.path)), Some(old))
+            }
+            DiffSide::Right => (
+                file.raw_path.clone(),
+                self.compare_refs.as_ref().map(|(_, new)| new.clone()),
+            ),
+        };
+
+        let key = (path, rev);
+        if let Some(hunks) = self.blame_cache.get(&key) {
+            let hunks = Rc::clone(hunks);
+            self.open_blame_popup(&hunks, line_no);
+            return;
+        }
+        // Blaming walks the file's history, which can take seconds
+        let (tx, rx) = mpsc::channel();
+        self.blame_rx = Some(rx);
+        self.status_message = Some("Blaming...".to_string());
+        let workdir = self.repo.workdir().to_path_buf();
+        std::thread::spawn(move || {
+            let result =
+                Repo::discover(&workdir).and_then(|repo| repo.blame(&key.0, key.1.as_deref()));
+            let _ = tx.send(BlameDone { key, line_no, result });
+        });
+    }
 
-        // Return cached result if still valid
-        if let Some((ref path, cached_side, ref lines)) = self.content_lines_cache {
-            if *path == file.path && cached_side == side {
-                return lines.clone();
+    /// Cache a finished blame and show its popup, unless the diff has lost
+    /// focus since it was asked for.
+    pub fn drain_blame(&mut self) {
+        let Some(rx) = &self.blame_rx else {
+            return;
+        };
+        let done = match rx.try_recv() {
+            Ok(done) => done,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.blame_rx = None;
+                return;
+            }
+        };
+        self.blame_rx = None;
+        let hunks = match done.result {
+            Ok(hunks) => Rc::new(hunks),
+            Err(e) => {
+                self.status_message = Some(format!("Blame failed: {e:#}"));
+                return;
             }
+        };
+        self.blame_cache.insert(done.key, Rc::clone(&hunks));
+        if self.status_message.as_deref() == Some("Blaming...") {
+            self.status_message = None;
         }
+        if self.view_mode == ViewMode::Git && self.focused_pane == FocusedPane::DiffView {
+            self.open_blame_popup(&hunks, done.line_no);
+        }
+    }
 
-        let mut lines = Vec::new();
+    fn open_blame_popup(&mut self, hunks: &[BlameHunk], line_no: u32) {
+        let commit = hunks
+            .iter()
+            .find(|h| (h.start..h.start + h.lines).contains(&line_no))
+            .and_then(|h| h.commit.clone());
+        self.blame_popup = Some(BlamePopup { line_no, commit });
+    }
+
+    /// New-file line to open the editor at: the cursor's in Normal mode, else
+    /// the top of the viewport. Rows with no new-side line (hunk headers,
+    /// deletions) use the next row that has one.
+    pub fn editor_line(&self) -> Option<u32> {
+        let file = self.shown_file()?;
+        let start = match self.diff_view_mode {
+            DiffViewMode::Normal => self.cursor_pos.row,
+            _ => self.diff_scroll_y as usize,
+        };
+        let mut last = None;
+        let mut row = 0;
         for hunk in &file.hunks {
-            lines.push(hunk.header.clone());
-            for row in &hunk.rows {
-                let side_line = match side {
-                    DiffSide::Left => row.left.as_ref(),
-                    DiffSide::Right => row.right.as_ref(),
-                };
-                match side_line {
-                    Some(sl) => lines.push(sl.content.clone()),
-                    None => lines.push(String::new()),
+            row += 1; // hunk header
+            for diff_row in &hunk.rows {
+                if let Some(right) = &diff_row.right {
+                    if row >= start {
+                        return Some(right.line_no);
+                    }
+                    last = Some(right.line_no);
                 }
+                row += 1;
+            }
+        }
+        last
+    }
+
+    /// Content strings of the current side of the shown file, built for
+    /// `rows` (clamped to the file). Shared until the file or side changes,
+    /// or rows outside the built window are asked for.
+    pub fn content_lines(&mut self, rows: Range<usize>) -> Rc<ContentLines> {
+        let side = self.cursor_pos.side;
+        let Some(file) = self.selected_file() else {
+            return Rc::new(ContentLines::default());
+        };
+        if let Some(cached) = &self.content_lines_cache {
+            if cached.path == file.path && cached.side == Some(side) && cached.covers(&rows) {
+                return Rc::clone(cached);
             }
         }
-        self.content_lines_cache = Some((file.path.clone(), side, lines.clone()));
+        let lines = Rc::new(ContentLines::build(file, side, self.changes_only, rows));
+        self.content_lines_cache = Some(Rc::clone(&lines));
         lines
     }
 
-    fn current_line_len(&self, lines: &[String]) -> usize {
+    /// `content_lines` for the rows a motion repeated `count` times can reach
+    /// from the cursor and the Visual anchor, plus the visible window.
+    fn content_lines_near_cursor(&mut self, count: usize) -> Rc<ContentLines> {
+        const MARGIN: usize = 100;
+        let row = self.cursor_pos.row;
+        let anchor = match self.diff_view_mode {
+            DiffViewMode::Visual | DiffViewMode::VisualLine | DiffViewMode::VisualBlock => {
+                self.visual_anchor.map_or(row, |a| a.row)
+            }
+            _ => row,
+        };
+        let top = self.diff_scroll_y as usize;
+        let bottom = top + self.diff_view_height as usize;
+        let reach = count.saturating_add(MARGIN);
+        let start = row.min(anchor).min(top).saturating_sub(reach);
+        let end = row.max(anchor).max(bottom).saturating_add(reach).saturating_add(1);
+        self.content_lines(start..end)
+    }
+
+    fn current_line_len(&self, lines: &ContentLines) -> usize {
         lines
             .get(self.cursor_pos.row)
             .map(|l| l.chars().count().max(1))
             .unwrap_or(1)
     }
 
-    fn clamp_col(&mut self, lines: &[String]) {
+    fn clamp_col(&mut self, lines: &ContentLines) {
         let len = self.current_line_len(lines);
         if self.cursor_pos.col >= len {
             self.cursor_pos.col = len.saturating_sub(1);
         }
     }
 
+    /// Text of display row `row` on `side` of the selected file (hunk
+    /// headers included), for mapping char columns to screen columns.
+    fn row_text(&self, row: usize, side: DiffSide) -> Option<String> {
+        let file = self.shown_file()?;
+        let mut start = 0;
+        for hunk in &file.hunks {
+            if row == start {
+                return Some(hunk.header.clone());
+            }
+            if let Some(r) = hunk.rows.get(row.wrapping_sub(start + 1)) {
+                let side_line = match side {
+                    DiffSide::Left => r.left.as_ref(),
+                    DiffSide::Right => r.right.as_ref(),
+                };
+                return Some(side_line.map(|l| l.content.clone()).unwrap_or_default());
+            }
+            start += hunk.rows.len() + 1;
+        }
+        None
+    }
+
+    /// Screen column of char column `col` in display row `row` on `side`.
+    fn screen_col(&self, row: usize, side: DiffSide, col: usize) -> usize {
+        match self.row_text(row, side) {
+            Some(text) => display_col(&text, col, self.config.tabstop),
+            None => col,
+        }
+    }
+
+    /// Scroll horizontally so char columns `start..end` of display row `row`
+    /// are visible, roughly centered, unless they already are.
+    fn reveal_cols(&mut self, row: usize, side: DiffSide, start: usize, end: usize) {
+        let start = self.screen_col(row, side, start);
+        let end = self.screen_col(row, side, end);
+        let width = self.diff_content_width as usize;
+        let scroll_x = self.diff_scroll_x as usize;
+        if width == 0 || (start >= scroll_x && end <= scroll_x + width) {
+            return;
+        }
+        self.diff_scroll_x = start.saturating_sub(width / 2).min(u16::MAX as usize) as u16;
+    }
+
     fn scroll_to_cursor(&mut self) {
         let row = self.cursor_pos.row as u16;
         let height = self.diff_view_height;
-        if height == 0 {
-            return;
+        if height > 0 {
+            if row < self.diff_scroll_y {
+                self.diff_scroll_y = row;
+            } else if row >= self.diff_scroll_y + height {
+                self.diff_scroll_y = row - height + 1;
+            }
         }
-        if row < self.diff_scroll_y {
-            self.diff_scroll_y = row;
-        } else if row >= self.diff_scroll_y + height {
-            self.diff_scroll_y = row - height + 1;
+
+        // Keep the cursor's cells (two for wide chars) inside
+        // [diff_scroll_x, diff_scroll_x + width)
+        let CursorPos { row, col, side } = self.cursor_pos;
+        let start = self.screen_col(row, side, col);
+        let end = self.screen_col(row, side, col + 1).max(start + 1);
+        let width = self.diff_content_width as usize;
+        if width > 0 {
+            if start < self.diff_scroll_x as usize {
+                self.diff_scroll_x = start.min(u16::MAX as usize) as u16;
+            } else if end > self.diff_scroll_x as usize + width {
+                self.diff_scroll_x = (end.saturating_sub(width)).min(u16::MAX as usize) as u16;
+            }
         }
     }
 
-    fn move_word_forward(&mut self, lines: &[String]) {
+    /// Move to the start of the next word. `big_word` selects vim's `W`
+    /// (whitespace-delimited) instead of `w` (keyword/punctuation runs).
+    fn move_word_forward(&mut self, lines: &ContentLines, big_word: bool) {
         let total = lines.len();
         if total == 0 {
             return;
@@ -2125,9 +4742,14 @@ impl App {
         let mut col = self.cursor_pos.col;
         let mut row = self.cursor_pos.row;
 
-        // Skip current word chars
-        while col < line.len() && !line[col].is_whitespace() {
-            col += 1;
+        // Skip the rest of the current word (same char class)
+        if let Some(&c) = line.get(col) {
+            let class = char_class(c, big_word);
+            if class != CharClass::Whitespace {
+                while col < line.len() && char_class(line[col], big_word) == class {
+                    col += 1;
+                }
+            }
         }
         // Skip whitespace
         while col < line.len() && line[col].is_whitespace() {
@@ -2147,7 +4769,8 @@ impl App {
         self.cursor_pos.col = col.min(self.line_len_at(lines, row).saturating_sub(1));
     }
 
-    fn move_word_backward(&mut self, lines: &[String]) {
+    /// Move to the start of the previous word (`b`, or `B` when `big_word`).
+    fn move_word_backward(&mut self, lines: &ContentLines, big_word: bool) {
         if lines.is_empty() {
             return;
         }
@@ -2168,18 +4791,22 @@ impl App {
         // Move back one
         col = col.saturating_sub(1);
         // Skip whitespace backward
-        while col > 0 && line.get(col).map_or(false, |c| c.is_whitespace()) {
+        while col > 0 && line.get(col).is_some_and(|c| c.is_whitespace()) {
             col -= 1;
         }
-        // Skip word chars backward
-        while col > 0 && line.get(col - 1).map_or(false, |c| !c.is_whitespace()) {
-            col -= 1;
+        // Skip chars of the same class backward
+        if let Some(&c) = line.get(col) {
+            let class = char_class(c, big_word);
+            while col > 0 && line.get(col - 1).is_some_and(|&p| char_class(p, big_word) == class) {
+                col -= 1;
+            }
         }
         self.cursor_pos.row = row;
         self.cursor_pos.col = col;
     }
 
-    fn move_word_end(&mut self, lines: &[String]) {
+    /// Move to the end of the current/next word (`e`, or `E` when `big_word`).
+    fn move_word_end(&mut self, lines: &ContentLines, big_word: bool) {
         let total = lines.len();
         if total == 0 {
             return;
@@ -2199,19 +4826,65 @@ impl App {
         while col < cur_line.len() && cur_line[col].is_whitespace() {
             col += 1;
         }
-        // Move to end of word
-        while col + 1 < cur_line.len() && !cur_line[col + 1].is_whitespace() {
-            col += 1;
+        // Move to end of the run of same-class chars
+        if let Some(&c) = cur_line.get(col) {
+            let class = char_class(c, big_word);
+            while col + 1 < cur_line.len() && char_class(cur_line[col + 1], big_word) == class {
+                col += 1;
+            }
         }
         self.cursor_pos.row = row;
         self.cursor_pos.col = col.min(self.line_len_at(lines, row).saturating_sub(1));
     }
 
-    fn line_len_at(&self, lines: &[String], row: usize) -> usize {
+    /// Row indices of hunk headers in the selected file's content lines.
+    fn hunk_header_rows(&self) -> Vec<usize> {
+        let mut rows = Vec::new();
+        if let Some(file) = self.shown_file() {
+            let mut row = 0;
+            for hunk in &file.hunks {
+                rows.push(row);
+                row += hunk.rows.len() + 1;
+            }
+        }
+        rows
+    }
+
+    /// Move to the next (`}`) or previous (`{`) blank line. Hunk headers
+    /// count as paragraph boundaries too.
+    fn move_paragraph(&mut self, lines: &ContentLines, forward: bool) {
+        if lines.is_empty() {
+            return;
+        }
+        let headers = self.hunk_header_rows();
+        let is_boundary = |r: usize| lines[r].trim().is_empty() || headers.contains(&r);
+        let last = lines.len() - 1;
+        let mut row = self.cursor_pos.row.min(last);
+        if forward {
+            // Step off the current boundary run, then cross the paragraph
+            while row < last && is_boundary(row) {
+                row += 1;
+            }
+            while row < last && !is_boundary(row) {
+                row += 1;
+            }
+        } else {
+            while row > 0 && is_boundary(row) {
+                row -= 1;
+            }
+            while row > 0 && !is_boundary(row) {
+                row -= 1;
+            }
+        }
+        self.cursor_pos.row = row;
+        self.cursor_pos.col = 0;
+    }
+
+    fn line_len_at(&self, lines: &ContentLines, row: usize) -> usize {
         lines.get(row).map(|l| l.chars().count().max(1)).unwrap_or(1)
     }
 
-    fn yank_selection(&self, lines: &[String]) -> String {
+    fn yank_selection(&self, lines: &ContentLines) -> String {
         let anchor = match self.visual_anchor {
             Some(a) => a,
             None => return String::new(),
@@ -2258,23 +4931,92 @@ impl App {
                 }
                 result
             }
+            DiffViewMode::VisualBlock => {
+                let (start, end) = self.block_corners(anchor);
+                extract_block(lines, start, end)
+            }
             _ => String::new(),
         }
     }
 
-    fn ordered_selection(&self, anchor: CursorPos) -> (CursorPos, CursorPos) {
-        if anchor.row < self.cursor_pos.row
-            || (anchor.row == self.cursor_pos.row && anchor.col <= self.cursor_pos.col)
-        {
-            (anchor, self.cursor_pos)
-        } else {
-            (self.cursor_pos, anchor)
-        }
-    }
-
-    fn apply_text_object(&mut self, prefix: char, key: KeyCode, lines: &[String]) {
-        let inner = prefix == 'i';
-        match key {
+    /// `gy` — copy the selection with each line prefixed by `path:line_no:`,
+    /// like grep output. Rows without a line on the selected side (hunk
+    /// headers, the blank side of an addition or deletion) are left out.
+    fn yank_selection_with_location(&mut self, lines: &ContentLines) {
+        let Some(anchor) = self.visual_anchor else {
+            return;
+        };
+        let Some(path) = self.selected_file().map(|f| f.path.clone()) else {
+            return;
+        };
+        let start_row = anchor.row.min(self.cursor_pos.row);
+        let line_nos = self.side_line_numbers(self.cursor_pos.side);
+        let text = self
+            .yank_selection(lines)
+            .split('\n')
+            .zip(start_row..)
+            .filter_map(|(text, row)| {
+                let line_no = line_nos.get(row).copied().flatten()?;
+                Some(format!("{path}:{line_no}:{text}"))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.copy_to_clipboard(&text);
+        self.diff_view_mode = DiffViewMode::Normal;
+        self.visual_anchor = None;
+    }
+
+    /// Line number on `side` for each row of the selected file, in the same
+    /// order as `content_lines`; None for hunk headers and blank sides.
+    fn side_line_numbers(&self, side: DiffSide) -> Vec<Option<u32>> {
+        let Some(file) = self.shown_file() else {
+            return Vec::new();
+        };
+        let mut line_nos = Vec::with_capacity(file.row_count());
+        for hunk in &file.hunks {
+            line_nos.push(None);
+            for row in &hunk.rows {
+                let side_line = match side {
+                    DiffSide::Left => row.left.as_ref(),
+                    DiffSide::Right => row.right.as_ref(),
+                };
+                line_nos.push(side_line.map(|sl| sl.line_no));
+            }
+        }
+        line_nos
+    }
+
+    /// Top-left and bottom-right corners of the block between `anchor` and
+    /// the cursor.
+    fn block_corners(&self, anchor: CursorPos) -> (CursorPos, CursorPos) {
+        let side = self.cursor_pos.side;
+        (
+            CursorPos {
+                row: anchor.row.min(self.cursor_pos.row),
+                col: anchor.col.min(self.cursor_pos.col),
+                side,
+            },
+            CursorPos {
+                row: anchor.row.max(self.cursor_pos.row),
+                col: anchor.col.max(self.cursor_pos.col),
+                side,
+            },
+        )
+    }
+
+    fn ordered_selection(&self, anchor: CursorPos) -> (CursorPos, CursorPos) {
+        if anchor.row < self.cursor_pos.row
+            || (anchor.row == self.cursor_pos.row && anchor.col <= self.cursor_pos.col)
+        {
+            (anchor, self.cursor_pos)
+        } else {
+            (self.cursor_pos, anchor)
+        }
+    }
+
+    fn apply_text_object(&mut self, prefix: char, key: KeyCode, lines: &ContentLines) {
+        let inner = prefix == 'i';
+        match key {
             KeyCode::Char('w') => self.select_text_object_word(inner, lines),
             KeyCode::Char('"') => self.select_text_object_delim(inner, '"', '"', lines),
             KeyCode::Char('\'') => self.select_text_object_delim(inner, '\'', '\'', lines),
@@ -2288,7 +5030,7 @@ impl App {
         }
     }
 
-    fn select_text_object_word(&mut self, inner: bool, lines: &[String]) {
+    fn select_text_object_word(&mut self, inner: bool, lines: &ContentLines) {
         if let Some(line) = lines.get(self.cursor_pos.row) {
             let chars: Vec<char> = line.chars().collect();
             let col = self.cursor_pos.col.min(chars.len().saturating_sub(1));
@@ -2315,7 +5057,7 @@ impl App {
         }
     }
 
-    fn select_text_object_delim(&mut self, inner: bool, open: char, close: char, lines: &[String]) {
+    fn select_text_object_delim(&mut self, inner: bool, open: char, close: char, lines: &ContentLines) {
         if let Some(line) = lines.get(self.cursor_pos.row) {
             let chars: Vec<char> = line.chars().collect();
             let col = self.cursor_pos.col.min(chars.len().saturating_sub(1));
@@ -2353,12 +5095,52 @@ impl App {
     fn re_search_on_file_change(&mut self) {
         if self.search.origin == SearchOrigin::DiffView && self.search.query.is_some() {
             self.search.reset_matches();
+            self.search.within = None;
             self.content_lines_cache = None;
             let query = self.search.query.clone().unwrap();
             self.search_diff_view(&query);
         }
     }
 
+    /// `match x/y` for the active search in `origin`, with `x` taken from the
+    /// cursor / selection (`-` when it isn't on a match).
+    pub fn search_match_label(&self, origin: SearchOrigin) -> Option<String> {
+        if self.search.origin != origin || self.search.query.is_none() || self.search.matches.is_empty() {
+            return None;
+        }
+        let cursor = self.cursor_pos;
+        let in_scroll = self.diff_view_mode == DiffViewMode::Scroll;
+        let current = self.search.matches.iter().position(|m| match m {
+            SearchMatch::DiffLine { row, col_start, col_end, side } => {
+                !in_scroll
+                    && *row == cursor.row
+                    && *side == cursor.side
+                    && (*col_start..*col_end).contains(&cursor.col)
+            }
+            SearchMatch::TreeEntry(idx) => *idx == self.selected_tree_idx,
+            SearchMatch::CommitEntry(idx) => *idx == self.git_log.selected_idx,
+            SearchMatch::BranchEntry(idx) => *idx == self.branch_list.selected_idx,
+            SearchMatch::ReflogEntry(idx) => *idx == self.reflog.selected_idx,
+            SearchMatch::DetailLine { .. } | SearchMatch::UnifiedLine { .. } => false,
+        });
+        // Scroll mode and the detail view have no cursor; fall back to the
+        // last jumped-to match
+        let current = if (in_scroll && origin == SearchOrigin::DiffView)
+            || origin == SearchOrigin::GitHubDetail
+            || origin == SearchOrigin::UnifiedView
+        {
+            self.search.current_match_idx
+        } else {
+            current
+        };
+        let total = self.search.matches.len();
+        let more = if self.search.truncated { "+" } else { "" };
+        Some(match current {
+            Some(i) => format!("match {}/{total}{more}", i + 1),
+            None => format!("match -/{total}{more}"),
+        })
+    }
+
     fn handle_search_input_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Enter => {
@@ -2387,6 +5169,10 @@ impl App {
             KeyCode::Down | KeyCode::Char('n') if key.code == KeyCode::Down || key.modifiers.contains(KeyModifiers::CONTROL) => {
                 self.search.history_next();
             }
+            KeyCode::Char(_) if self.search.input.chars().count() >= SEARCH_INPUT_MAX => {
+                self.status_message =
+                    Some(format!("Search pattern is limited to {SEARCH_INPUT_MAX} characters"));
+            }
             KeyCode::Char(c) => {
                 self.search.input.push(c);
                 self.search.history_idx = None;
@@ -2395,9 +5181,562 @@ impl App {
         }
     }
 
+    fn handle_command_line_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(input) = self.command_line.as_mut() else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let input = self.command_line.take().unwrap_or_default();
+                self.run_command(&input)?;
+            }
+            KeyCode::Esc => {
+                self.command_line = None;
+            }
+            // `:base <partial>` completes from the ref picker
+            KeyCode::Tab if input == "base" || input.starts_with("base ") => {
+                let query = input["base".len()..].trim().to_string();
+                self.command_line = None;
+                self.open_base_picker(query);
+            }
+            // Like vim, backspacing over the `:` closes the line
+            KeyCode::Backspace if input.pop().is_none() => {
+                self.command_line = None;
+            }
+            KeyCode::Char(c) => {
+                input.push(c);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn run_command(&mut self, input: &str) -> Result<()> {
+        let command = match Command::parse(input) {
+            Ok(Some(command)) => command,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                self.status_message = Some(format!("{e}"));
+                return Ok(());
+            }
+        };
+        match command {
+            Command::Quit { force: false } => self.request_quit(),
+            Command::Quit { force: true } => self.should_quit = true,
+            Command::Refresh => self.refresh_all()?,
+            Command::Base(spec) => {
+                if let Err(e) = self.set_diff_base(spec) {
+                    self.status_message = Some(e);
+                }
+            }
+            Command::Compare(refs) => {
+                let error = refs.as_ref().and_then(|(old, new)| self.compare_refs_error(old, new));
+                match error {
+                    Some(error) => self.status_message = Some(error),
+                    None => self.set_compare_refs(refs),
+                }
+            }
+            Command::Goto(line) => self.goto_line(line),
+            Command::Theme(None) => {
+                self.status_message = Some(format!(
+                    "Theme: {} (available: {})",
+                    self.highlighter.theme_name(),
+                    SyntaxHighlighter::theme_names().join(", ")
+                ));
+            }
+            Command::Theme(Some(name)) => match self.apply_theme(&name) {
+                Ok(()) => self.status_message = Some(format!("Theme: {name}")),
+                Err(e) => self.status_message = Some(format!("{e}")),
+            },
+            Command::Reload => {
+                self.status_message = Some(match self.reload_config() {
+                    Ok(()) => "Config reloaded".to_string(),
+                    Err(e) => format!("{e:#}"),
+                });
+            }
+            Command::Help => self.open_help(),
+        }
+        Ok(())
+    }
+
+    /// Switch the syntax theme and re-highlight with it.
+    fn apply_theme(&mut self, name: &str) -> Result<()> {
+        self.highlighter.set_theme(name)?;
+        self.highlight_cache = None;
+        self.bg_highlights.clear();
+        self.bg_highlight_rx = None;
+        self.spawn_bg_highlight();
+        Ok(())
+    }
+
+    /// `:reload` — re-read the config files and apply them. The tick rate,
+    /// watcher debounce, `image_preview`, `base` and `view` are only read at
+    /// startup, so they keep their current values until a restart.
+    fn reload_config(&mut self) -> Result<()> {
+        let mut config = Config::load(self.repo.workdir())?;
+        config.tick_rate_ms = self.config.tick_rate_ms;
+        config.watch_debounce_ms = self.config.watch_debounce_ms;
+        config.image_preview = self.config.image_preview;
+        config.base = self.config.base.clone();
+        config.view = self.config.view;
+
+        // Checked first so a bad theme leaves everything as it was
+        self.apply_theme(config.theme.as_deref().unwrap_or(DEFAULT_THEME))
+            .context("Invalid `theme` in config")?;
+        self.diff_palette = config.diff_colors;
+        self.github.set_limits(config.issue_limit, config.pr_limit);
+        self.repo.set_ignore_whitespace(config.ignore_whitespace);
+        self.config = config;
+        // Picks up the log / reflog limits and whitespace setting
+        self.refresh_all()
+    }
+
+    /// Compare the working tree against `spec`, or HEAD when None. A ref
+    /// that doesn't resolve to a tree is refused without changing anything.
+    fn set_diff_base(&mut self, spec: Option<String>) -> std::result::Result<(), String> {
+        if let Some(spec) = &spec {
+            let resolved = self
+                .repo
+                .inner()
+                .revparse_single(spec)
+                .and_then(|o| o.peel_to_tree());
+            if resolved.is_err() {
+                return Err(format!("Unknown ref: {spec}"));
+            }
+        }
+        self.diff_base_label = spec.as_deref().map(|s| self.repo.describe_ref(s));
+        self.diff_base_ref = spec;
+        self.compare_refs = None;
+        if let Err(e) = self.refresh_diff() {
+            self.status_message = Some(format!("Diff error: {e}"));
+        }
+        Ok(())
+    }
+
+    fn open_base_picker(&mut self, query: String) {
+        self.count = None;
+        self.base_picker = Some(BasePickerState {
+            query,
+            refs: self.repo.list_refs(),
+            matches: Vec::new(),
+            selected: 0,
+            error: None,
+        });
+        self.update_base_picker();
+    }
+
+    /// Re-rank the refs against the picker's query, keeping list order on ties.
+    fn update_base_picker(&mut self) {
+        let Some(picker) = self.base_picker.as_mut() else {
+            return;
+        };
+        let mut scored: Vec<(i64, usize, Vec<usize>)> = picker
+            .refs
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, name)| {
+                let (score, positions) = fuzzy_match(&picker.query, name)?;
+                Some((score, idx, positions))
+            })
+            .collect();
+        scored.sort_by_key(|(score, idx, _)| (std::cmp::Reverse(*score), *idx));
+        picker.matches = scored.into_iter().map(|(_, idx, pos)| (idx, pos)).collect();
+        picker.selected = 0;
+        picker.error = None;
+    }
+
+    fn handle_base_picker_key(&mut self, key: KeyEvent) {
+        let Some(picker) = self.base_picker.as_mut() else {
+            return;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.base_picker = None,
+            KeyCode::Char('c') if ctrl => self.should_quit = true,
+            KeyCode::Down | KeyCode::Tab => {
+                picker.selected = (picker.selected + 1).min(picker.matches.len().saturating_sub(1));
+            }
+            KeyCode::Char('n' | 'j') if ctrl => {
+                picker.selected = (picker.selected + 1).min(picker.matches.len().saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::BackTab => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Char('p' | 'k') if ctrl => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Backspace => {
+                picker.query.pop();
+                self.update_base_picker();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                picker.query.push(c);
+                self.update_base_picker();
+            }
+            KeyCode::Enter => {
+                // With nothing listed the query itself is tried, for hashes
+                // and `HEAD~12`-style specs
+                let spec = match picker.matches.get(picker.selected) {
+                    Some((idx, _)) => Some(picker.refs[*idx].clone()),
+                    None => Some(picker.query.trim().to_string()).filter(|q| !q.is_empty()),
+                };
+                match self.set_diff_base(spec) {
+                    Ok(()) => self.base_picker = None,
+                    Err(e) => {
+                        if let Some(picker) = self.base_picker.as_mut() {
+                            picker.error = Some(e);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Report that an operation needs the working tree. True while comparing refs.
+    fn working_tree_only(&mut self) -> bool {
+        if self.compare_refs.is_some() {
+            self.status_message = Some("Not available while comparing refs".to_string());
+        }
+        self.compare_refs.is_some()
+    }
+
+    /// Hunks are applied by their position in the index diffs, so the
+    /// diff on screen must be one of them: workdir vs index (unstaged) or
+    /// HEAD vs index (staged, no base).
+    fn index_diff_hidden(&mut self) -> bool {
+        let message = match (self.diff_scope, &self.diff_base_ref) {
+            (DiffScope::All, _) => "Switch to the unstaged or staged view (s) to stage hunks",
+            (DiffScope::Staged, Some(_)) => "Not available with a diff base",
+            (DiffScope::Staged | DiffScope::Unstaged, _) => return false,
+        };
+        self.status_message = Some(message.to_string());
+        true
+    }
+
+    /// Hunks diffed with whitespace ignored don't match the file byte for
+    /// byte, so they can't be applied to the index or working tree.
+    fn whitespace_hidden(&mut self) -> bool {
+        let hidden = self.repo.ignores_whitespace();
+        if hidden {
+            self.status_message =
+                Some("Not available while whitespace changes are hidden".to_string());
+        }
+        hidden
+    }
+
+    /// A file forced to text has its control characters replaced for display,
+    /// so its hunks no longer match the file and can't be applied.
+    fn forced_text_under_cursor(&mut self) -> bool {
+        let forced = self.selected_file().is_some_and(|f| self.forced_text.contains(&f.path));
+        if forced {
+            self.status_message = Some("Not available on a file forced to text (T)".to_string());
+        }
+        forced
+    }
+
+    /// Hunks of an unmerged file can't be staged or unstaged; the index
+    /// holds the conflict stages rather than a single blob.
+    fn conflict_under_cursor(&mut self) -> bool {
+        let conflicted = self.selected_file().is_some_and(|f| f.status == FileStatus::Conflicted);
+        if conflicted {
+            self.status_message = Some("Resolve the conflict in the editor (e) first".to_string());
+        }
+        conflicted
+    }
+
+    fn open_file_finder(&mut self) {
+        if self.diff_state.files.is_empty() {
+            self.status_message = Some("No changed files".to_string());
+            return;
+        }
+        self.count = None;
+        self.file_finder = Some(FileFinderState {
+            query: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+        });
+        self.update_file_finder();
+    }
+
+    /// Re-rank the changed files against the finder's query.
+    fn update_file_finder(&mut self) {
+        let Some(finder) = self.file_finder.as_mut() else {
+            return;
+        };
+        let mut scored: Vec<(i64, usize, Vec<usize>)> = self
+            .diff_state
+            .files
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, file)| {
+                let (score, positions) = fuzzy_match(&finder.query, &file.path)?;
+                Some((score, idx, positions))
+            })
+            .collect();
+        // Best score first, then shorter paths, then tree order
+        let files = &self.diff_state.files;
+        scored.sort_by_key(|(score, idx, _)| {
+            (std::cmp::Reverse(*score), files[*idx].path.len(), *idx)
+        });
+        finder.matches = scored.into_iter().map(|(_, idx, pos)| (idx, pos)).collect();
+        finder.selected = 0;
+    }
+
+    fn handle_file_finder_key(&mut self, key: KeyEvent) {
+        let Some(finder) = self.file_finder.as_mut() else {
+            return;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.file_finder = None,
+            KeyCode::Char('c') if ctrl => self.should_quit = true,
+            KeyCode::Down | KeyCode::Tab => {
+                finder.selected = (finder.selected + 1).min(finder.matches.len().saturating_sub(1));
+            }
+            KeyCode::Char('n' | 'j') if ctrl => {
+                finder.selected = (finder.selected + 1).min(finder.matches.len().saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::BackTab => finder.selected = finder.selected.saturating_sub(1),
+            KeyCode::Char('p' | 'k') if ctrl => finder.selected = finder.selected.saturating_sub(1),
+            KeyCode::Backspace => {
+                finder.query.pop();
+                self.update_file_finder();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                finder.query.push(c);
+                self.update_file_finder();
+            }
+            KeyCode::Enter => {
+                let file_idx = finder.matches.get(finder.selected).map(|(idx, _)| *idx);
+                self.file_finder = None;
+                if let Some(file_idx) = file_idx {
+                    self.jump_to_file(file_idx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Remember the tree layout for the next run. Directories that no longer
+    /// hold a changed file are dropped rather than kept forever.
+    pub fn save_session(&self) -> Result<()> {
+        let live: HashSet<&str> = self
+            .diff_state
+            .files
+            .iter()
+            .flat_map(|f| Path::new(&f.path).ancestors().skip(1))
+            .filter_map(|dir| dir.to_str())
+            .collect();
+        let mut collapsed_dirs: Vec<String> = self
+            .collapsed_dirs
+            .iter()
+            .filter(|dir| live.contains(dir.as_str()))
+            .cloned()
+            .collect();
+        collapsed_dirs.sort();
+        let sorted = |numbers: &HashSet<u64>| {
+            let mut numbers: Vec<u64> = numbers.iter().copied().collect();
+            numbers.sort_unstable();
+            numbers
+        };
+        Session {
+            collapsed_dirs,
+            pinned_issues: sorted(&self.github.pinned_issues),
+            pinned_prs: sorted(&self.github.pinned_prs),
+            view: Some(self.view_mode),
+            focused_pane: Some(self.focused_pane),
+            // The detail isn't reloaded on launch, so reopen on its list
+            gh_focused_pane: Some(match self.github.focused_pane {
+                GhFocusedPane::Detail => self.github.previous_pane,
+                pane => pane,
+            }),
+        }
+        .save(self.repo.workdir())
+    }
+
+    /// Focus the panes saved by the last session, unless they would open on
+    /// nothing: the diff needs a file to show and the log a commit.
+    fn restore_focus(&mut self, pane: Option<FocusedPane>, gh_pane: Option<GhFocusedPane>) {
+        match pane {
+            Some(FocusedPane::DiffView) => {
+                let first_file = self
+                    .build_tree_entries()
+                    .iter()
+                    .position(|e| matches!(e, TreeEntry::File { .. }));
+                if let Some(idx) = first_file {
+                    self.selected_tree_idx = idx;
+                    self.focused_pane = FocusedPane::DiffView;
+                }
+            }
+            Some(FocusedPane::GitLog) if self.git_log.commits.is_empty() => {}
+            Some(pane) => self.focused_pane = pane,
+            None => {}
+        }
+        if let Some(pane @ (GhFocusedPane::IssueList | GhFocusedPane::PrList)) = gh_pane {
+            self.github.focused_pane = pane;
+            self.github.previous_pane = pane;
+        }
+    }
+
+    /// Switch between the Git and GitHub views, loading GitHub data on first use.
+    pub fn set_view_mode(&mut self, mode: ViewMode) {
+        self.view_mode = mode;
+        if mode == ViewMode::GitHub {
+            self.github.initialize();
+        }
+    }
+
+    /// Select a file in the tree, expanding its directories, and show its diff.
+    fn jump_to_file(&mut self, file_idx: usize) {
+        let Some(path) = self.diff_state.files.get(file_idx).map(|f| f.path.clone()) else {
+            return;
+        };
+        for dir in std::path::Path::new(&path).ancestors().skip(1) {
+            self.collapsed_dirs.remove(dir.to_string_lossy().as_ref());
+        }
+        let entry = self.build_tree_entries().iter().position(
+            |e| matches!(e, TreeEntry::File { file_idx: idx, .. } if *idx == file_idx),
+        );
+        if let Some(entry) = entry {
+            self.save_file_view();
+            self.selected_tree_idx = entry;
+            self.restore_file_view();
+            self.re_search_on_file_change();
+        }
+        self.set_focus(FocusedPane::DiffView);
+    }
+
+    /// `D` — open the ref picker, prefilled with the refs being compared, or
+    /// the diff base and HEAD.
+    fn open_ref_picker(&mut self) {
+        let fields = match &self.compare_refs {
+            Some((old, new)) => [old.clone(), new.clone()],
+            None => [
+                self.diff_base_ref.clone().unwrap_or_default(),
+                "HEAD".to_string(),
+            ],
+        };
+        self.ref_picker = Some(RefPickerState {
+            fields,
+            active: 0,
+            error: None,
+        });
+    }
+
+    fn handle_ref_picker_key(&mut self, key: KeyEvent) {
+        let Some(picker) = self.ref_picker.as_mut() else {
+            return;
+        };
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.should_quit = true;
+            return;
+        }
+        match key.code {
+            KeyCode::Esc => self.ref_picker = None,
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+                picker.active = 1 - picker.active;
+            }
+            KeyCode::Backspace => {
+                picker.fields[picker.active].pop();
+                picker.error = None;
+            }
+            KeyCode::Char(c) => {
+                picker.fields[picker.active].push(c);
+                picker.error = None;
+            }
+            KeyCode::Enter => {
+                let old = picker.fields[0].trim().to_string();
+                let new = picker.fields[1].trim().to_string();
+                // Both fields empty goes back to the working tree
+                let refs = match (old.is_empty(), new.is_empty()) {
+                    (true, true) => None,
+                    (false, false) => Some((old, new)),
+                    _ => {
+                        picker.error = Some("Enter both refs".to_string());
+                        return;
+                    }
+                };
+                let error = refs.as_ref().and_then(|(old, new)| self.compare_refs_error(old, new));
+                if let (Some(error), Some(picker)) = (error, self.ref_picker.as_mut()) {
+                    picker.error = Some(error);
+                    return;
+                }
+                self.ref_picker = None;
+                self.set_compare_refs(refs);
+            }
+            _ => {}
+        }
+    }
+
+    fn compare_refs_error(&self, old: &str, new: &str) -> Option<String> {
+        [old, new]
+            .into_iter()
+            .find(|r| self.repo.ref_is_invalid(r))
+            .map(|r| format!("Unknown ref: {r}"))
+    }
+
+    /// Diff two refs against each other, or go back to the working tree with `None`.
+    fn set_compare_refs(&mut self, refs: Option<(String, String)>) {
+        self.compare_refs = refs;
+        // Row indices change, so remembered positions no longer apply
+        self.per_file_view.clear();
+        if let Err(e) = self.refresh_diff() {
+            self.status_message = Some(format!("Diff error: {e}"));
+            return;
+        }
+        self.cursor_pos.row = 0;
+        self.cursor_pos.col = 0;
+        self.re_search_on_file_change();
+        self.status_message = Some(match &self.compare_refs {
+            Some((old, new)) => format!("Comparing {old}..{new}"),
+            None => "Showing the working tree".to_string(),
+        });
+    }
+
+    /// `:goto` — put the cursor on a line of the new file (the old file when
+    /// the cursor is on the left in Normal mode). Lines outside every hunk
+    /// land on the next line shown, or the last one.
+    fn goto_line(&mut self, line: usize) {
+        let Some(file) = self.shown_file().map(Cow::into_owned) else {
+            self.status_message = Some("No file selected".to_string());
+            return;
+        };
+        let side = match self.diff_view_mode {
+            DiffViewMode::Normal => self.cursor_pos.side,
+            _ => DiffSide::Right,
+        };
+        let mut target = None;
+        let mut last = None;
+        let mut row = 0;
+        'hunks: for hunk in &file.hunks {
+            row += 1; // hunk header
+            for diff_row in &hunk.rows {
+                let side_line = match side {
+                    DiffSide::Left => diff_row.left.as_ref(),
+                    DiffSide::Right => diff_row.right.as_ref(),
+                };
+                if let Some(side_line) = side_line {
+                    if side_line.line_no as usize >= line {
+                        target = Some(row);
+                        break 'hunks;
+                    }
+                    last = Some(row);
+                }
+                row += 1;
+            }
+        }
+        let Some(row) = target.or(last) else {
+            self.status_message = Some("No lines on this side".to_string());
+            return;
+        };
+        if self.focused_pane != FocusedPane::DiffView {
+            self.set_focus(FocusedPane::DiffView);
+        }
+        self.push_jump();
+        self.diff_view_mode = DiffViewMode::Normal;
+        self.cursor_pos = CursorPos { row, col: 0, side };
+        self.scroll_to_cursor();
+    }
+
     fn execute_search(&mut self) {
-        self.search.matches.clear();
-        self.search.current_match_idx = None;
+        self.search.reset_matches();
         let query = match &self.search.query {
             Some(q) => q.clone(),
             None => return,
@@ -2408,57 +5747,86 @@ impl App {
             SearchOrigin::CommitLog => self.search_commit_log(&query),
             SearchOrigin::BranchList => self.search_branch_list(&query),
             SearchOrigin::Reflog => self.search_reflog(&query),
+            SearchOrigin::GitHubDetail => self.search_gh_detail(&query),
+            SearchOrigin::UnifiedView => self.search_unified_view(&query),
         }
     }
 
     fn search_diff_view(&mut self, query: &str) {
         let query_lower = query.to_lowercase();
-        let file = match self.selected_file() {
-            Some(f) => f.clone(),
+        let whole_word = self.search.whole_word;
+        let file = match self.shown_file() {
+            Some(f) => f.into_owned(),
             None => return,
         };
+        // Limit to the visual selection as matches are found, so the match
+        // cap counts only matches inside it
+        let within = self.search.within;
+        let in_scope = |row: usize, col_start: usize, col_end: usize, side: DiffSide| {
+            let Some((start, end, mode)) = within else {
+                return true;
+            };
+            let line_wise = mode == DiffViewMode::VisualLine;
+            let block = mode == DiffViewMode::VisualBlock;
+            side == start.side
+                && (start.row..=end.row).contains(&row)
+                && (line_wise || !(block || row == start.row) || col_start >= start.col)
+                && (line_wise || !(block || row == end.row) || col_end <= end.col + 1)
+        };
+        // Every searchable line: hunk headers, then both sides of each row
+        let mut lines = Vec::new();
         let mut row_idx: usize = 0;
         for hunk in &file.hunks {
-            // Search hunk header
-            for (col_start, _) in hunk.header.to_lowercase().match_indices(&query_lower) {
-                let col_end = col_start + query.len();
-                self.search.matches.push(SearchMatch::DiffLine {
-                    row: row_idx,
-                    col_start,
-                    col_end,
-                    side: DiffSide::Left,
-                });
-            }
+            lines.push((row_idx, hunk.header.as_str(), DiffSide::Left));
             row_idx += 1;
-
             for row in &hunk.rows {
-                // Search left side
                 if let Some(ref side_line) = row.left {
-                    for (col_start, _) in side_line.content.to_lowercase().match_indices(&query_lower) {
-                        let col_end = col_start + query.len();
-                        self.search.matches.push(SearchMatch::DiffLine {
-                            row: row_idx,
-                            col_start,
-                            col_end,
-                            side: DiffSide::Left,
-                        });
-                    }
+                    lines.push((row_idx, side_line.content.as_str(), DiffSide::Left));
                 }
-                // Search right side
                 if let Some(ref side_line) = row.right {
-                    for (col_start, _) in side_line.content.to_lowercase().match_indices(&query_lower) {
-                        let col_end = col_start + query.len();
-                        self.search.matches.push(SearchMatch::DiffLine {
-                            row: row_idx,
-                            col_start,
-                            col_end,
-                            side: DiffSide::Right,
-                        });
-                    }
+                    lines.push((row_idx, side_line.content.as_str(), DiffSide::Right));
                 }
                 row_idx += 1;
             }
         }
+        for (row, text, side) in lines {
+            for col_start in find_matches(text, &query_lower, whole_word) {
+                let col_end = col_start + query.len();
+                if in_scope(row, col_start, col_end, side)
+                    && !self.search.push_match(SearchMatch::DiffLine { row, col_start, col_end, side })
+                {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// `*` / `#` — search for the keyword under (or after) the cursor as a whole word.
+    fn search_word_under_cursor(&mut self, lines: &ContentLines, forward: bool) {
+        let word = match lines.get(self.cursor_pos.row) {
+            Some(line) => word_at(line, self.cursor_pos.col),
+            None => None,
+        };
+        let Some(word) = word else {
+            self.status_message = Some("No word under cursor".to_string());
+            return;
+        };
+        self.search.start(SearchOrigin::DiffView);
+        self.search.active = false;
+        self.search.whole_word = true;
+        self.search.push_history(&word);
+        self.search.query = Some(word);
+        self.execute_search();
+
+        // Start cycling from the occurrence under the cursor so the jump
+        // lands on the next/previous one, as in vim.
+        let cursor = self.cursor_pos;
+        self.search.current_match_idx = self.search.matches.iter().position(|m| {
+            matches!(m, SearchMatch::DiffLine { row, col_start, col_end, side }
+                if *row == cursor.row && *side == cursor.side
+                    && *col_start <= cursor.col && cursor.col < *col_end)
+        });
+        self.jump_to_match(forward);
     }
 
     fn search_file_tree(&mut self, query: &str) {
@@ -2474,33 +5842,114 @@ impl App {
                     }
                 }
             };
-            if name.to_lowercase().contains(&query_lower) {
-                self.search.matches.push(SearchMatch::TreeEntry(idx));
+            if name.to_lowercase().contains(&query_lower)
+                && !self.search.push_match(SearchMatch::TreeEntry(idx))
+            {
+                break;
             }
         }
     }
 
     fn search_commit_log(&mut self, query: &str) {
         let query_lower = query.to_lowercase();
+        let show_email = self.git_log.show_email;
         for (idx, commit) in self.git_log.commits.iter().enumerate() {
             let text = format!(
                 "{} {} {} {}",
                 commit.short_hash,
-                commit.author,
+                commit.author_label(show_email),
                 commit.date,
                 commit.message
             );
-            if text.to_lowercase().contains(&query_lower) {
-                self.search.matches.push(SearchMatch::CommitEntry(idx));
+            if text.to_lowercase().contains(&query_lower)
+                && !self.search.push_match(SearchMatch::CommitEntry(idx))
+            {
+                break;
             }
         }
+
+        // Nothing among the loaded commits: look further back in the history
+        // on a background thread; `drain_history_search` shows what turns up
+        if !self.search.matches.is_empty() || self.git_log.commits.len() < self.config.log_limit {
+            self.history_search_rx = None;
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        self.history_search_rx = Some(rx);
+        let workdir = self.repo.workdir().to_path_buf();
+        let ref_name = self.git_log.ref_name.clone();
+        let query = query.to_string();
+        let limit = self.config.log_limit;
+        std::thread::spawn(move || {
+            let found = Repo::discover(&workdir)
+                .map(|repo| repo.search_log(&ref_name, &query, limit))
+                .unwrap_or_default();
+            let _ = tx.send((query, found));
+        });
+    }
+
+    /// Pick up a finished full-history search and jump to its first hit,
+    /// unless the search it ran for has been replaced or cleared since.
+    pub fn drain_history_search(&mut self) {
+        let Some(rx) = &self.history_search_rx else {
+            return;
+        };
+        let (query, found) = match rx.try_recv() {
+            Ok(done) => done,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.history_search_rx = None;
+                return;
+            }
+        };
+        self.history_search_rx = None;
+        if self.search.origin != SearchOrigin::CommitLog
+            || self.search.query.as_deref() != Some(query.as_str())
+            || !self.search.matches.is_empty()
+        {
+            return;
+        }
+        let loaded = &self.git_log.commits;
+        self.search.history_commits = found
+            .into_iter()
+            .filter(|c| !loaded.iter().any(|l| l.full_hash == c.full_hash))
+            .collect();
+        let start = loaded.len();
+        let end = start + self.search.history_commits.len();
+        self.search.matches.extend((start..end).map(SearchMatch::CommitEntry));
+        self.jump_to_match(true);
+    }
+
+    /// The loaded Git Log followed by the commits a history search found.
+    pub fn log_commits(&self) -> impl Iterator<Item = &CommitInfo> {
+        self.git_log.commits.iter().chain(&self.search.history_commits)
+    }
+
+    pub fn log_commit(&self, idx: usize) -> Option<&CommitInfo> {
+        self.log_commits().nth(idx)
+    }
+
+    pub fn log_len(&self) -> usize {
+        self.git_log.commits.len() + self.search.history_commits.len()
+    }
+
+    /// Keep the selection and range mark within the Git Log once the
+    /// history hits after it are gone.
+    pub fn clamp_log_selection(&mut self) {
+        let last = self.log_len().saturating_sub(1);
+        self.git_log.selected_idx = self.git_log.selected_idx.min(last);
+        if let Some(anchor) = self.git_log.anchor.as_mut() {
+            *anchor = (*anchor).min(last);
+        }
     }
 
     fn search_branch_list(&mut self, query: &str) {
         let query_lower = query.to_lowercase();
         for (idx, branch) in self.branch_list.branches.iter().enumerate() {
-            if branch.name.to_lowercase().contains(&query_lower) {
-                self.search.matches.push(SearchMatch::BranchEntry(idx));
+            if branch.name.to_lowercase().contains(&query_lower)
+                && !self.search.push_match(SearchMatch::BranchEntry(idx))
+            {
+                break;
             }
         }
     }
@@ -2508,16 +5957,86 @@ impl App {
     fn search_reflog(&mut self, query: &str) {
         let query_lower = query.to_lowercase();
         for (idx, entry) in self.reflog.entries.iter().enumerate() {
-            if entry.short_hash.to_lowercase().contains(&query_lower)
+            if (entry.short_hash.to_lowercase().contains(&query_lower)
                 || entry.selector.to_lowercase().contains(&query_lower)
                 || entry.action.to_lowercase().contains(&query_lower)
-                || entry.message.to_lowercase().contains(&query_lower)
+                || entry.message.to_lowercase().contains(&query_lower))
+                && !self.search.push_match(SearchMatch::ReflogEntry(idx))
             {
-                self.search.matches.push(SearchMatch::ReflogEntry(idx));
+                break;
             }
         }
     }
 
+    fn search_gh_detail(&mut self, query: &str) {
+        let query_lower = query.to_lowercase();
+        let pane = self.github.detail_pane;
+        let (lines, _) = crate::ui::github::detail_view::pane_text(&self.github.detail, pane);
+        'lines: for (line, text) in lines.iter().enumerate() {
+            for col_start in find_matches(text, &query_lower, false) {
+                let m = SearchMatch::DetailLine {
+                    pane,
+                    line,
+                    col_start,
+                    col_end: col_start + query_lower.len(),
+                };
+                if !self.search.push_match(m) {
+                    break 'lines;
+                }
+            }
+        }
+    }
+
+    fn search_unified_view(&mut self, query: &str) {
+        let query_lower = query.to_lowercase();
+        let whole_word = self.search.whole_word;
+        let Some(view) = self.unified_view.take() else {
+            return;
+        };
+        'lines: for (line, l) in view.lines.iter().enumerate() {
+            for col_start in find_matches(&l.text, &query_lower, whole_word) {
+                let m = SearchMatch::UnifiedLine {
+                    line,
+                    col_start,
+                    col_end: col_start + query_lower.len(),
+                };
+                if !self.search.push_match(m) {
+                    break 'lines;
+                }
+            }
+        }
+        self.unified_view = Some(view);
+    }
+
+    /// Scroll the active detail pane so `line` sits a third of the way down,
+    /// selecting the comment / review / check that contains it. Panes wrap
+    /// long lines, so the scroll counts the wrapped rows above `line`.
+    fn scroll_gh_detail_to_line(&mut self, line: usize) {
+        use crate::ui::github::detail_view::{pane_text, wrapped_height};
+
+        let margin = (self.github.detail_view_height / 3) as usize;
+        let (text, offsets) = pane_text(&self.github.detail, self.github.detail_pane);
+        let lines: Vec<ratatui::text::Line> = text.into_iter().map(ratatui::text::Line::from).collect();
+        let width = self.github.detail_text_width;
+        let end = line.min(lines.len());
+        let rows = |from: usize| wrapped_height(&lines[from.min(end)..end], width);
+        if self.github.detail_pane == GhDetailPane::Body {
+            self.github.detail_scroll_body = rows(0).saturating_sub(margin).min(u16::MAX as usize) as u16;
+            return;
+        }
+        let Some(item) = offsets.iter().rposition(|&o| o as usize <= line) else {
+            return;
+        };
+        *self.github.active_selected_idx_mut() = item;
+        // Checks are table rows, which don't wrap
+        let within = match self.github.detail_pane {
+            GhDetailPane::Status => line - offsets[item] as usize,
+            _ => rows(offsets[item] as usize),
+        };
+        *self.github.active_detail_scroll_mut() =
+            within.saturating_sub(margin).min(u16::MAX as usize) as u16;
+    }
+
     fn jump_to_match(&mut self, forward: bool) {
         // If no active query but last_query exists, re-execute search
         if self.search.query.is_none() {
@@ -2530,7 +6049,12 @@ impl App {
         }
 
         if self.search.matches.is_empty() {
-            self.status_message = Some("Pattern not found".to_string());
+            let searching =
+                self.history_search_rx.is_some() && self.search.origin == SearchOrigin::CommitLog;
+            self.status_message = Some(
+                if searching { "Searching the full history..." } else { "Pattern not found" }
+                    .to_string(),
+            );
             return;
         }
 
@@ -2554,22 +6078,28 @@ impl App {
         self.search.current_match_idx = Some(new_idx);
 
         match &self.search.matches[new_idx] {
-            SearchMatch::DiffLine { row, col_start, side, .. } => {
+            SearchMatch::DiffLine { row, col_start, col_end, side } => {
                 let row = *row;
                 let col_start = *col_start;
+                let col_end = *col_end;
                 let side = *side;
                 if self.diff_view_mode == DiffViewMode::Scroll {
-                    // In scroll mode, just scroll to the row
+                    // In scroll mode, just scroll to the match
                     self.diff_scroll_y = row.saturating_sub(
                         (self.diff_view_height / 3) as usize,
                     ) as u16;
+                    self.reveal_cols(row, side, col_start, col_end);
                 } else {
                     // In Normal/Visual mode, move cursor
+                    if self.diff_view_mode == DiffViewMode::Normal {
+                        self.push_jump();
+                    }
                     self.cursor_pos.row = row;
                     self.cursor_pos.col = col_start;
                     self.cursor_pos.side = side;
                     self.content_lines_cache = None; // side may have changed
                     self.scroll_to_cursor();
+                    self.reveal_cols(row, side, col_start, col_end);
                 }
             }
             SearchMatch::TreeEntry(idx) => {
@@ -2585,8 +6115,153 @@ impl App {
             SearchMatch::ReflogEntry(idx) => {
                 self.reflog.selected_idx = *idx;
             }
+            SearchMatch::DetailLine { pane, line, .. } => {
+                let line = *line;
+                self.github.detail_pane = *pane;
+                self.scroll_gh_detail_to_line(line);
+            }
+            SearchMatch::UnifiedLine { line, col_start, col_end } => {
+                if let Some(view) = self.unified_view.as_mut() {
+                    view.cursor = *line;
+                    view.scroll = line.saturating_sub(view.view_height / 3);
+                    let width = view.view_width.max(1);
+                    if *col_start < view.scroll_x || *col_end > view.scroll_x + width {
+                        view.scroll_x = col_start.saturating_sub(width / 3);
+                    }
+                }
+            }
         }
 
-        self.status_message = Some(format!("[{}/{}]", new_idx + 1, total));
+        let from_history = matches!(
+            self.search.matches[new_idx],
+            SearchMatch::CommitEntry(idx) if idx >= self.git_log.commits.len()
+        );
+        self.status_message = Some(if from_history {
+            format!("[{}/{}] from the full history", new_idx + 1, total)
+        } else if self.search.truncated {
+            format!("[{}/{}+] Too many matches, refine the search", new_idx + 1, total)
+        } else {
+            format!("[{}/{}]", new_idx + 1, total)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::diff::SideLine;
+    use git2::{Repository, RepositoryInitOptions, Signature};
+    use tempfile::TempDir;
+
+    /// A repository with one commit on `main` and a `feature` branch at it.
+    fn repo_with_branch() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let mut opts = RepositoryInitOptions::new();
+        opts.initial_head("main");
+        let git = Repository::init_opts(dir.path(), &opts).unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        let mut index = git.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        let tree = git.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let commit = git.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[]).unwrap();
+        git.branch("feature", &git.find_commit(commit).unwrap(), false).unwrap();
+        drop(tree);
+        (dir, git)
+    }
+
+    fn app_with_base(dir: &TempDir, base: &str) -> App {
+        let repo = Repo::discover(dir.path()).unwrap();
+        let config = Config { image_preview: false, ..Config::default() };
+        App::new(repo, config, Some(base.to_string()), DiffScope::All).unwrap()
+    }
+
+    #[test]
+    fn base_survives_a_ref_missing_for_one_refresh() {
+        let (dir, git) = repo_with_branch();
+        let mut app = app_with_base(&dir, "feature");
+        let head = git.head().unwrap().peel_to_commit().unwrap();
+
+        // The branch is deleted and recreated, as a rewrite does
+        git.find_branch("feature", git2::BranchType::Local).unwrap().delete().unwrap();
+        app.refresh_diff().unwrap();
+        assert_eq!(app.diff_base_ref.as_deref(), Some("feature"));
+        git.branch("feature", &head, false).unwrap();
+        app.retry_refresh_diff().unwrap();
+        assert_eq!(app.diff_base_ref.as_deref(), Some("feature"));
+        assert!(!app.diff_retry_pending);
+    }
+
+    #[test]
+    fn base_is_dropped_when_its_ref_stays_missing() {
+        let (dir, git) = repo_with_branch();
+        let mut app = app_with_base(&dir, "feature");
+
+        git.find_branch("feature", git2::BranchType::Local).unwrap().delete().unwrap();
+        app.refresh_diff().unwrap();
+        app.retry_refresh_diff().unwrap();
+        assert_eq!(app.diff_base_ref, None);
+        assert!(app.status_message.as_deref().is_some_and(|m| m.starts_with("Invalid ref")));
+    }
+
+    #[test]
+    fn unified_view_keeps_the_colors_of_an_opened_file() {
+        let (dir, _git) = repo_with_branch();
+        std::fs::write(dir.path().join("main.rs"), "fn main() {\n    let x = 1;\n}\n").unwrap();
+        let mut app = app_with_base(&dir, "HEAD");
+        let start = std::time::Instant::now();
+        while !app.bg_highlights.contains_key("main.rs") {
+            assert!(start.elapsed() < std::time::Duration::from_secs(30), "no background highlight");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            app.drain_bg_highlights();
+        }
+
+        let file = app.diff_state.files.iter().find(|f| f.path == "main.rs").unwrap().clone();
+        app.ensure_file_highlight(&file, usize::MAX);
+        let added: Vec<_> = app
+            .unified_lines()
+            .into_iter()
+            .filter(|l| l.kind == UnifiedLineKind::Added)
+            .collect();
+        assert_eq!(added.len(), 3);
+        assert!(added.iter().all(|l| !l.colors.is_empty()));
+    }
+
+    #[test]
+    fn content_lines_build_only_the_requested_rows() {
+        let row = |n: u32, line_type| {
+            let side = SideLine { line_no: n, content: format!("line {n}") };
+            SideBySideRow { left: Some(side.clone()), right: Some(side), line_type }
+        };
+        let hunk = |header: &str, rows| DiffHunk { header: header.to_string(), old_start: 1, new_start: 1, rows };
+        let file = FileDiff {
+            path: "big.txt".to_string(),
+            raw_path: PathBuf::from("big.txt"),
+            old_path: None,
+            status: FileStatus::Modified,
+            hunks: vec![
+                hunk("@@ -1,50000 +1,50000 @@", (1..=50_000).map(|n| row(n, LineType::Context)).collect()),
+                hunk("@@ -60000 +60000,2 @@", vec![row(60_000, LineType::Context), row(60_001, LineType::Added)]),
+            ],
+            is_binary: false,
+        };
+
+        let lines = ContentLines::build(&file, DiffSide::Right, false, 25_000..25_050);
+        assert_eq!(lines.len(), 50_004);
+        assert_eq!(lines.lines.len(), 50);
+        // Row 0 is the hunk header, so row N is line N
+        assert_eq!(lines.get(25_000).map(String::as_str), Some("line 25000"));
+        assert_eq!(lines.get(25_049).map(String::as_str), Some("line 25049"));
+        assert_eq!(lines.get(24_999), None);
+        assert_eq!(lines.get(25_050), None);
+
+        let across = ContentLines::build(&file, DiffSide::Right, false, 50_000..50_003);
+        let texts: Vec<_> = (50_000..50_003).filter_map(|r| across.get(r)).collect();
+        assert_eq!(texts, ["line 50000", "@@ -60000 +60000,2 @@", "line 60000"]);
+
+        let changes = ContentLines::build(&file, DiffSide::Right, true, 0..usize::MAX);
+        let texts: Vec<_> = (0..changes.len()).filter_map(|r| changes.get(r)).collect();
+        assert_eq!(texts, ["@@ -1,50000 +1,50000 @@", "@@ -60000 +60000,2 @@", "line 60001"]);
     }
 }
@@ -0,0 +1,80 @@
+use anyhow::{bail, Result};
+
+/// A command entered on the `:` command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// `:q` asks first like `q` when `confirm_quit` is set; `:q!` doesn't
+    Quit { force: bool },
+    Refresh,
+    /// Compare against a ref, or HEAD when none is given
+    Base(Option<String>),
+    /// Diff two refs against each other (`:compare v1 v2` or `:compare v1..v2`);
+    /// no refs goes back to the working tree
+    Compare(Option<(String, String)>),
+    /// Jump to a line of the file on the cursor's side (`:goto 42` or `:42`)
+    Goto(usize),
+    /// Switch the syntax theme, or list the available ones
+    Theme(Option<String>),
+    /// Re-read the config files and apply what can change while running
+    Reload,
+    Help,
+}
+
+impl Command {
+    /// Parse the text typed after `:`. Returns None for empty input.
+    pub fn parse(input: &str) -> Result<Option<Self>> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(None);
+        }
+        let (name, arg) = match input.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, Some(arg.trim())),
+            None => (input, None),
+        };
+        if let Ok(line) = name.parse::<usize>() {
+            return match arg {
+                None => Ok(Some(Command::Goto(line))),
+                Some(_) => bail!("Trailing characters: {input}"),
+            };
+        }
+        let command = match name {
+            "q" | "quit" => Command::Quit { force: false },
+            "q!" | "quit!" => Command::Quit { force: true },
+            "refresh" => Command::Refresh,
+            "base" => return Ok(Some(Command::Base(arg.map(str::to_string)))),
+            "compare" => {
+                let Some(arg) = arg else {
+                    return Ok(Some(Command::Compare(None)));
+                };
+                let refs = match arg.split_once("..") {
+                    Some((old, new)) => (old.trim(), new.trim()),
+                    None => match arg.split_once(char::is_whitespace) {
+                        Some((old, new)) => (old, new.trim()),
+                        None => bail!("Usage: compare <old> <new>"),
+                    },
+                };
+                if refs.0.is_empty() || refs.1.is_empty() || refs.1.contains(char::is_whitespace) {
+                    bail!("Usage: compare <old> <new>");
+                }
+                return Ok(Some(Command::Compare(Some((refs.0.to_string(), refs.1.to_string())))));
+            }
+            "goto" => {
+                let Some(arg) = arg else {
+                    bail!("Usage: goto <line>");
+                };
+                match arg.parse() {
+                    Ok(line) => return Ok(Some(Command::Goto(line))),
+                    Err(_) => bail!("Not a line number: {arg}"),
+                }
+            }
+            "theme" => return Ok(Some(Command::Theme(arg.map(str::to_string)))),
+            "reload" => Command::Reload,
+            "h" | "help" => Command::Help,
+            _ => bail!("Unknown command: {name}"),
+        };
+        if arg.is_some() {
+            bail!("{name} takes no argument");
+        }
+        Ok(Some(command))
+    }
+}
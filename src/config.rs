@@ -0,0 +1,169 @@
+use crate::app::ViewMode;
+use crate::ui::diff_view::DiffPalette;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Per-repository config, read from the repository root.
+pub const REPO_CONFIG_FILE: &str = ".vig.toml";
+
+/// Keys a repository's `.vig.toml` may set. A cloned repository is not
+/// trusted with anything else, least of all a command to run (`diff_tool`).
+const REPO_CONFIG_KEYS: [&str; 5] = ["base", "ignore_whitespace", "theme", "view", "diff_colors"];
+
+/// User configuration loaded from `$XDG_CONFIG_HOME/vig/config.toml`
+/// (falls back to `~/.config/vig/config.toml`), with a repository's
+/// `.vig.toml` layered on top for the review defaults in
+/// `REPO_CONFIG_KEYS`. Missing keys use defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Files with more diff rows than this skip syntax highlighting.
+    pub large_file_threshold: usize,
+    /// Ask before quitting with `q` while there are changed files.
+    pub confirm_quit: bool,
+    /// How often the UI wakes up when idle, in milliseconds.
+    pub tick_rate_ms: u64,
+    /// How long file changes are batched before the diff refreshes, in milliseconds.
+    pub watch_debounce_ms: u64,
+    /// Columns between tab stops when displaying tabs in the diff.
+    pub tabstop: usize,
+    /// Show changed PNG/JPEG files as images on terminals with graphics support.
+    pub image_preview: bool,
+    /// Ref to compare the working tree against instead of HEAD (`--base` wins).
+    pub base: Option<String>,
+    /// Hide whitespace-only changes, like `git diff -w`.
+    pub ignore_whitespace: bool,
+    /// Syntax highlighting theme (see `:theme`).
+    pub theme: Option<String>,
+    /// Colors of added / deleted lines at startup (`P` cycles them).
+    pub diff_colors: DiffPalette,
+    /// View shown at startup, until a session for the repository records
+    /// the last one used.
+    pub view: ViewMode,
+    /// Commits loaded into the Git Log for the selected branch.
+    pub log_limit: usize,
+    /// Entries loaded into the Reflog pane.
+    pub reflog_limit: usize,
+    /// Issues fetched for the GitHub view.
+    pub issue_limit: usize,
+    /// Pull requests fetched for the GitHub view.
+    pub pr_limit: usize,
+    /// External diff tool run by `E`, e.g. `meld {old} {new}`. `{old}` and
+    /// `{new}` are files with the two versions, `{path}` the repository path.
+    pub diff_tool: Option<String>,
+}
+
+/// Lower bound for `tick_rate_ms` and `watch_debounce_ms`.
+pub const MIN_INTERVAL_MS: u64 = 50;
+
+/// Upper bounds the fetch limits are clamped to: past these, loading the
+/// log or asking `gh` for the lists gets slow enough to stall the UI.
+const MAX_HISTORY_LIMIT: usize = 10_000;
+const MAX_GITHUB_LIMIT: usize = 1_000;
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            large_file_threshold: 20_000,
+            confirm_quit: false,
+            tick_rate_ms: 250,
+            watch_debounce_ms: 500,
+            tabstop: 4,
+            image_preview: true,
+            base: None,
+            ignore_whitespace: false,
+            theme: None,
+            diff_colors: DiffPalette::RedGreen,
+            view: ViewMode::Git,
+            log_limit: 100,
+            reflog_limit: 500,
+            issue_limit: 50,
+            pr_limit: 50,
+            diff_tool: None,
+        }
+    }
+}
+
+impl Config {
+    /// Path of the global config file, if a config directory can be determined.
+    pub fn path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+        Some(base.join("vig").join("config.toml"))
+    }
+
+    /// Load the global config, then the `.vig.toml` in `workdir` whose keys
+    /// take precedence. The repository file may only set `REPO_CONFIG_KEYS`.
+    /// Missing files yield the defaults.
+    pub fn load(workdir: &Path) -> Result<Self> {
+        let repo_path = workdir.join(REPO_CONFIG_FILE);
+        let mut paths: Vec<PathBuf> = Self::path().into_iter().collect();
+        paths.push(repo_path.clone());
+
+        let mut table = toml::Table::new();
+        for path in paths.iter().filter(|p| p.exists()) {
+            let text = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let layer: toml::Table = toml::from_str(&text)
+                .with_context(|| format!("Invalid config {}", path.display()))?;
+            if *path == repo_path {
+                if let Some(key) = layer.keys().find(|k| !REPO_CONFIG_KEYS.contains(&k.as_str())) {
+                    anyhow::bail!(
+                        "Invalid config {}: `{key}` can only be set in the global config",
+                        path.display()
+                    );
+                }
+            }
+            table.extend(layer);
+        }
+        let mut config: Self = table
+            .try_into()
+            .with_context(|| format!("Invalid config {}", Self::describe(&paths)))?;
+        config
+            .validate()
+            .with_context(|| format!("Invalid config {}", Self::describe(&paths)))?;
+        config.log_limit = config.log_limit.min(MAX_HISTORY_LIMIT);
+        config.reflog_limit = config.reflog_limit.min(MAX_HISTORY_LIMIT);
+        config.issue_limit = config.issue_limit.min(MAX_GITHUB_LIMIT);
+        config.pr_limit = config.pr_limit.min(MAX_GITHUB_LIMIT);
+        Ok(config)
+    }
+
+    /// The existing config files, for error messages.
+    fn describe(paths: &[PathBuf]) -> String {
+        paths
+            .iter()
+            .filter(|p| p.exists())
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (key, value) in [
+            ("tick_rate_ms", self.tick_rate_ms),
+            ("watch_debounce_ms", self.watch_debounce_ms),
+        ] {
+            if value < MIN_INTERVAL_MS {
+                anyhow::bail!("{key} must be at least {MIN_INTERVAL_MS} (got {value})");
+            }
+        }
+        for (key, value) in [
+            ("log_limit", self.log_limit),
+            ("reflog_limit", self.reflog_limit),
+            ("issue_limit", self.issue_limit),
+            ("pr_limit", self.pr_limit),
+        ] {
+            if value == 0 {
+                anyhow::bail!("{key} must be at least 1");
+            }
+        }
+        if !(1..=16).contains(&self.tabstop) {
+            anyhow::bail!("tabstop must be between 1 and 16 (got {})", self.tabstop);
+        }
+        Ok(())
+    }
+}
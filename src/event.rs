@@ -11,7 +11,16 @@ pub enum Event {
     Key(KeyEvent),
     Tick,
     Resize(u16, u16),
-    FsChange,
+    FsChange(FsChange),
+}
+
+/// What a batch of filesystem changes touched.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsChange {
+    /// Refs, packed-refs or HEAD moved: branches and the reflog are stale
+    pub refs: bool,
+    /// Working tree or index files changed: only the diff is stale
+    pub workdir: bool,
 }
 
 pub struct EventHandler {
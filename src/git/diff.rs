@@ -1,12 +1,18 @@
-use git2::{Delta, DiffDelta, DiffLine, DiffOptions, ObjectType, Patch, Repository};
+use git2::{Delta, Diff, DiffDelta, DiffFindOptions, DiffLine, DiffOptions, FileMode, ObjectType, Oid, Patch, Repository};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum FileStatus {
     Added,
     Deleted,
     Modified,
     Renamed,
     Untracked,
+    /// Unmerged during a merge, rebase or cherry-pick
+    Conflicted,
 }
 
 impl FileStatus {
@@ -27,12 +33,42 @@ impl FileStatus {
             FileStatus::Modified => "M",
             FileStatus::Renamed => "R",
             FileStatus::Untracked => "?",
+            FileStatus::Conflicted => "U",
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Which changes the diff shows: everything since the base, only what is
+/// not yet staged (workdir vs index), or only what is staged (index vs base).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffScope {
+    #[default]
+    All,
+    Unstaged,
+    Staged,
+}
+
+impl DiffScope {
+    pub fn next(self) -> Self {
+        match self {
+            DiffScope::All => DiffScope::Unstaged,
+            DiffScope::Unstaged => DiffScope::Staged,
+            DiffScope::Staged => DiffScope::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DiffScope::All => "all",
+            DiffScope::Unstaged => "unstaged",
+            DiffScope::Staged => "staged",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[allow(dead_code)]
+#[serde(rename_all = "snake_case")]
 pub enum LineType {
     Context,
     Added,
@@ -40,39 +76,201 @@ pub enum LineType {
     HunkHeader,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SideLine {
     pub line_no: u32,
     pub content: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SideBySideRow {
     pub left: Option<SideLine>,
     pub right: Option<SideLine>,
     pub line_type: LineType,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DiffHunk {
     pub header: String,
+    /// First line number of the hunk in the old / new file (1-based).
+    pub old_start: u32,
+    pub new_start: u32,
     pub rows: Vec<SideBySideRow>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FileDiff {
+    /// Display path; lossy when the file name is not valid UTF-8.
     pub path: String,
+    /// Exact path as stored by git, for filesystem and object lookups.
+    #[serde(skip)]
+    pub raw_path: PathBuf,
+    /// Previous path of a renamed file.
+    pub old_path: Option<String>,
     pub status: FileStatus,
     pub hunks: Vec<DiffHunk>,
     pub is_binary: bool,
 }
 
-#[derive(Debug, Clone)]
+impl FileDiff {
+    /// Path of the file in the working tree (the new name for renames), or
+    /// `None` if the file was deleted.
+    pub fn worktree_path(&self) -> Option<&Path> {
+        match self.status {
+            FileStatus::Deleted => None,
+            _ => Some(&self.raw_path),
+        }
+    }
+
+    /// `old → new` for renames, otherwise the path.
+    pub fn display_path(&self) -> String {
+        match &self.old_path {
+            Some(old) => format!("{old} → {}", self.path),
+            None => self.path.clone(),
+        }
+    }
+
+    /// Added and deleted lines in this file.
+    pub fn stats(&self) -> DiffStats {
+        let mut stats = DiffStats::default();
+        for row in self.hunks.iter().flat_map(|h| &h.rows) {
+            match row.line_type {
+                LineType::Added => stats.additions += 1,
+                LineType::Deleted => {
+                    // Paired rows count as both a deletion and addition
+                    if row.right.is_some() {
+                        stats.additions += 1;
+                    }
+                    stats.deletions += 1;
+                }
+                _ => {}
+            }
+        }
+        stats
+    }
+
+    /// Number of display rows (one per hunk header plus one per content row).
+    pub fn row_count(&self) -> usize {
+        self.hunks.iter().map(|h| h.rows.len() + 1).sum()
+    }
+
+    /// Render the file's hunks back into unified diff text.
+    pub fn to_unified(&self) -> String {
+        let old = self.old_path.as_deref().unwrap_or(&self.path);
+        let mut out = vec![format!("--- a/{old}"), format!("+++ b/{}", self.path)];
+        for hunk in &self.hunks {
+            out.extend(hunk.to_unified_lines());
+        }
+        out.join("\n")
+    }
+
+    /// Index of the hunk containing display row `row` (as counted by `row_count`).
+    pub fn hunk_index_at_row(&self, row: usize) -> Option<usize> {
+        let mut start = 0;
+        for (idx, hunk) in self.hunks.iter().enumerate() {
+            let end = start + hunk.rows.len() + 1;
+            if row < end {
+                return Some(idx);
+            }
+            start = end;
+        }
+        None
+    }
+
+    /// A copy without context rows, keeping only added/deleted rows under
+    /// their hunk headers. Hunks stay in the same order.
+    pub fn changes_only(&self) -> FileDiff {
+        let hunks = self
+            .hunks
+            .iter()
+            .map(|hunk| DiffHunk {
+                header: hunk.header.clone(),
+                old_start: hunk.old_start,
+                new_start: hunk.new_start,
+                rows: hunk
+                    .rows
+                    .iter()
+                    .filter(|row| row.line_type != LineType::Context)
+                    .cloned()
+                    .collect(),
+            })
+            .collect();
+        FileDiff {
+            path: self.path.clone(),
+            raw_path: self.raw_path.clone(),
+            old_path: self.old_path.clone(),
+            status: self.status,
+            hunks,
+            is_binary: self.is_binary,
+        }
+    }
+
+    /// For each display row of `changes_only`, the display row it comes from.
+    pub fn change_rows(&self) -> Vec<usize> {
+        let mut rows = Vec::new();
+        let mut row = 0;
+        for hunk in &self.hunks {
+            rows.push(row);
+            row += 1;
+            for diff_row in &hunk.rows {
+                if diff_row.line_type != LineType::Context {
+                    rows.push(row);
+                }
+                row += 1;
+            }
+        }
+        rows
+    }
+}
+
+impl DiffHunk {
+    /// The hunk header followed by its rows as unified diff lines.
+    pub fn to_unified_lines(&self) -> Vec<String> {
+        let mut out = vec![self.header.clone()];
+        // Changed rows pair deletions with additions side by side; unified
+        // output lists a whole run of deletions before its additions.
+        let mut added = Vec::new();
+        for row in &self.rows {
+            if row.line_type == LineType::Context {
+                out.append(&mut added);
+                if let Some(ref l) = row.left {
+                    out.push(format!(" {}", l.content));
+                }
+                continue;
+            }
+            if let Some(ref l) = row.left {
+                out.push(format!("-{}", l.content));
+            }
+            if let Some(ref r) = row.right {
+                added.push(format!("+{}", r.content));
+            }
+        }
+        out.append(&mut added);
+        out
+    }
+
+    /// New-side text of the hunk, as the body of a GitHub suggestion.
+    pub fn new_side_lines(&self) -> Vec<&str> {
+        self.rows
+            .iter()
+            .filter_map(|row| row.right.as_ref().map(|r| r.content.as_str()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
 pub struct DiffStats {
     pub additions: usize,
     pub deletions: usize,
 }
 
+impl DiffStats {
+    pub fn add(&mut self, other: DiffStats) {
+        self.additions += other.additions;
+        self.deletions += other.deletions;
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DiffState {
     pub files: Vec<FileDiff>,
@@ -87,79 +285,286 @@ struct RawHunkLine {
     content: String,
 }
 
-pub fn parse_diff(repo: &Repository, base_ref: Option<&str>) -> anyhow::Result<Vec<FileDiff>> {
+pub fn parse_diff(
+    repo: &Repository,
+    base_ref: Option<&str>,
+    scope: DiffScope,
+    ignore_whitespace: bool,
+) -> anyhow::Result<Vec<FileDiff>> {
     let head = match base_ref {
-        Some(r) => {
-            let obj = repo
-                .revparse_single(r)
-                .map_err(|e| anyhow::anyhow!("Cannot resolve '{}': {}", r, e))?;
-            let tree_obj = obj
-                .peel(ObjectType::Tree)
-                .map_err(|e| anyhow::anyhow!("Cannot peel to tree: {}", e))?;
-            Some(
-                tree_obj
-                    .into_tree()
-                    .map_err(|_| anyhow::anyhow!("Not a tree"))?,
-            )
-        }
+        Some(r) => Some(resolve_tree(repo, r)?),
         None => repo.head().ok().and_then(|r| r.peel_to_tree().ok()),
     };
     let mut opts = DiffOptions::new();
     opts.include_untracked(true);
     opts.recurse_untracked_dirs(true);
     opts.show_untracked_content(true);
+    opts.ignore_whitespace(ignore_whitespace);
+
+    let diff = match scope {
+        DiffScope::All => repo.diff_tree_to_workdir_with_index(head.as_ref(), Some(&mut opts))?,
+        DiffScope::Unstaged => repo.diff_index_to_workdir(None, Some(&mut opts))?,
+        DiffScope::Staged => repo.diff_tree_to_index(head.as_ref(), None, Some(&mut opts))?,
+    };
+    files_from_diff(repo, diff, true)
+}
 
-    let diff = repo.diff_tree_to_workdir_with_index(head.as_ref(), Some(&mut opts))?;
+/// Diff between the trees of two refs (`old..new`).
+pub fn parse_ref_diff(
+    repo: &Repository,
+    old: &str,
+    new: &str,
+    ignore_whitespace: bool,
+) -> anyhow::Result<Vec<FileDiff>> {
+    let old_tree = resolve_tree(repo, old)?;
+    let new_tree = resolve_tree(repo, new)?;
+    let mut opts = DiffOptions::new();
+    opts.ignore_whitespace(ignore_whitespace);
+    let diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), Some(&mut opts))?;
+    files_from_diff(repo, diff, false)
+}
+
+fn resolve_tree<'r>(repo: &'r Repository, spec: &str) -> anyhow::Result<git2::Tree<'r>> {
+    let obj = repo
+        .revparse_single(spec)
+        .map_err(|e| anyhow::anyhow!("Cannot resolve '{}': {}", spec, e))?;
+    let tree_obj = obj
+        .peel(ObjectType::Tree)
+        .map_err(|e| anyhow::anyhow!("Cannot peel to tree: {}", e))?;
+    tree_obj
+        .into_tree()
+        .map_err(|_| anyhow::anyhow!("Not a tree"))
+}
 
+/// Convert a libgit2 diff into files. With `with_conflicts`, paths the index
+/// lists as conflicted are marked as such and diffed from our side.
+fn files_from_diff(
+    repo: &Repository,
+    mut diff: Diff,
+    with_conflicts: bool,
+) -> anyhow::Result<Vec<FileDiff>> {
+    // Pair deletions with added/untracked files so moves show up as renames
+    let mut find_opts = DiffFindOptions::new();
+    find_opts.renames(true).for_untracked(true);
+    diff.find_similar(Some(&mut find_opts))?;
+
+    let conflicts = if with_conflicts {
+        conflicted_paths(repo)
+    } else {
+        HashMap::new()
+    };
     let mut files = Vec::new();
 
     let num_deltas = diff.deltas().count();
     for idx in 0..num_deltas {
         let delta = diff.get_delta(idx).unwrap();
-        let status = delta_status(&delta);
-        let path = delta_path(&delta);
-
-        if let Ok(patch) = Patch::from_diff(&diff, idx) {
-            if let Some(patch) = patch {
-                let is_binary = patch.delta().flags().is_binary();
-                if is_binary {
-                    files.push(FileDiff {
-                        path,
-                        status,
-                        hunks: Vec::new(),
-                        is_binary: true,
-                    });
-                    continue;
-                }
-
-                let mut hunks = Vec::new();
-                for hunk_idx in 0..patch.num_hunks() {
-                    let (hunk, _) = patch.hunk(hunk_idx)?;
-                    let header = String::from_utf8_lossy(hunk.header()).trim().to_string();
-
-                    let mut raw_lines = Vec::new();
-                    for line_idx in 0..patch.num_lines_in_hunk(hunk_idx)? {
-                        let line = patch.line_in_hunk(hunk_idx, line_idx)?;
-                        raw_lines.push(raw_from_diff_line(&line));
-                    }
-
-                    let rows = align_hunk_lines(&raw_lines);
-                    hunks.push(DiffHunk { header, rows });
-                }
-
+        let raw_path = delta_path(&delta);
+        let status = if conflicts.contains_key(&raw_path) {
+            FileStatus::Conflicted
+        } else {
+            delta_status(&delta)
+        };
+        let path = raw_path.to_string_lossy().into_owned();
+        let old_path = match status {
+            FileStatus::Renamed => delta
+                .old_file()
+                .path()
+                .map(|p| p.to_string_lossy().into_owned()),
+            _ => None,
+        };
+
+        // Conflicted entries have no merged index entry for libgit2 to diff,
+        // so compare our side with the working file, markers and all
+        let ours;
+        let worktree;
+        let patch = match conflicts.get(&raw_path) {
+            Some(our_id) => {
+                ours = our_id
+                    .and_then(|id| repo.find_blob(id).ok())
+                    .map(|blob| blob.content().to_vec())
+                    .unwrap_or_default();
+                worktree = repo
+                    .workdir()
+                    .and_then(|w| std::fs::read(w.join(&raw_path)).ok())
+                    .unwrap_or_default();
+                Patch::from_buffers(
+                    &ours,
+                    Some(&raw_path),
+                    &worktree,
+                    Some(&raw_path),
+                    None,
+                )
+                .ok()
+            }
+            None => Patch::from_diff(&diff, idx).ok().flatten(),
+        };
+        if let Some(patch) = patch {
+            let is_binary = patch.delta().flags().is_binary();
+            if is_binary {
                 files.push(FileDiff {
                     path,
+                    raw_path,
+                    old_path,
                     status,
-                    hunks,
-                    is_binary: false,
+                    hunks: Vec::new(),
+                    is_binary: true,
                 });
+                continue;
             }
+
+            let mut hunks = hunks_from_patch(&patch)?;
+
+            let is_submodule = delta.old_file().mode() == FileMode::Commit
+                || delta.new_file().mode() == FileMode::Commit;
+            if is_submodule {
+                hunks.extend(submodule_log_hunk(
+                    repo,
+                    &path,
+                    delta.old_file().id(),
+                    delta.new_file().id(),
+                ));
+            }
+
+            files.push(FileDiff {
+                path,
+                raw_path,
+                old_path,
+                status,
+                hunks,
+                is_binary: false,
+            });
         }
     }
 
     Ok(files)
 }
 
+fn hunks_from_patch(patch: &Patch) -> anyhow::Result<Vec<DiffHunk>> {
+    let mut hunks = Vec::new();
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, _) = patch.hunk(hunk_idx)?;
+        let header = String::from_utf8_lossy(hunk.header()).trim().to_string();
+
+        let mut raw_lines = Vec::new();
+        for line_idx in 0..patch.num_lines_in_hunk(hunk_idx)? {
+            let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+            raw_lines.push(raw_from_diff_line(&line));
+        }
+
+        let rows = align_hunk_lines(&raw_lines);
+        hunks.push(DiffHunk {
+            header,
+            old_start: hunk.old_start(),
+            new_start: hunk.new_start(),
+            rows,
+        });
+    }
+    Ok(hunks)
+}
+
+/// Line diff of two versions of a file git took for binary, to read it as
+/// text anyway. Control characters, like the stray NUL that usually causes
+/// this, are shown as their Unicode control pictures (`␀`).
+pub fn forced_text_hunks(
+    old: &[u8],
+    new: &[u8],
+    path: &Path,
+    ignore_whitespace: bool,
+) -> anyhow::Result<Vec<DiffHunk>> {
+    let mut opts = DiffOptions::new();
+    opts.force_text(true);
+    opts.ignore_whitespace(ignore_whitespace);
+    let patch = Patch::from_buffers(old, Some(path), new, Some(path), Some(&mut opts))?;
+    let mut hunks = hunks_from_patch(&patch)?;
+    for row in hunks.iter_mut().flat_map(|h| h.rows.iter_mut()) {
+        for side in [&mut row.left, &mut row.right].into_iter().flatten() {
+            side.content = side
+                .content
+                .chars()
+                .map(|c| match c {
+                    '\t' => c,
+                    '\0'..='\x1f' => char::from_u32(0x2400 + c as u32).unwrap_or(c),
+                    '\x7f' => '\u{2421}',
+                    _ => c,
+                })
+                .collect();
+        }
+    }
+    Ok(hunks)
+}
+
+/// Conflicted paths in the index, with the blob of our side (if any).
+fn conflicted_paths(repo: &Repository) -> HashMap<PathBuf, Option<Oid>> {
+    let mut paths = HashMap::new();
+    let Ok(index) = repo.index() else {
+        return paths;
+    };
+    let Ok(conflicts) = index.conflicts() else {
+        return paths;
+    };
+    for conflict in conflicts.flatten() {
+        let entry = conflict
+            .our
+            .as_ref()
+            .or(conflict.their.as_ref())
+            .or(conflict.ancestor.as_ref());
+        if let Some(entry) = entry {
+            paths.insert(bytes_to_path(&entry.path), conflict.our.as_ref().map(|e| e.id));
+        }
+    }
+    paths
+}
+
+/// A path as git stores it, unchanged where the OS allows any bytes, so it
+/// matches the `raw_path` of the file's delta.
+#[cfg(unix)]
+fn bytes_to_path(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn bytes_to_path(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Commits between the recorded and checked-out revision of a submodule:
+/// new commits as added rows, commits no longer included as deleted rows.
+fn submodule_log_hunk(repo: &Repository, path: &str, old: Oid, new: Oid) -> Option<DiffHunk> {
+    if old.is_zero() || new.is_zero() || old == new {
+        return None;
+    }
+    let sub = repo.find_submodule(path).ok()?.open().ok()?;
+    let mut rows = Vec::new();
+    for (from, to, line_type) in [(old, new, LineType::Added), (new, old, LineType::Deleted)] {
+        let mut walk = sub.revwalk().ok()?;
+        walk.push(to).ok()?;
+        walk.hide(from).ok()?;
+        for (i, oid) in walk.flatten().enumerate() {
+            let summary = sub
+                .find_commit(oid)
+                .ok()
+                .and_then(|c| c.summary().map(str::to_string))
+                .unwrap_or_default();
+            let line = Some(SideLine {
+                line_no: i as u32 + 1,
+                content: format!("{:.7} {summary}", oid),
+            });
+            let (left, right) = match line_type {
+                LineType::Deleted => (line, None),
+                _ => (None, line),
+            };
+            rows.push(SideBySideRow { left, right, line_type });
+        }
+    }
+    Some(DiffHunk {
+        header: format!("Submodule {path} {:.7}..{:.7}", old, new),
+        old_start: 0,
+        new_start: 0,
+        rows,
+    })
+}
+
 fn delta_status(delta: &DiffDelta) -> FileStatus {
     match delta.status() {
         Delta::Added | Delta::Untracked => {
@@ -173,13 +578,13 @@ fn delta_status(delta: &DiffDelta) -> FileStatus {
     }
 }
 
-fn delta_path(delta: &DiffDelta) -> String {
+fn delta_path(delta: &DiffDelta) -> PathBuf {
     delta
         .new_file()
         .path()
         .or_else(|| delta.old_file().path())
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|| "<unknown>".to_string())
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("<unknown>"))
 }
 
 fn raw_from_diff_line(line: &DiffLine) -> RawHunkLine {
@@ -278,27 +683,9 @@ fn align_hunk_lines(lines: &[RawHunkLine]) -> Vec<SideBySideRow> {
 }
 
 pub fn compute_stats(files: &[FileDiff]) -> DiffStats {
-    let mut additions = 0;
-    let mut deletions = 0;
+    let mut stats = DiffStats::default();
     for file in files {
-        for hunk in &file.hunks {
-            for row in &hunk.rows {
-                match row.line_type {
-                    LineType::Added => additions += 1,
-                    LineType::Deleted => {
-                        // Paired rows count as both a deletion and addition
-                        if row.right.is_some() {
-                            additions += 1;
-                        }
-                        deletions += 1;
-                    }
-                    _ => {}
-                }
-            }
-        }
-    }
-    DiffStats {
-        additions,
-        deletions,
+        stats.add(file.stats());
     }
+    stats
 }
@@ -1,6 +1,8 @@
-use crate::git::diff::{compute_stats, parse_diff, DiffState};
+use crate::git::diff::{compute_stats, parse_diff, parse_ref_diff, DiffHunk, DiffScope, DiffState};
 use anyhow::{Context, Result};
-use git2::Repository;
+use git2::{ApplyLocation, ApplyOptions, Diff, DiffOptions, Repository, ResetType};
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::path::Path;
 
 pub struct BranchInfo {
@@ -8,14 +10,61 @@ pub struct BranchInfo {
     pub is_head: bool,
 }
 
+#[derive(Debug, Clone)]
 pub struct CommitInfo {
     pub short_hash: String,
     pub full_hash: String,
     pub author: String,
+    pub email: String,
     pub date: String,
+    /// Time of day, `HH:MM`, in the commit's time zone
+    pub time: String,
+    /// Commit time in seconds since the epoch, for relative dates
+    pub epoch: i64,
     pub message: String,
 }
 
+impl CommitInfo {
+    /// The author's name, as `name <email>` when `with_email` is set.
+    pub fn author_label(&self, with_email: bool) -> String {
+        if with_email && !self.email.is_empty() {
+            format!("{} <{}>", self.author, self.email)
+        } else {
+            self.author.clone()
+        }
+    }
+}
+
+pub struct SubmoduleInfo {
+    pub path: String,
+    /// Commit recorded in HEAD / checked out in the submodule (short hashes)
+    pub head_id: Option<String>,
+    pub workdir_id: Option<String>,
+    /// The checked-out commit differs from the recorded one
+    pub moved: bool,
+    /// The submodule has uncommitted or untracked changes
+    pub dirty: bool,
+}
+
+/// Outcome of checking a commit's GPG / SSH signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// Signed, and `git verify-commit` accepts the signature
+    Verified,
+    /// Signed, but the signature is bad or its key isn't trusted or known
+    Unverified,
+    Unsigned,
+}
+
+/// A run of consecutive lines last changed by the same commit.
+pub struct BlameHunk {
+    /// First line of the run, 1-based
+    pub start: u32,
+    pub lines: u32,
+    /// None for lines that aren't committed yet
+    pub commit: Option<CommitInfo>,
+}
+
 pub struct ReflogEntry {
     pub short_hash: String,
     pub full_hash: String,
@@ -24,14 +73,83 @@ pub struct ReflogEntry {
     pub message: String,
 }
 
+/// How far `Repo::reset` goes, as in `git reset --soft/--mixed`. There is
+/// no hard mode; vig never discards working tree changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetMode {
+    /// Move HEAD only
+    Soft,
+    /// Move HEAD and reset the index
+    Mixed,
+}
+
+impl ResetMode {
+    pub const ALL: [ResetMode; 2] = [ResetMode::Soft, ResetMode::Mixed];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ResetMode::Soft => "Soft (keep index)",
+            ResetMode::Mixed => "Mixed (keep changes)",
+        }
+    }
+
+    pub fn key(self) -> char {
+        match self {
+            ResetMode::Soft => 's',
+            ResetMode::Mixed => 'm',
+        }
+    }
+}
+
+/// A multi-step git operation left in progress in the repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepoState {
+    Clean,
+    Merging,
+    /// With the current and total step when git records them
+    Rebasing(Option<(usize, usize)>),
+    CherryPicking,
+    Reverting,
+    Bisecting,
+    ApplyingPatches,
+}
+
+impl RepoState {
+    /// Header badge text, e.g. `REBASING 3/7`; None when nothing is in progress.
+    pub fn label(self) -> Option<String> {
+        let label = match self {
+            RepoState::Clean => return None,
+            RepoState::Merging => "MERGING".to_string(),
+            RepoState::Rebasing(Some((step, total))) => format!("REBASING {step}/{total}"),
+            RepoState::Rebasing(None) => "REBASING".to_string(),
+            RepoState::CherryPicking => "CHERRY-PICKING".to_string(),
+            RepoState::Reverting => "REVERTING".to_string(),
+            RepoState::Bisecting => "BISECTING".to_string(),
+            RepoState::ApplyingPatches => "APPLYING PATCHES".to_string(),
+        };
+        Some(label)
+    }
+}
+
 pub struct Repo {
     inner: Repository,
+    /// Diff with whitespace changes ignored, like `git diff -w`
+    ignore_whitespace: bool,
 }
 
 impl Repo {
     pub fn discover(path: &Path) -> Result<Self> {
-        let repo = Repository::discover(path).context("Not a git repository")?;
-        Ok(Self { inner: repo })
+        let repo = Repository::discover(path)
+            .with_context(|| format!("Not a git repository: {}", path.display()))?;
+        Ok(Self { inner: repo, ignore_whitespace: false })
+    }
+
+    pub fn set_ignore_whitespace(&mut self, ignore: bool) {
+        self.ignore_whitespace = ignore;
+    }
+
+    pub fn ignores_whitespace(&self) -> bool {
+        self.ignore_whitespace
     }
 
     pub fn workdir(&self) -> &Path {
@@ -41,23 +159,124 @@ impl Repo {
     }
 
     pub fn branch_name(&self) -> String {
-        self.inner
-            .head()
-            .ok()
-            .and_then(|r| {
-                if r.is_branch() {
-                    r.shorthand().map(|s| s.to_string())
-                } else {
-                    // Detached HEAD — show short hash
-                    r.target()
-                        .map(|oid| format!("{:.7}", oid))
-                }
-            })
-            .unwrap_or_else(|| "HEAD".to_string())
+        match self.inner.head() {
+            Ok(r) if r.is_branch() => r.shorthand().unwrap_or("HEAD").to_string(),
+            // Detached HEAD — show short hash
+            Ok(r) => r
+                .target()
+                .map(|oid| format!("{:.7} (detached)", oid))
+                .unwrap_or_else(|| "HEAD".to_string()),
+            Err(_) => match self.unborn_branch() {
+                Some(name) => format!("{name} (no commits yet)"),
+                None => "HEAD".to_string(),
+            },
+        }
     }
 
-    pub fn diff_workdir(&self, base_ref: Option<&str>) -> Result<DiffState> {
-        let files = parse_diff(&self.inner, base_ref)?;
+    /// Commits HEAD's branch is ahead of and behind its upstream. `None` when
+    /// detached, unborn, or the branch tracks nothing.
+    pub fn upstream_ahead_behind(&self) -> Option<(usize, usize)> {
+        let name = self.head_branch()?;
+        let branch = self.inner.find_branch(&name, git2::BranchType::Local).ok()?;
+        let local = branch.get().target()?;
+        let upstream = branch.upstream().ok()?.get().target()?;
+        self.inner.graph_ahead_behind(local, upstream).ok()
+    }
+
+    /// Name of the branch HEAD points to, or `None` when detached or unborn.
+    fn head_branch(&self) -> Option<String> {
+        let head = self.inner.head().ok()?;
+        if head.is_branch() {
+            head.shorthand().map(|s| s.to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Branch HEAD points to in a repository without commits.
+    fn unborn_branch(&self) -> Option<String> {
+        let head = self.inner.find_reference("HEAD").ok()?;
+        let target = head.symbolic_target()?;
+        Some(target.strip_prefix("refs/heads/").unwrap_or(target).to_string())
+    }
+
+    /// The operation in progress, if any. libgit2 doesn't expose rebase
+    /// progress, so it is read from the rebase directory git keeps.
+    pub fn state(&self) -> RepoState {
+        use git2::RepositoryState as S;
+        match self.inner.state() {
+            S::Clean => RepoState::Clean,
+            S::Merge => RepoState::Merging,
+            S::Revert | S::RevertSequence => RepoState::Reverting,
+            S::CherryPick | S::CherryPickSequence => RepoState::CherryPicking,
+            S::Bisect => RepoState::Bisecting,
+            S::ApplyMailbox => RepoState::ApplyingPatches,
+            S::Rebase | S::RebaseInteractive | S::RebaseMerge | S::ApplyMailboxOrRebase => {
+                let git_dir = self.inner.path();
+                let progress = read_rebase_progress(&git_dir.join("rebase-merge"), "msgnum", "end")
+                    .or_else(|| read_rebase_progress(&git_dir.join("rebase-apply"), "next", "last"));
+                RepoState::Rebasing(progress)
+            }
+        }
+    }
+
+    /// Who last changed each line of `path` as of `rev`, or, without one, in
+    /// the working tree, where lines not committed yet have no commit.
+    pub fn blame(&self, path: &Path, rev: Option<&str>) -> Result<Vec<BlameHunk>> {
+        let mut opts = git2::BlameOptions::new();
+        if let Some(rev) = rev {
+            let commit = self
+                .inner
+                .revparse_single(rev)
+                .and_then(|o| o.peel_to_commit())
+                .with_context(|| format!("Unknown revision {rev}"))?;
+            opts.newest_commit(commit.id());
+        }
+        let committed = match self.inner.blame_file(path, Some(&mut opts)) {
+            Ok(blame) => blame,
+            // A file new in the working tree has no history to blame
+            Err(_) if rev.is_none() => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let worktree;
+        let blame = match rev {
+            Some(_) => &committed,
+            None => {
+                let contents = std::fs::read(self.workdir().join(path))?;
+                worktree = committed.blame_buffer(&contents)?;
+                &worktree
+            }
+        };
+
+        let mut commits: HashMap<git2::Oid, CommitInfo> = HashMap::new();
+        let mut hunks = Vec::with_capacity(blame.len());
+        for hunk in blame.iter() {
+            let id = hunk.final_commit_id();
+            let commit = if id.is_zero() {
+                None
+            } else if let Some(info) = commits.get(&id) {
+                Some(info.clone())
+            } else {
+                let info = commit_info(&self.inner.find_commit(id)?);
+                commits.insert(id, info.clone());
+                Some(info)
+            };
+            hunks.push(BlameHunk {
+                start: hunk.final_start_line() as u32,
+                lines: hunk.lines_in_hunk() as u32,
+                commit,
+            });
+        }
+        Ok(hunks)
+    }
+
+    /// True while HEAD's branch has no commits (freshly initialized repository).
+    pub fn is_unborn(&self) -> bool {
+        matches!(self.inner.head(), Err(e) if e.code() == git2::ErrorCode::UnbornBranch)
+    }
+
+    pub fn diff_workdir(&self, base_ref: Option<&str>, scope: DiffScope) -> Result<DiffState> {
+        let files = parse_diff(&self.inner, base_ref, scope, self.ignore_whitespace)?;
         let stats = compute_stats(&files);
         let branch_name = self.branch_name();
         Ok(DiffState {
@@ -67,8 +286,38 @@ impl Repo {
         })
     }
 
+    /// Diff between two refs (`old..new`), independent of the working tree.
+    pub fn diff_refs(&self, old: &str, new: &str) -> Result<DiffState> {
+        let files = parse_ref_diff(&self.inner, old, new, self.ignore_whitespace)?;
+        let stats = compute_stats(&files);
+        let branch_name = self.branch_name();
+        Ok(DiffState {
+            files,
+            branch_name,
+            stats,
+        })
+    }
+
+    /// Whether `spec` definitively doesn't name a tree, as opposed to failing
+    /// to resolve for a moment (e.g. while a ref is being rewritten).
+    pub fn ref_is_invalid(&self, spec: &str) -> bool {
+        match self.inner.revparse_single(spec) {
+            Ok(obj) => obj.peel_to_tree().is_err(),
+            Err(e) => matches!(
+                e.code(),
+                git2::ErrorCode::NotFound | git2::ErrorCode::InvalidSpec | git2::ErrorCode::Ambiguous
+            ),
+        }
+    }
+
+    /// Whether `spec` fails to resolve only because nothing by that name
+    /// exists right now, which can be a ref caught mid-rewrite.
+    pub fn ref_is_missing(&self, spec: &str) -> bool {
+        self.inner.revparse_single(spec).is_err_and(|e| e.code() == git2::ErrorCode::NotFound)
+    }
+
     pub fn list_local_branches(&self) -> Vec<BranchInfo> {
-        let head_name = self.branch_name();
+        let head_name = self.head_branch();
         let mut branches: Vec<BranchInfo> =
             match self.inner.branches(Some(git2::BranchType::Local)) {
                 Ok(iter) => iter
@@ -76,7 +325,7 @@ impl Repo {
                     .filter_map(|(branch, _)| {
                         branch.name().ok().flatten().map(|name| BranchInfo {
                             name: name.to_string(),
-                            is_head: name == head_name,
+                            is_head: head_name.as_deref() == Some(name),
                         })
                     })
                     .collect(),
@@ -90,6 +339,33 @@ impl Repo {
         branches
     }
 
+    /// Refs to offer as a diff base: local branches, remote branches, tags,
+    /// then `HEAD~1`..`HEAD~5` as far as history goes.
+    pub fn list_refs(&self) -> Vec<String> {
+        let mut refs: Vec<String> =
+            self.list_local_branches().into_iter().map(|b| b.name).collect();
+        if let Ok(iter) = self.inner.branches(Some(git2::BranchType::Remote)) {
+            let mut remotes: Vec<String> = iter
+                .filter_map(|b| b.ok())
+                .filter_map(|(branch, _)| branch.name().ok().flatten().map(str::to_string))
+                .filter(|name| !name.ends_with("/HEAD"))
+                .collect();
+            remotes.sort();
+            refs.extend(remotes);
+        }
+        if let Ok(tags) = self.inner.tag_names(None) {
+            let mut tags: Vec<String> = tags.iter().flatten().map(str::to_string).collect();
+            tags.sort();
+            refs.extend(tags);
+        }
+        refs.extend(
+            (1..=5)
+                .map(|n| format!("HEAD~{n}"))
+                .take_while(|spec| self.inner.revparse_single(spec).is_ok()),
+        );
+        refs
+    }
+
     pub fn log_for_ref(&self, ref_name: &str, limit: usize) -> Vec<CommitInfo> {
         let obj = match self.inner.revparse_single(ref_name) {
             Ok(obj) => obj,
@@ -114,26 +390,74 @@ impl Repo {
                 Ok(c) => c,
                 Err(_) => continue,
             };
-            let hash_str = oid.to_string();
-            let short_hash = hash_str[..7.min(hash_str.len())].to_string();
-            let author = commit
-                .author()
-                .name()
-                .unwrap_or("unknown")
-                .to_string();
-            let date = epoch_to_date(commit.time().seconds());
-            let message = commit.summary().unwrap_or("").to_string();
-            commits.push(CommitInfo {
-                short_hash,
-                full_hash: hash_str,
-                author,
-                date,
-                message,
-            });
+            commits.push(commit_info(&commit));
         }
         commits
     }
 
+    /// Walk the whole history of `ref_name` for commits whose hash starts
+    /// with `pattern` or whose message or author contains it (ignoring case).
+    pub fn search_log(&self, ref_name: &str, pattern: &str, limit: usize) -> Vec<CommitInfo> {
+        let obj = match self.inner.revparse_single(ref_name) {
+            Ok(obj) => obj,
+            Err(_) => return Vec::new(),
+        };
+        let mut revwalk = match self.inner.revwalk() {
+            Ok(rw) => rw,
+            Err(_) => return Vec::new(),
+        };
+        if revwalk.push(obj.id()).is_err() {
+            return Vec::new();
+        }
+        let _ = revwalk.set_sorting(git2::Sort::TIME);
+
+        let pattern = pattern.to_lowercase();
+        let mut commits = Vec::new();
+        for oid in revwalk {
+            let Ok(oid) = oid else {
+                break;
+            };
+            let Ok(commit) = self.inner.find_commit(oid) else {
+                continue;
+            };
+            let matches = oid.to_string().starts_with(&pattern)
+                || commit.message().is_some_and(|m| m.to_lowercase().contains(&pattern))
+                || commit.author().name().is_some_and(|a| a.to_lowercase().contains(&pattern));
+            if matches {
+                commits.push(commit_info(&commit));
+                if commits.len() >= limit {
+                    break;
+                }
+            }
+        }
+        commits
+    }
+
+    /// Check the signature of commit `hash`. Unsigned commits are told apart
+    /// by libgit2; signed ones go through `git verify-commit`, which knows
+    /// about gpg, ssh `allowedSignersFile` and the rest of the user's setup.
+    pub fn signature_status(&self, hash: &str) -> SignatureStatus {
+        let Ok(oid) = git2::Oid::from_str(hash) else {
+            return SignatureStatus::Unsigned;
+        };
+        if self.inner.extract_signature(&oid, None).is_err() {
+            return SignatureStatus::Unsigned;
+        }
+        let verified = std::process::Command::new("git")
+            .arg("verify-commit")
+            .arg(hash)
+            .current_dir(self.workdir())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+        if verified {
+            SignatureStatus::Verified
+        } else {
+            SignatureStatus::Unverified
+        }
+    }
+
     /// Switch to the given branch using `git switch`.
     pub fn switch_branch(&self, name: &str) -> Result<()> {
         let workdir = self.workdir();
@@ -167,6 +491,20 @@ impl Repo {
         Ok(())
     }
 
+    /// Reset HEAD (and the current branch) to the given commit.
+    pub fn reset(&self, target: &str, mode: ResetMode) -> Result<()> {
+        let object = self
+            .inner
+            .revparse_single(target)
+            .with_context(|| format!("Unknown commit: {target}"))?;
+        let kind = match mode {
+            ResetMode::Soft => ResetType::Soft,
+            ResetMode::Mixed => ResetType::Mixed,
+        };
+        self.inner.reset(&object, kind, None).context("Reset failed")?;
+        Ok(())
+    }
+
     pub fn reflog(&self, limit: usize) -> Vec<ReflogEntry> {
         let reflog = match self.inner.reflog("HEAD") {
             Ok(r) => r,
@@ -197,12 +535,221 @@ impl Repo {
             .collect()
     }
 
+    /// Commit id of the merge base between HEAD and `branch`.
+    pub fn merge_base_with_head(&self, branch: &str) -> Result<String> {
+        let head = self
+            .inner
+            .head()
+            .context("HEAD has no commits yet")?
+            .peel_to_commit()?
+            .id();
+        let other = self
+            .inner
+            .revparse_single(branch)
+            .with_context(|| format!("Cannot resolve '{branch}'"))?
+            .peel_to_commit()?
+            .id();
+        let base = self
+            .inner
+            .merge_base(head, other)
+            .with_context(|| format!("No merge base between HEAD and '{branch}'"))?;
+        Ok(base.to_string())
+    }
+
+    pub fn submodule_status(&self) -> Vec<SubmoduleInfo> {
+        let submodules = match self.inner.submodules() {
+            Ok(s) => s,
+            Err(_) => return Vec::new(),
+        };
+        submodules
+            .iter()
+            .filter_map(|sm| {
+                let status = self
+                    .inner
+                    .submodule_status(sm.name()?, git2::SubmoduleIgnore::None)
+                    .ok()?;
+                let short = |oid: Option<git2::Oid>| oid.map(|o| format!("{:.7}", o));
+                Some(SubmoduleInfo {
+                    path: sm.path().to_string_lossy().into_owned(),
+                    head_id: short(sm.head_id()),
+                    workdir_id: short(sm.workdir_id()),
+                    moved: status.is_wd_modified(),
+                    dirty: status.intersects(
+                        git2::SubmoduleStatus::WD_WD_MODIFIED
+                            | git2::SubmoduleStatus::WD_INDEX_MODIFIED
+                            | git2::SubmoduleStatus::WD_UNTRACKED,
+                    ),
+                })
+            })
+            .collect()
+    }
+
+    /// URL of the `origin` remote, or of the first remote if there's no origin.
+    pub fn remote_url(&self) -> Option<String> {
+        let remote = match self.inner.find_remote("origin") {
+            Ok(remote) => remote,
+            Err(_) => {
+                let names = self.inner.remotes().ok()?;
+                let name = names.iter().flatten().next()?;
+                self.inner.find_remote(name).ok()?
+            }
+        };
+        remote.url().map(str::to_string)
+    }
+
+    /// Short header label for a diff base: branches and tags as `name (kind)`,
+    /// anything else as a 7-char hash with the commit summary.
+    pub fn describe_ref(&self, spec: &str) -> String {
+        if spec == "HEAD" {
+            return spec.to_string();
+        }
+        if let Ok(r) = self.inner.resolve_reference_from_short_name(spec) {
+            let kind = if r.is_branch() {
+                "branch"
+            } else if r.is_remote() {
+                "remote"
+            } else if r.is_tag() {
+                "tag"
+            } else {
+                "ref"
+            };
+            return format!("{spec} ({kind})");
+        }
+        match self.inner.revparse_single(spec).and_then(|o| o.peel_to_commit()) {
+            Ok(commit) => {
+                let summary = commit.summary().unwrap_or("");
+                let summary = if summary.chars().count() > 30 {
+                    format!("{}…", summary.chars().take(29).collect::<String>())
+                } else {
+                    summary.to_string()
+                };
+                format!("{:.7} {summary}", commit.id()).trim_end().to_string()
+            }
+            Err(_) => spec.to_string(),
+        }
+    }
+
+    /// Contents of `path` in the diff base tree (`base_ref`, or HEAD).
+    pub fn base_file_bytes(&self, base_ref: Option<&str>, path: &Path) -> Option<Vec<u8>> {
+        let tree = match base_ref {
+            Some(r) => self.inner.revparse_single(r).ok()?.peel_to_tree().ok()?,
+            None => self.inner.head().ok()?.peel_to_tree().ok()?,
+        };
+        let entry = tree.get_path(path).ok()?;
+        let blob = self.inner.find_blob(entry.id()).ok()?;
+        Some(blob.content().to_vec())
+    }
+
+    /// Stage one hunk of `path`, or unstage it with `reverse`. The hunk is
+    /// taken from the index-vs-workdir diff when staging and from the
+    /// HEAD-vs-index diff when unstaging, and is matched by its start lines.
+    pub fn apply_hunk_to_index(&self, path: &Path, hunk: &DiffHunk, reverse: bool) -> Result<()> {
+        // libgit2 can't apply untracked deltas; a new file is a single hunk anyway
+        let untracked = self
+            .inner
+            .status_file(path)
+            .is_ok_and(|s| s.contains(git2::Status::WT_NEW));
+        if untracked && !reverse {
+            let mut index = self.inner.index()?;
+            index.add_path(path)?;
+            index.write()?;
+            return Ok(());
+        }
+        let mut opts = hunk_diff_options(path, reverse);
+        let diff = if reverse {
+            let head = self.inner.head().ok().and_then(|r| r.peel_to_tree().ok());
+            self.inner.diff_tree_to_index(head.as_ref(), None, Some(&mut opts))?
+        } else {
+            self.inner.diff_index_to_workdir(None, Some(&mut opts))?
+        };
+        self.apply_one_hunk(&diff, hunk, reverse)
+    }
+
+    /// Apply to the index only the hunk of `diff` that starts where `hunk`
+    /// does (with old and new swapped when `diff` was generated reversed).
+    fn apply_one_hunk(&self, diff: &Diff, hunk: &DiffHunk, reversed: bool) -> Result<()> {
+        // Adding or removing a whole file is a single hunk, and libgit2
+        // applies it without consulting the hunk callback
+        let whole_file = diff
+            .deltas()
+            .any(|d| matches!(d.status(), git2::Delta::Added | git2::Delta::Deleted));
+        if whole_file {
+            return self.inner.apply(diff, ApplyLocation::Index, None).context("Hunk does not apply");
+        }
+        let (old_start, new_start) = if reversed {
+            (hunk.new_start, hunk.old_start)
+        } else {
+            (hunk.old_start, hunk.new_start)
+        };
+        let found = Cell::new(false);
+        let mut apply_opts = ApplyOptions::new();
+        apply_opts.hunk_callback(|h| {
+            let hit = h.is_some_and(|h| h.old_start() == old_start && h.new_start() == new_start);
+            found.set(found.get() || hit);
+            hit
+        });
+        self.inner
+            .apply(diff, ApplyLocation::Index, Some(&mut apply_opts))
+            .context("Hunk does not apply")?;
+        if !found.get() {
+            anyhow::bail!("Hunk not found; refresh and try again");
+        }
+        Ok(())
+    }
+
+    /// Contents of `path` as staged in the index.
+    pub fn index_file_bytes(&self, path: &Path) -> Option<Vec<u8>> {
+        let index = self.inner.index().ok()?;
+        let entry = index.get_path(path, 0)?;
+        let blob = self.inner.find_blob(entry.id).ok()?;
+        Some(blob.content().to_vec())
+    }
+
     #[allow(dead_code)]
     pub fn inner(&self) -> &Repository {
         &self.inner
     }
 }
 
+/// Diff options limited to exactly `path`, including untracked content so
+/// new files can be staged hunk by hunk.
+fn hunk_diff_options(path: &Path, reverse: bool) -> DiffOptions {
+    let mut opts = DiffOptions::new();
+    opts.pathspec(path)
+        .disable_pathspec_match(true)
+        .include_untracked(true)
+        .show_untracked_content(true)
+        .reverse(reverse);
+    opts
+}
+
+fn commit_info(commit: &git2::Commit) -> CommitInfo {
+    let hash_str = commit.id().to_string();
+    let short_hash = hash_str[..7.min(hash_str.len())].to_string();
+    let author = commit
+        .author()
+        .name()
+        .unwrap_or("unknown")
+        .to_string();
+    let email = commit.author().email().unwrap_or_default().to_string();
+    // Date and time the author wrote the commit, in their own time zone
+    // rather than UTC
+    let time = commit.author().when();
+    let epoch = time.seconds();
+    let local = epoch + i64::from(time.offset_minutes()) * 60;
+    let message = commit.summary().unwrap_or("").to_string();
+    CommitInfo {
+        short_hash,
+        full_hash: hash_str,
+        author,
+        email,
+        date: epoch_to_date(local),
+        time: epoch_to_time(local),
+        epoch,
+        message,
+    }
+}
+
 fn epoch_to_date(epoch: i64) -> String {
     // Howard Hinnant's civil_from_days algorithm
     let z = (epoch / 86400) as i32 + 719468;
@@ -217,3 +764,192 @@ fn epoch_to_date(epoch: i64) -> String {
     let y = if m <= 2 { y + 1 } else { y };
     format!("{y:04}-{m:02}-{d:02}")
 }
+
+fn epoch_to_time(epoch: i64) -> String {
+    let secs = epoch.rem_euclid(86400);
+    format!("{:02}:{:02}", secs / 3600, secs % 3600 / 60)
+}
+
+/// Age of `epoch` relative to `now`, e.g. "3 days ago".
+pub fn epoch_to_relative(epoch: i64, now: i64) -> String {
+    let secs = now - epoch;
+    if secs < 0 {
+        return "in the future".to_string();
+    }
+    let (n, unit) = match secs {
+        0..60 => return "just now".to_string(),
+        60..3600 => (secs / 60, "minute"),
+        3600..86400 => (secs / 3600, "hour"),
+        _ if secs < 14 * 86400 => (secs / 86400, "day"),
+        _ if secs < 70 * 86400 => (secs / (7 * 86400), "week"),
+        _ if secs < 365 * 86400 => (secs / (30 * 86400), "month"),
+        _ => (secs / (365 * 86400), "year"),
+    };
+    format!("{n} {unit}{} ago", if n == 1 { "" } else { "s" })
+}
+
+/// Current and total step of a rebase from the two counter files in `dir`.
+fn read_rebase_progress(dir: &Path, step: &str, total: &str) -> Option<(usize, usize)> {
+    let read = |name: &str| -> Option<usize> {
+        std::fs::read_to_string(dir.join(name)).ok()?.trim().parse().ok()
+    };
+    Some((read(step)?, read(total)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::diff::FileStatus;
+    use git2::{RepositoryInitOptions, Signature};
+    use tempfile::TempDir;
+
+    /// A repository in a temp dir whose HEAD points at `main`.
+    fn init_repo() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let mut opts = RepositoryInitOptions::new();
+        opts.initial_head("main");
+        let repo = Repository::init_opts(dir.path(), &opts).unwrap();
+        (dir, repo)
+    }
+
+    /// Commit `contents` as `path` on top of HEAD.
+    fn commit_file(repo: &Repository, path: &str, contents: &[u8], message: &str) -> git2::Oid {
+        std::fs::write(repo.workdir().unwrap().join(path), contents).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents).unwrap()
+    }
+
+    #[test]
+    fn fresh_repository_is_unborn() {
+        let (dir, _) = init_repo();
+        let repo = Repo::discover(dir.path()).unwrap();
+        assert!(repo.is_unborn());
+        assert_eq!(repo.head_branch(), None);
+        assert_eq!(repo.branch_name(), "main (no commits yet)");
+        assert_eq!(repo.describe_ref("HEAD"), "HEAD");
+    }
+
+    #[test]
+    fn detached_checkout_has_no_head_branch() {
+        let (dir, git) = init_repo();
+        let first = commit_file(&git, "a.txt", b"one\n", "First commit");
+        commit_file(&git, "a.txt", b"two\n", "Second commit");
+        git.set_head_detached(first).unwrap();
+
+        let repo = Repo::discover(dir.path()).unwrap();
+        let short = format!("{first:.7}");
+        assert!(!repo.is_unborn());
+        assert_eq!(repo.head_branch(), None);
+        assert_eq!(repo.branch_name(), format!("{short} (detached)"));
+        assert_eq!(repo.describe_ref(&short), format!("{short} First commit"));
+        assert_eq!(repo.describe_ref("main"), "main (branch)");
+    }
+
+    #[test]
+    fn hunks_of_a_partly_staged_file_apply_at_their_index_positions() {
+        let (dir, git) = init_repo();
+        let lines: Vec<String> = (1..=30).map(|n| format!("line {n}\n")).collect();
+        commit_file(&git, "a.txt", lines.concat().as_bytes(), "Initial commit");
+        let mut edited = lines.clone();
+        edited[19] = "line 20 changed\n".to_string();
+        let mut top_only = lines.clone();
+        for added in ["new 1\n", "new 2\n", "new 3\n"] {
+            edited.insert(0, added.to_string());
+            top_only.insert(0, added.to_string());
+        }
+        std::fs::write(dir.path().join("a.txt"), edited.concat()).unwrap();
+        let path = Path::new("a.txt");
+        let repo = Repo::discover(dir.path()).unwrap();
+        let hunk = |scope, idx: usize| {
+            let state = repo.diff_workdir(None, scope).unwrap();
+            state.files[0].hunks[idx].clone()
+        };
+
+        // Staging the insertion shifts the change at line 20 in the index
+        repo.apply_hunk_to_index(path, &hunk(DiffScope::Unstaged, 0), false).unwrap();
+        let unstaged = hunk(DiffScope::Unstaged, 0);
+        assert_eq!((unstaged.old_start, unstaged.new_start), (20, 20));
+        repo.apply_hunk_to_index(path, &unstaged, false).unwrap();
+        assert_eq!(repo.index_file_bytes(path).unwrap(), edited.concat().as_bytes());
+
+        repo.apply_hunk_to_index(path, &hunk(DiffScope::Staged, 1), true).unwrap();
+        assert_eq!(repo.index_file_bytes(path).unwrap(), top_only.concat().as_bytes());
+    }
+
+    #[test]
+    fn commit_is_dated_in_its_author_time_zone() {
+        let (_dir, git) = init_repo();
+        let tree = git.find_tree(git.index().unwrap().write_tree().unwrap()).unwrap();
+        // 2024-01-15 23:00 at +09:00, which is still 14:00 that day in UTC
+        // and already the next day at the +10:00 the committer used
+        let author = Signature::new("Test", "test@example.com", &git2::Time::new(1705327200, 540))
+            .unwrap();
+        let committer =
+            Signature::new("Test", "test@example.com", &git2::Time::new(1705327200, 600))
+                .unwrap();
+        let oid = git.commit(Some("HEAD"), &author, &committer, "Late commit", &tree, &[]).unwrap();
+
+        let info = commit_info(&git.find_commit(oid).unwrap());
+        assert_eq!(info.date, "2024-01-15");
+        assert_eq!(info.time, "23:00");
+        assert_eq!(info.epoch, 1705327200);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn latin1_file_name_keeps_its_raw_path() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let (dir, git) = init_repo();
+        // "café.txt" encoded as latin-1, which is not valid UTF-8
+        let name = std::ffi::OsStr::from_bytes(b"caf\xe9.txt");
+        commit_file(&git, "keep.txt", b"keep\n", "Initial commit");
+        std::fs::write(dir.path().join(name), b"old\n").unwrap();
+        let mut index = git.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        std::fs::write(dir.path().join(name), b"new\n").unwrap();
+
+        let repo = Repo::discover(dir.path()).unwrap();
+        let state = repo.diff_workdir(None, DiffScope::All).unwrap();
+        let file = state.files.iter().find(|f| f.path != "keep.txt").unwrap();
+        assert_eq!(file.path, "caf\u{FFFD}.txt");
+        assert_eq!(file.raw_path.as_os_str(), name);
+        assert_eq!(repo.index_file_bytes(&file.raw_path).as_deref(), Some(&b"old\n"[..]));
+        assert_eq!(std::fs::read(repo.workdir().join(&file.raw_path)).unwrap(), b"new\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn latin1_conflicted_file_stays_conflicted() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let (dir, git) = init_repo();
+        let name = std::ffi::OsStr::from_bytes(b"caf\xe9.txt");
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let commit = |contents: &[u8], update_ref: &str, parents: &[&git2::Commit]| {
+            std::fs::write(dir.path().join(name), contents).unwrap();
+            let mut index = git.index().unwrap();
+            index.add_path(Path::new(name)).unwrap();
+            index.write().unwrap();
+            let tree = git.find_tree(index.write_tree().unwrap()).unwrap();
+            let oid = git.commit(Some(update_ref), &sig, &sig, "Change", &tree, parents).unwrap();
+            git.find_commit(oid).unwrap()
+        };
+        let base = commit(b"base\n", "HEAD", &[]);
+        let theirs = commit(b"theirs\n", "refs/heads/other", &[&base]);
+        commit(b"ours\n", "HEAD", &[&base]);
+        git.merge(&[&git.find_annotated_commit(theirs.id()).unwrap()], None, None).unwrap();
+
+        let repo = Repo::discover(dir.path()).unwrap();
+        let state = repo.diff_workdir(None, DiffScope::All).unwrap();
+        let file = state.files.iter().find(|f| f.raw_path.as_os_str() == name).unwrap();
+        assert_eq!(file.status, FileStatus::Conflicted);
+    }
+}
@@ -1,42 +1,85 @@
-use crate::event::Event;
+use crate::event::{Event, FsChange};
 use anyhow::Result;
+use git2::Repository;
+use notify::{EventKind, Watcher};
 use std::path::Path;
-use std::sync::mpsc::Sender;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
 use std::time::Duration;
 
 pub struct FsWatcher {
-    _debouncer: notify_debouncer_mini::Debouncer<notify::RecommendedWatcher>,
+    _watcher: notify::RecommendedWatcher,
 }
 
 impl FsWatcher {
-    pub fn new(watch_path: &Path, tx: Sender<Event>) -> Result<Self> {
-        let debouncer = notify_debouncer_mini::new_debouncer(
-            Duration::from_millis(500),
-            move |events: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
-                if let Ok(events) = events {
-                    let dominated_by_git_internal = events.iter().all(|e| {
-                        let in_git = e.path.components().any(|c| c.as_os_str() == ".git");
-                        let is_index = e.path.ends_with(".git/index");
-                        let is_refs = e.path.components().zip(e.path.components().skip(1))
-                            .any(|(a, b)| a.as_os_str() == ".git" && b.as_os_str() == "refs");
-                        let is_packed_refs = e.path.ends_with("packed-refs");
-                        in_git && !is_index && !is_refs && !is_packed_refs
-                    });
-                    // Skip if ALL events are .git-internal (except index changes)
-                    if !dominated_by_git_internal {
-                        let _ = tx.send(Event::FsChange);
-                    }
+    pub fn new(watch_path: &Path, debounce: Duration, tx: Sender<Event>) -> Result<Self> {
+        // Batch everything that arrives within one debounce window into a single refresh
+        let (raw_tx, raw_rx) = mpsc::channel::<FsChange>();
+        thread::spawn(move || {
+            while let Ok(mut change) = raw_rx.recv() {
+                thread::sleep(debounce);
+                while let Ok(more) = raw_rx.try_recv() {
+                    change.refs |= more.refs;
+                    change.workdir |= more.workdir;
                 }
-            },
-        )?;
+                if tx.send(Event::FsChange(change)).is_err() {
+                    return;
+                }
+            }
+        });
+
+        // Separate handle so the callback thread can consult .gitignore rules
+        let repo = Repository::open(watch_path).ok();
+        let root = watch_path.to_path_buf();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let Ok(event) = res else {
+                return;
+            };
+            // Reads (including vig's own) never change the diff
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            let mut change = FsChange::default();
+            for path in &event.paths {
+                match classify(path, &root, repo.as_ref()) {
+                    Some(ChangeKind::Refs) => change.refs = true,
+                    Some(ChangeKind::Workdir) => change.workdir = true,
+                    None => {}
+                }
+            }
+            if change.refs || change.workdir {
+                let _ = raw_tx.send(change);
+            }
+        })?;
+        watcher.watch(watch_path, notify::RecursiveMode::Recursive)?;
 
-        let mut debouncer = debouncer;
-        debouncer
-            .watcher()
-            .watch(watch_path, notify::RecursiveMode::Recursive)?;
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+enum ChangeKind {
+    Refs,
+    Workdir,
+}
 
-        Ok(Self {
-            _debouncer: debouncer,
-        })
+/// What a change at `path` can alter: refs and HEAD move branches and the
+/// reflog, the index and tracked files only the diff. Other `.git` internals
+/// and ignored files alter nothing.
+fn classify(path: &Path, root: &Path, repo: Option<&Repository>) -> Option<ChangeKind> {
+    let in_git = path.components().any(|c| c.as_os_str() == ".git");
+    if in_git {
+        let is_refs = path
+            .components()
+            .zip(path.components().skip(1))
+            .any(|(a, b)| a.as_os_str() == ".git" && b.as_os_str() == "refs");
+        if is_refs || path.ends_with("packed-refs") || path.ends_with(".git/HEAD") {
+            return Some(ChangeKind::Refs);
+        }
+        return path.ends_with(".git/index").then_some(ChangeKind::Workdir);
     }
+    let ignored = match (repo, path.strip_prefix(root)) {
+        (Some(repo), Ok(rel)) => repo.is_path_ignored(rel).unwrap_or(false),
+        _ => false,
+    };
+    (!ignored).then_some(ChangeKind::Workdir)
 }
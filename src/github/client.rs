@@ -1,4 +1,6 @@
 use crate::github::types::*;
+use std::ffi::OsStr;
+use std::path::Path;
 use std::process::Command;
 
 pub fn check_gh_available() -> Result<(), String> {
@@ -33,16 +35,21 @@ pub fn list_issues(limit: usize) -> Result<Vec<GhIssueListItem>, String> {
     serde_json::from_slice(&output.stdout).map_err(|e| format!("JSON parse error: {e}"))
 }
 
-pub fn list_prs(limit: usize) -> Result<Vec<GhPrListItem>, String> {
-    let output = Command::new("gh")
-        .args([
-            "pr",
-            "list",
-            "--json",
-            "number,title,state,author,labels,headRefName,createdAt,reviewDecision,isDraft",
-            "--limit",
-            &limit.to_string(),
-        ])
+/// `author` is passed through to `gh pr list --author` (e.g. `@me`).
+pub fn list_prs(limit: usize, author: Option<&str>) -> Result<Vec<GhPrListItem>, String> {
+    let mut cmd = Command::new("gh");
+    cmd.args([
+        "pr",
+        "list",
+        "--json",
+        "number,title,state,author,labels,headRefName,createdAt,reviewDecision,isDraft",
+        "--limit",
+        &limit.to_string(),
+    ]);
+    if let Some(author) = author {
+        cmd.args(["--author", author]);
+    }
+    let output = cmd
         .output()
         .map_err(|e| format!("gh pr list failed: {e}"))?;
     if !output.status.success() {
@@ -52,8 +59,13 @@ pub fn list_prs(limit: usize) -> Result<Vec<GhPrListItem>, String> {
     serde_json::from_slice(&output.stdout).map_err(|e| format!("JSON parse error: {e}"))
 }
 
-pub fn get_issue(number: u64) -> Result<GhIssueDetail, String> {
-    let output = Command::new("gh")
+/// The JSON `gh` printed for a detail view, kept with `--debug`
+pub type RawJson = Option<String>;
+
+/// Fetch one issue. With `keep_raw` the JSON as `gh` printed it comes back
+/// too, also when it doesn't parse.
+pub fn get_issue(number: u64, keep_raw: bool) -> (Result<GhIssueDetail, String>, RawJson) {
+    let output = match Command::new("gh")
         .args([
             "issue",
             "view",
@@ -62,12 +74,25 @@ pub fn get_issue(number: u64) -> Result<GhIssueDetail, String> {
             "number,title,state,author,body,comments,labels,createdAt",
         ])
         .output()
-        .map_err(|e| format!("gh issue view failed: {e}"))?;
+    {
+        Ok(output) => output,
+        Err(e) => return (Err(format!("gh issue view failed: {e}")), None),
+    };
+    parse_view(output, keep_raw)
+}
+
+/// Parse the output of `gh … view --json`, keeping stdout if asked.
+fn parse_view<T: serde::de::DeserializeOwned>(
+    output: std::process::Output,
+    keep_raw: bool,
+) -> (Result<T, String>, RawJson) {
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(stderr.trim().to_string());
+        return (Err(stderr.trim().to_string()), None);
     }
-    serde_json::from_slice(&output.stdout).map_err(|e| format!("JSON parse error: {e}"))
+    let raw = keep_raw.then(|| String::from_utf8_lossy(&output.stdout).into_owned());
+    let parsed = serde_json::from_slice(&output.stdout).map_err(|e| format!("JSON parse error: {e}"));
+    (parsed, raw)
 }
 
 pub fn open_issue_in_browser(number: u64) -> Result<(), String> {
@@ -105,7 +130,39 @@ pub fn repo_nwo() -> Option<String> {
     }
 }
 
+/// Web URL of a repository from its remote URL: `git@host:owner/repo.git`,
+/// `ssh://git@host/owner/repo` and `https://host/owner/repo.git` all become
+/// `https://host/owner/repo`.
+pub fn web_url_from_remote(remote: &str) -> Option<String> {
+    let rest = match remote.split_once("://") {
+        Some((_, rest)) => rest.to_string(),
+        // scp-like syntax: user@host:path
+        None => remote.replacen(':', "/", 1),
+    };
+    let rest = rest.rsplit_once('@').map_or(rest.as_str(), |(_, r)| r);
+    let (host, path) = rest.split_once('/')?;
+    // Drop any port; the web UI doesn't share the SSH one
+    let host = host.split(':').next()?;
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let mut parts = path.split('/');
+    let (owner, repo) = (parts.next()?, parts.next()?);
+    if host.is_empty() || owner.is_empty() || repo.is_empty() || parts.next().is_some() {
+        return None;
+    }
+    Some(format!("https://{host}/{owner}/{repo}"))
+}
+
 pub fn open_url(url: &str) -> Result<(), String> {
+    system_open(url.as_ref()).map_err(|e| format!("Failed to open URL: {e}"))
+}
+
+/// Open a local file with the system's default application.
+pub fn open_path(path: &Path) -> Result<(), String> {
+    system_open(path.as_os_str()).map_err(|e| format!("Failed to open file: {e}"))
+}
+
+fn system_open(target: &OsStr) -> std::io::Result<()> {
     #[cfg(target_os = "macos")]
     let cmd = "open";
     #[cfg(target_os = "linux")]
@@ -114,29 +171,53 @@ pub fn open_url(url: &str) -> Result<(), String> {
     let cmd = "start";
 
     Command::new(cmd)
-        .arg(url)
+        .arg(target)
         .stdin(std::process::Stdio::null())
         .stdout(std::process::Stdio::null())
         .stderr(std::process::Stdio::null())
-        .spawn()
-        .map_err(|e| format!("Failed to open URL: {e}"))?;
+        .spawn()?;
     Ok(())
 }
 
-pub fn get_pr(number: u64) -> Result<GhPrDetail, String> {
+/// Run a `gh pr` subcommand that changes the PR; gh's stderr is the error.
+fn run_pr_action(args: &[&str]) -> Result<(), String> {
     let output = Command::new("gh")
+        .arg("pr")
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| format!("gh pr {} failed: {e}", args[0]))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.trim().to_string());
+    }
+    Ok(())
+}
+
+/// Mark a PR as a draft, or as ready for review (`gh pr ready [--undo]`).
+pub fn set_pr_draft(number: u64, draft: bool) -> Result<(), String> {
+    let number = number.to_string();
+    if draft {
+        run_pr_action(&["ready", &number, "--undo"])
+    } else {
+        run_pr_action(&["ready", &number])
+    }
+}
+
+/// Fetch one PR, like `get_issue`.
+pub fn get_pr(number: u64, keep_raw: bool) -> (Result<GhPrDetail, String>, RawJson) {
+    let output = match Command::new("gh")
         .args([
             "pr",
             "view",
             &number.to_string(),
             "--json",
-            "number,title,state,author,body,comments,reviews,labels,createdAt,reviewDecision,statusCheckRollup,additions,deletions,changedFiles,headRefName",
+            "number,title,state,author,body,comments,reviews,labels,createdAt,reviewDecision,statusCheckRollup,additions,deletions,changedFiles,headRefName,isDraft,mergeable,mergeStateStatus",
         ])
         .output()
-        .map_err(|e| format!("gh pr view failed: {e}"))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(stderr.trim().to_string());
-    }
-    serde_json::from_slice(&output.stdout).map_err(|e| format!("JSON parse error: {e}"))
+    {
+        Ok(output) => output,
+        Err(e) => return (Err(format!("gh pr view failed: {e}")), None),
+    };
+    parse_view(output, keep_raw)
 }
@@ -1,9 +1,11 @@
 use crate::github::client;
 use crate::github::types::*;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::mpsc;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum GhFocusedPane {
     IssueList,
     PrList,
@@ -19,7 +21,7 @@ pub enum GhDetailContent {
     Error(String),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum GhDetailKind {
     Issue,
     Pr,
@@ -35,21 +37,42 @@ pub enum GhDetailPane {
 
 pub enum GhBgMessage {
     AuthStatus(Result<(), String>),
+    /// `owner/name` of the repository `gh` resolves
+    RepoName(Option<String>),
     IssueList(Result<Vec<GhIssueListItem>, String>),
     PrList(Result<Vec<GhPrListItem>, String>),
     IssueDetail(Result<GhIssueDetail, String>),
     PrDetail(Result<GhPrDetail, String>),
+    /// What `gh` printed for a detail, sent ahead of it with `--debug`
+    RawJson {
+        kind: GhDetailKind,
+        number: u64,
+        json: String,
+    },
+    /// A `gh pr` write (`gh pr ready`) finished; `done` describes it on success
+    PrAction {
+        number: u64,
+        done: String,
+        result: Result<(), String>,
+    },
 }
 
 pub struct GitHubState {
     pub gh_available: Option<bool>,
     pub gh_error: Option<String>,
+    /// `owner/name`, shown in the header once known
+    pub repo_name: Option<String>,
     pub issues: Vec<GhIssueListItem>,
     pub prs: Vec<GhPrListItem>,
     pub issues_loading: bool,
     pub prs_loading: bool,
     pub issue_selected_idx: usize,
     pub pr_selected_idx: usize,
+    /// Only list PRs authored by the current `gh` user
+    pub prs_mine_only: bool,
+    /// Issues / PRs pinned with `p`, listed before the rest
+    pub pinned_issues: HashSet<u64>,
+    pub pinned_prs: HashSet<u64>,
     pub focused_pane: GhFocusedPane,
     pub previous_pane: GhFocusedPane,
     pub detail: GhDetailContent,
@@ -62,24 +85,38 @@ pub struct GitHubState {
     pub detail_review_idx: usize,
     pub detail_comment_idx: usize,
     pub detail_view_height: u16,
+    /// Text width of the active pane, for mapping lines to wrapped rows
+    pub detail_text_width: u16,
+    /// Furthest useful scroll of the active pane, reported by the renderer
+    pub detail_max_scroll: u16,
     issue_cache: HashMap<u64, GhIssueDetail>,
     pr_cache: HashMap<u64, GhPrDetail>,
+    /// Keep what `gh` printed for each detail (`--debug`)
+    pub debug: bool,
+    pub raw_json: HashMap<(GhDetailKind, u64), String>,
     bg_rx: Option<mpsc::Receiver<GhBgMessage>>,
     bg_tx: Option<mpsc::Sender<GhBgMessage>>,
     pub initialized: bool,
+    /// How many issues / PRs to ask `gh` for
+    issue_limit: usize,
+    pr_limit: usize,
 }
 
 impl GitHubState {
-    pub fn new() -> Self {
+    pub fn new(issue_limit: usize, pr_limit: usize) -> Self {
         Self {
             gh_available: None,
             gh_error: None,
+            repo_name: None,
             issues: Vec::new(),
             prs: Vec::new(),
             issues_loading: false,
             prs_loading: false,
             issue_selected_idx: 0,
             pr_selected_idx: 0,
+            prs_mine_only: false,
+            pinned_issues: HashSet::new(),
+            pinned_prs: HashSet::new(),
             focused_pane: GhFocusedPane::IssueList,
             previous_pane: GhFocusedPane::IssueList,
             detail: GhDetailContent::None,
@@ -92,11 +129,17 @@ impl GitHubState {
             detail_review_idx: 0,
             detail_comment_idx: 0,
             detail_view_height: 0,
+            detail_text_width: 0,
+            detail_max_scroll: 0,
             issue_cache: HashMap::new(),
             pr_cache: HashMap::new(),
+            debug: false,
+            raw_json: HashMap::new(),
             bg_rx: None,
             bg_tx: None,
             initialized: false,
+            issue_limit,
+            pr_limit,
         }
     }
 
@@ -120,6 +163,36 @@ impl GitHubState {
         }
     }
 
+    /// Number of the PR in the detail pane, loaded or still loading.
+    pub fn shown_pr(&self) -> Option<u64> {
+        match &self.detail {
+            GhDetailContent::Pr(detail) => Some(detail.number),
+            GhDetailContent::Loading { kind: GhDetailKind::Pr, number } => Some(*number),
+            _ => None,
+        }
+    }
+
+    /// Issue or PR behind the detail pane; after a failed load, the one
+    /// selected in the list it was opened from.
+    pub fn shown_item(&self) -> Option<(GhDetailKind, u64)> {
+        match &self.detail {
+            GhDetailContent::Issue(detail) => Some((GhDetailKind::Issue, detail.number)),
+            GhDetailContent::Pr(detail) => Some((GhDetailKind::Pr, detail.number)),
+            GhDetailContent::Loading { kind, number } => Some((*kind, *number)),
+            GhDetailContent::Error(_) => match self.previous_pane {
+                GhFocusedPane::IssueList => self
+                    .issues
+                    .get(self.issue_selected_idx)
+                    .map(|issue| (GhDetailKind::Issue, issue.number)),
+                GhFocusedPane::PrList => {
+                    self.prs.get(self.pr_selected_idx).map(|pr| (GhDetailKind::Pr, pr.number))
+                }
+                GhFocusedPane::Detail => None,
+            },
+            GhDetailContent::None => None,
+        }
+    }
+
     pub fn is_pr(&self) -> bool {
         matches!(&self.detail, GhDetailContent::Pr(_))
     }
@@ -151,36 +224,36 @@ impl GitHubState {
 
         // Auth check + issue list
         let tx2 = tx.clone();
+        let issue_limit = self.issue_limit;
         std::thread::spawn(move || {
             let auth = client::check_gh_available();
             let _ = tx2.send(GhBgMessage::AuthStatus(auth.clone()));
             if auth.is_ok() {
-                let issues = client::list_issues(50);
+                let _ = tx2.send(GhBgMessage::RepoName(client::repo_nwo()));
+                let issues = client::list_issues(issue_limit);
                 let _ = tx2.send(GhBgMessage::IssueList(issues));
             }
         });
 
         // PR list (parallel)
-        let tx3 = tx;
-        std::thread::spawn(move || {
-            // Small delay to let auth check land first
-            let prs = client::list_prs(50);
-            let _ = tx3.send(GhBgMessage::PrList(prs));
-        });
+        self.fetch_prs();
     }
 
-    /// Drain background messages from worker threads.
-    pub fn drain_bg_messages(&mut self) {
+    /// Drain background messages from worker threads. Returns a note for the
+    /// status bar when a write action finished.
+    pub fn drain_bg_messages(&mut self) -> Option<String> {
         // Collect all pending messages first to avoid borrow conflict
         let messages: Vec<_> = match &self.bg_rx {
             Some(rx) => rx.try_iter().collect(),
-            None => return,
+            None => return None,
         };
 
+        let mut note = None;
         let mut issue_list_arrived = false;
         let mut pr_list_arrived = false;
         for msg in messages {
             match msg {
+                GhBgMessage::RepoName(name) => self.repo_name = name,
                 GhBgMessage::AuthStatus(result) => match result {
                     Ok(()) => {
                         self.gh_available = Some(true);
@@ -197,7 +270,12 @@ impl GitHubState {
                     self.issues_loading = false;
                     match result {
                         Ok(issues) => {
+                            let selected = self.issues.get(self.issue_selected_idx).map(|i| i.number);
                             self.issues = issues;
+                            pinned_first(&mut self.issues, &self.pinned_issues, |i| i.number);
+                            let numbers: Vec<u64> = self.issues.iter().map(|i| i.number).collect();
+                            self.issue_selected_idx =
+                                relocate_selection(&numbers, selected, self.issue_selected_idx);
                             issue_list_arrived = true;
                         }
                         Err(e) => {
@@ -211,7 +289,12 @@ impl GitHubState {
                     self.prs_loading = false;
                     match result {
                         Ok(prs) => {
+                            let selected = self.prs.get(self.pr_selected_idx).map(|p| p.number);
                             self.prs = prs;
+                            pinned_first(&mut self.prs, &self.pinned_prs, |p| p.number);
+                            let numbers: Vec<u64> = self.prs.iter().map(|p| p.number).collect();
+                            self.pr_selected_idx =
+                                relocate_selection(&numbers, selected, self.pr_selected_idx);
                             pr_list_arrived = true;
                         }
                         Err(e) => {
@@ -235,6 +318,27 @@ impl GitHubState {
                     }
                     Err(e) => self.detail = GhDetailContent::Error(e),
                 },
+                GhBgMessage::RawJson { kind, number, json } => {
+                    self.raw_json.insert((kind, number), json);
+                }
+                GhBgMessage::PrAction { number, done, result } => match result {
+                    Ok(()) => {
+                        note = Some(done);
+                        self.pr_cache.remove(&number);
+                        self.prs_loading = true;
+                        // The selected PR's detail is re-fetched once the list arrives
+                        self.fetch_prs();
+                    }
+                    Err(e) => {
+                        // gh explains the refusal (failing checks, conflicts, ...) better
+                        // than a one-line status would
+                        if self.shown_pr() == Some(number) {
+                            self.detail = GhDetailContent::Error(e);
+                        } else {
+                            note = Some(e);
+                        }
+                    }
+                },
             }
         }
 
@@ -249,6 +353,7 @@ impl GitHubState {
         } else if issue_list_arrived {
             self.load_selected_issue_detail();
         }
+        note
     }
 
     /// Load issue detail — serves from cache if available, otherwise fetches in background.
@@ -263,10 +368,15 @@ impl GitHubState {
             number,
         };
         self.reset_detail_panes();
+        self.raw_json.remove(&(GhDetailKind::Issue, number));
         if let Some(tx) = &self.bg_tx {
             let tx = tx.clone();
+            let debug = self.debug;
             std::thread::spawn(move || {
-                let result = client::get_issue(number);
+                let (result, raw) = client::get_issue(number, debug);
+                if let Some(json) = raw {
+                    let _ = tx.send(GhBgMessage::RawJson { kind: GhDetailKind::Issue, number, json });
+                }
                 let _ = tx.send(GhBgMessage::IssueDetail(result));
             });
         }
@@ -284,10 +394,15 @@ impl GitHubState {
             number,
         };
         self.reset_detail_panes();
+        self.raw_json.remove(&(GhDetailKind::Pr, number));
         if let Some(tx) = &self.bg_tx {
             let tx = tx.clone();
+            let debug = self.debug;
             std::thread::spawn(move || {
-                let result = client::get_pr(number);
+                let (result, raw) = client::get_pr(number, debug);
+                if let Some(json) = raw {
+                    let _ = tx.send(GhBgMessage::RawJson { kind: GhDetailKind::Pr, number, json });
+                }
                 let _ = tx.send(GhBgMessage::PrDetail(result));
             });
         }
@@ -347,15 +462,107 @@ impl GitHubState {
 
         if let Some(tx) = &self.bg_tx {
             let tx2 = tx.clone();
+            let issue_limit = self.issue_limit;
             std::thread::spawn(move || {
-                let issues = client::list_issues(50);
+                let issues = client::list_issues(issue_limit);
                 let _ = tx2.send(GhBgMessage::IssueList(issues));
             });
-            let tx3 = tx.clone();
+        }
+        self.fetch_prs();
+    }
+
+    /// Pin or unpin the selected issue, keeping it selected as it moves.
+    /// Returns its number and whether it's pinned now.
+    pub fn toggle_issue_pin(&mut self) -> Option<(u64, bool)> {
+        let number = self.issues.get(self.issue_selected_idx)?.number;
+        let pinned = toggle_pin(&mut self.pinned_issues, number);
+        pinned_first(&mut self.issues, &self.pinned_issues, |i| i.number);
+        self.issue_selected_idx = self.issues.iter().position(|i| i.number == number).unwrap_or(0);
+        Some((number, pinned))
+    }
+
+    /// Pin or unpin the selected PR, like [`Self::toggle_issue_pin`].
+    pub fn toggle_pr_pin(&mut self) -> Option<(u64, bool)> {
+        let number = self.prs.get(self.pr_selected_idx)?.number;
+        let pinned = toggle_pin(&mut self.pinned_prs, number);
+        pinned_first(&mut self.prs, &self.pinned_prs, |p| p.number);
+        self.pr_selected_idx = self.prs.iter().position(|p| p.number == number).unwrap_or(0);
+        Some((number, pinned))
+    }
+
+    /// Change how many issues / PRs are fetched, from the next fetch on.
+    pub fn set_limits(&mut self, issue_limit: usize, pr_limit: usize) {
+        self.issue_limit = issue_limit;
+        self.pr_limit = pr_limit;
+    }
+
+    /// Toggle between all PRs and only the current user's, re-fetching the list.
+    pub fn toggle_prs_mine_only(&mut self) {
+        self.prs_mine_only = !self.prs_mine_only;
+        // A different filter starts over at the top rather than following the selection
+        self.prs.clear();
+        self.pr_selected_idx = 0;
+        self.prs_loading = true;
+        self.fetch_prs();
+    }
+
+    /// Convert a PR to a draft or mark it ready for review in the background.
+    pub fn set_pr_draft(&self, number: u64, draft: bool) {
+        let done = if draft {
+            format!("Converted PR #{number} to draft")
+        } else {
+            format!("Marked PR #{number} as ready for review")
+        };
+        self.run_pr_action(number, done, move || client::set_pr_draft(number, draft));
+    }
+
+    fn run_pr_action(
+        &self,
+        number: u64,
+        done: String,
+        action: impl FnOnce() -> Result<(), String> + Send + 'static,
+    ) {
+        if let Some(tx) = self.bg_tx.clone() {
             std::thread::spawn(move || {
-                let prs = client::list_prs(50);
-                let _ = tx3.send(GhBgMessage::PrList(prs));
+                let result = action();
+                let _ = tx.send(GhBgMessage::PrAction { number, done, result });
             });
         }
     }
+
+    fn fetch_prs(&self) {
+        let Some(tx) = self.bg_tx.clone() else {
+            return;
+        };
+        let author = self.prs_mine_only.then_some("@me");
+        let pr_limit = self.pr_limit;
+        std::thread::spawn(move || {
+            let prs = client::list_prs(pr_limit, author);
+            let _ = tx.send(GhBgMessage::PrList(prs));
+        });
+    }
+}
+
+/// Move pinned items to the top, keeping the server's order within each group.
+fn pinned_first<T>(items: &mut [T], pinned: &HashSet<u64>, number: impl Fn(&T) -> u64) {
+    items.sort_by_key(|item| !pinned.contains(&number(item)));
+}
+
+/// Add `number` to `pinned`, or remove it if it's there. Returns whether it's
+/// pinned now.
+fn toggle_pin(pinned: &mut HashSet<u64>, number: u64) -> bool {
+    if pinned.remove(&number) {
+        false
+    } else {
+        pinned.insert(number);
+        true
+    }
+}
+
+/// Index of the previously selected item in a refreshed list, so a reorder
+/// doesn't silently move the selection; clamps the old index if it's gone.
+fn relocate_selection(numbers: &[u64], selected: Option<u64>, idx: usize) -> usize {
+    selected
+        .and_then(|n| numbers.iter().position(|&m| m == n))
+        .unwrap_or_else(|| idx.min(numbers.len().saturating_sub(1)))
 }
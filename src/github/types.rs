@@ -112,4 +112,12 @@ pub struct GhPrDetail {
     pub changed_files: u64,
     #[serde(rename = "headRefName")]
     pub head_ref_name: String,
+    #[serde(rename = "isDraft", default)]
+    pub is_draft: bool,
+    /// `MERGEABLE`, `CONFLICTING`, or `UNKNOWN` while GitHub is still computing
+    #[serde(default)]
+    pub mergeable: Option<String>,
+    /// Why the PR can or can't be merged: `CLEAN`, `BLOCKED`, `BEHIND`, ...
+    #[serde(rename = "mergeStateStatus", default)]
+    pub merge_state_status: Option<String>,
 }
@@ -1,24 +1,33 @@
 mod app;
+mod command;
+mod config;
 mod event;
 mod git;
 mod github;
+mod session;
 mod syntax;
 mod tui;
 mod ui;
 mod update;
 
 use crate::app::{App, FocusedPane, ViewMode};
+use crate::config::{Config, MIN_INTERVAL_MS};
 use crate::event::{Event, EventHandler};
+use crate::git::diff::DiffScope;
 use crate::git::repository::Repo;
 use crate::git::watcher::FsWatcher;
 use crate::ui::{
-    branch_action_menu, branch_selector, commit_log, confirm_dialog, diff_view, file_tree,
-    github as gh_ui, layout, reflog, status_bar,
+    base_picker, blame_popup, branch_action_menu, branch_selector, commit_log, confirm_dialog,
+    diff_view, file_finder, file_tree, github as gh_ui, layout, ref_picker, reflog, reset_menu,
+    status_bar,
 };
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use std::env;
-use std::process::Command;
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
 use std::time::Duration;
 
 #[derive(Parser)]
@@ -26,6 +35,37 @@ use std::time::Duration;
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Repository to open (defaults to the current directory)
+    path: Option<PathBuf>,
+
+    /// Print the diff as JSON to stdout instead of starting the TUI
+    #[arg(long)]
+    dump_diff: bool,
+
+    /// Compare the working tree against this ref instead of HEAD
+    #[arg(long, value_name = "REF")]
+    base: Option<String>,
+
+    /// Start with only staged changes, like `git diff --cached`
+    #[arg(long)]
+    cached: bool,
+
+    /// Start in the GitHub view (issues and pull requests)
+    #[arg(long)]
+    github: bool,
+
+    /// Keep the JSON `gh` returns, shown with `J` in the GitHub detail
+    #[arg(long)]
+    debug: bool,
+
+    /// Idle UI tick in milliseconds (overrides `tick_rate_ms`)
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(MIN_INTERVAL_MS..))]
+    tick_rate: Option<u64>,
+
+    /// File watcher debounce in milliseconds (overrides `watch_debounce_ms`)
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(MIN_INTERVAL_MS..))]
+    debounce: Option<u64>,
 }
 
 #[derive(Subcommand)]
@@ -39,13 +79,90 @@ fn main() -> Result<()> {
 
     match cli.command {
         Some(Commands::Update) => update::run()?,
-        None => run_tui()?,
+        None if cli.dump_diff => dump_diff(cli.path, cli.base.as_deref(), scope(cli.cached))?,
+        None => run_tui(cli)?,
     }
 
     Ok(())
 }
 
-fn run_tui() -> Result<()> {
+/// Headless mode: print the parsed diff as JSON for scripts and editor integrations.
+fn dump_diff(path: Option<PathBuf>, base: Option<&str>, scope: DiffScope) -> Result<()> {
+    let repo = discover_repo(path)?;
+    let state = repo.diff_workdir(base, scope)?;
+    let mut out = std::io::stdout().lock();
+    serde_json::to_writer_pretty(&mut out, &state.files)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+fn scope(cached: bool) -> DiffScope {
+    if cached {
+        DiffScope::Staged
+    } else {
+        DiffScope::All
+    }
+}
+
+fn discover_repo(path: Option<PathBuf>) -> Result<Repo> {
+    let dir = match path {
+        Some(path) => path,
+        None => env::current_dir()?,
+    };
+    Repo::discover(&dir)
+}
+
+/// Arguments that open `path` in `editor`, at `line` when the editor has a
+/// known syntax for it: `+N` for vi-likes, nano and emacs, `--goto path:N`
+/// for VS Code, `path:N` for Helix, Sublime Text and Zed.
+fn editor_args(editor: &str, path: &Path, line: Option<u32>) -> Vec<OsString> {
+    let Some(line) = line else {
+        return vec![path.into()];
+    };
+    let with_line = || {
+        let mut arg = path.as_os_str().to_owned();
+        arg.push(format!(":{line}"));
+        arg
+    };
+    let name = Path::new(editor)
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or("");
+    match name {
+        "vi" | "vim" | "nvim" | "gvim" | "nano" | "emacs" | "emacsclient" | "micro" | "kak" => {
+            vec![format!("+{line}").into(), path.into()]
+        }
+        "code" | "code-insiders" | "codium" | "cursor" => vec!["--goto".into(), with_line()],
+        "hx" | "helix" | "subl" | "zed" => vec![with_line()],
+        _ => vec![path.into()],
+    }
+}
+
+/// Run `command` with the terminal handed over to it, then take the screen
+/// back. The outer error is the terminal's; the inner one the command's.
+fn run_suspended(
+    terminal: &mut tui::Tui,
+    events: &EventHandler,
+    command: &mut Command,
+) -> Result<std::io::Result<ExitStatus>> {
+    // Pause event polling — blocks until the background
+    // thread has stopped calling crossterm::event::poll()
+    events.pause();
+    tui::restore()?;
+
+    let status = command.status();
+
+    *terminal = tui::enter()?;
+    // Flush stale terminal data before resuming the event thread
+    while crossterm::event::poll(Duration::ZERO)? {
+        let _ = crossterm::event::read();
+    }
+    events.drain();
+    events.resume();
+    Ok(status)
+}
+
+fn run_tui(cli: Cli) -> Result<()> {
     // Restore terminal on panic
     let default_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |info| {
@@ -53,22 +170,43 @@ fn run_tui() -> Result<()> {
         default_hook(info);
     }));
 
-    let cwd = env::current_dir()?;
-    let repo = Repo::discover(&cwd)?;
+    let mut repo = discover_repo(cli.path)?;
+    let mut config = Config::load(repo.workdir())?;
+    if let Some(ms) = cli.tick_rate {
+        config.tick_rate_ms = ms;
+    }
+    if let Some(ms) = cli.debounce {
+        config.watch_debounce_ms = ms;
+    }
+    let tick_rate = Duration::from_millis(config.tick_rate_ms);
+    let debounce = Duration::from_millis(config.watch_debounce_ms);
+
+    repo.set_ignore_whitespace(config.ignore_whitespace);
     let workdir = repo.workdir().to_path_buf();
-    let mut app = App::new(repo)?;
+    let base = cli.base.or_else(|| config.base.clone());
+    let mut app = App::new(repo, config, base, scope(cli.cached))?;
+    app.github.debug = cli.debug;
+    // Takes precedence over the view restored from the last session
+    if cli.github {
+        app.set_view_mode(ViewMode::GitHub);
+    }
 
-    let events = EventHandler::new(Duration::from_millis(250));
+    let events = EventHandler::new(tick_rate);
 
     // Start file watcher
-    let _watcher = FsWatcher::new(&workdir, events.tx())?;
+    let _watcher = FsWatcher::new(&workdir, debounce, events.tx())?;
 
     let mut terminal = tui::enter()?;
 
     loop {
         // Collect any completed background highlight results
         app.drain_bg_highlights();
-        app.github.drain_bg_messages();
+        if let Some(note) = app.github.drain_bg_messages() {
+            app.status_message = Some(note);
+        }
+        app.update_signatures();
+        app.drain_history_search();
+        app.drain_blame();
 
         // Draw
         terminal.draw(|frame| {
@@ -91,10 +229,34 @@ fn run_tui() -> Result<()> {
 
                     status_bar::render_status_bar(frame, &app, layout.status_bar);
 
+                    if app.unified_view.is_some() {
+                        diff_view::render_unified_view(frame, &mut app, layout.body);
+                    }
+
+                    if app.blame_popup.is_some() {
+                        blame_popup::render(frame, &app, layout.main_pane);
+                    }
+
                     if app.branch_action_menu.is_some() {
                         branch_action_menu::render(frame, &app, frame.area());
                     }
 
+                    if app.reset_menu.is_some() {
+                        reset_menu::render(frame, &app, frame.area());
+                    }
+
+                    if app.ref_picker.is_some() {
+                        ref_picker::render(frame, &app, frame.area());
+                    }
+
+                    if app.file_finder.is_some() {
+                        file_finder::render(frame, &app, frame.area());
+                    }
+
+                    if app.base_picker.is_some() {
+                        base_picker::render(frame, &app, frame.area());
+                    }
+
                     if app.error_dialog.is_some() {
                         confirm_dialog::render(frame, &app, frame.area());
                     }
@@ -106,14 +268,43 @@ fn run_tui() -> Result<()> {
                     gh_ui::pr_list::render(frame, &app, gl.pr_list);
                     gh_ui::detail_view::render(frame, &mut app, gl.main_pane);
                     status_bar::render_gh_status_bar(frame, &app, gl.status_bar);
+
+                    if app.raw_json_view.is_some() {
+                        gh_ui::raw_json::render(frame, &mut app, frame.area());
+                    }
                 }
             }
 
+            if app.confirm_dialog.is_some() {
+                confirm_dialog::render_confirm(frame, &app, frame.area());
+            }
+
             if app.show_help {
-                status_bar::render_help_overlay(frame, frame.area(), app.view_mode);
+                status_bar::render_help_overlay(frame, &mut app, frame.area());
             }
+
+            app.color_depth.apply(frame.buffer_mut());
         })?;
 
+        // Popups are plain text, so an image would be drawn over them
+        if app.show_help
+            || app.unified_view.is_some()
+            || app.blame_popup.is_some()
+            || app.confirm_dialog.is_some()
+            || app.error_dialog.is_some()
+            || app.branch_action_menu.is_some()
+            || app.reset_menu.is_some()
+            || app.ref_picker.is_some()
+            || app.file_finder.is_some()
+            || app.base_picker.is_some()
+        {
+            app.image_preview.requested = None;
+        }
+        if app.image_preview.flush(terminal.backend_mut())? {
+            terminal.clear()?;
+            continue;
+        }
+
         // Handle events
         match events.next()? {
             Event::Key(key) => {
@@ -128,27 +319,38 @@ fn run_tui() -> Result<()> {
                     break;
                 }
 
+                if let Some(launch) = app.diff_tool_launch.take() {
+                    let mut command = Command::new(&launch.program);
+                    command.args(&launch.args);
+                    let status = run_suspended(&mut terminal, &events, &mut command);
+                    // Also when the tool failed to start or the terminal broke
+                    let _ = std::fs::remove_dir_all(&launch.temp_dir);
+                    match status? {
+                        // Any edits to the working-tree side show up in the diff
+                        Ok(_) => app.refresh_diff()?,
+                        Err(e) => {
+                            app.status_message =
+                                Some(format!("Failed to run {}: {e}", launch.program));
+                        }
+                    }
+                }
+
                 if open_editor {
-                    if let Some(file) = app.selected_file() {
-                        let file_path = workdir.join(&file.path);
+                    let worktree_path = app
+                        .selected_file()
+                        .map(|file| file.worktree_path().map(|p| workdir.join(p)));
+                    if let Some(None) = worktree_path {
+                        app.status_message = Some("File was deleted".to_string());
+                    }
+                    let line = app.editor_line();
+                    if let Some(Some(file_path)) = worktree_path {
                         let editor = env::var("EDITOR")
                             .or_else(|_| env::var("VISUAL"))
                             .unwrap_or_else(|_| "vi".to_string());
 
-                        // Pause event polling — blocks until the background
-                        // thread has stopped calling crossterm::event::poll()
-                        events.pause();
-                        tui::restore()?;
-
-                        let status = Command::new(&editor).arg(&file_path).status();
-
-                        terminal = tui::enter()?;
-                        // Flush stale terminal data before resuming the event thread
-                        while crossterm::event::poll(Duration::ZERO)? {
-                            let _ = crossterm::event::read();
-                        }
-                        events.drain();
-                        events.resume();
+                        let mut command = Command::new(&editor);
+                        command.args(editor_args(&editor, &file_path, line));
+                        let status = run_suspended(&mut terminal, &events, &mut command)?;
 
                         match status {
                             Ok(s) if s.success() => {
@@ -166,17 +368,27 @@ fn run_tui() -> Result<()> {
                     }
                 }
             }
-            Event::FsChange => {
-                app.load_branches();
-                app.load_reflog();
+            Event::FsChange(change) => {
+                // A moved ref can also move HEAD, so the diff reloads either way
+                if change.refs {
+                    app.refresh_refs();
+                }
                 if let Err(e) = app.refresh_diff() {
                     app.status_message = Some(format!("Refresh error: {e}"));
                 }
             }
-            Event::Tick | Event::Resize(_, _) => {}
+            Event::Tick => {
+                if let Err(e) = app.retry_refresh_diff() {
+                    app.status_message = Some(format!("Refresh error: {e}"));
+                }
+            }
+            Event::Resize(_, _) => {}
         }
     }
 
     tui::restore()?;
+    if let Err(e) = app.save_session() {
+        eprintln!("Failed to save session: {e:#}");
+    }
     Ok(())
 }
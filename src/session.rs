@@ -0,0 +1,80 @@
+use crate::app::{FocusedPane, ViewMode};
+use crate::github::state::GhFocusedPane;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// UI state remembered per repository between runs, kept in
+/// `$XDG_CACHE_HOME/vig/sessions/` (falls back to `~/.cache/vig/sessions/`).
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Directories collapsed in the file tree
+    pub collapsed_dirs: Vec<String>,
+    /// Issue and PR numbers pinned to the top of the GitHub lists
+    pub pinned_issues: Vec<u64>,
+    pub pinned_prs: Vec<u64>,
+    /// View and focused panes at the last quit
+    pub view: Option<ViewMode>,
+    pub focused_pane: Option<FocusedPane>,
+    pub gh_focused_pane: Option<GhFocusedPane>,
+}
+
+impl Session {
+    /// Session file for the repository at `workdir`, named after its path.
+    fn path(workdir: &Path) -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))?;
+        let name = file_stem(workdir);
+        Some(base.join("vig").join("sessions").join(format!("{name}.toml")))
+    }
+
+    /// The saved session for `workdir`. A missing or unreadable file yields
+    /// the default, so a corrupt cache never stops vig from starting.
+    pub fn load(workdir: &Path) -> Self {
+        Self::path(workdir)
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, workdir: &Path) -> Result<()> {
+        let Some(path) = Self::path(workdir) else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let text = toml::to_string(self)?;
+        std::fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// `workdir` percent-encoded byte by byte, keeping only ASCII letters,
+/// digits, `-`, `_` and `.`, so distinct paths never share a session file.
+fn file_stem(workdir: &Path) -> String {
+    let bytes = workdir.as_os_str().as_encoded_bytes();
+    let end = bytes.iter().rposition(|b| !matches!(b, b'/' | b'\\')).map_or(0, |i| i + 1);
+    bytes[..end]
+        .iter()
+        .map(|&b| match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' => char::from(b).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distinct_paths_get_distinct_session_files() {
+        assert_eq!(file_stem(Path::new("/src/my-repo/")), "%2Fsrc%2Fmy-repo");
+        assert_ne!(file_stem(Path::new("/src/a%b")), file_stem(Path::new("/src/a/b")));
+        assert_ne!(file_stem(Path::new("/src/a%/b")), file_stem(Path::new("/src/a/%b")));
+    }
+}
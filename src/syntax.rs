@@ -1,12 +1,20 @@
+use anyhow::{bail, Result};
 use ratatui::style::Color;
 use syntect::highlighting::{HighlightIterator, HighlightState, Highlighter, Theme, ThemeSet};
 use syntect::parsing::{ParseState, ScopeStack, SyntaxDefinition, SyntaxReference, SyntaxSet};
+use std::time::{Duration, Instant};
+
+/// How long one `extend_cache` call may highlight before yielding to the UI.
+const EXTEND_BUDGET: Duration = Duration::from_millis(40);
 
 pub struct SyntaxHighlighter {
     syntax_set: SyntaxSet,
     theme: Theme,
+    theme_name: String,
 }
 
+pub const DEFAULT_THEME: &str = "base16-eighties.dark";
+
 /// Cached highlight state for incremental processing.
 /// Stores pre-expanded per-character colors and syntect parse state
 /// so highlighting can resume where it left off on scroll.
@@ -30,6 +38,75 @@ struct IncrementalState {
     right_lines: Vec<String>,
     /// Row indices where hunks start — parser state resets here.
     hunk_starts: Vec<usize>,
+    /// First old/new line number of each hunk, for seeding the reset state.
+    hunk_lines: Vec<(usize, usize)>,
+    left_seed: Option<Seed>,
+    right_seed: Option<Seed>,
+}
+
+/// Full old/new file text used to seed the parser at each hunk start, so a
+/// hunk that begins inside a block comment or string isn't parsed as top level.
+#[derive(Default)]
+pub struct FileSources {
+    pub left: Option<String>,
+    pub right: Option<String>,
+    /// First old/new line number (1-based) of each hunk, in hunk order.
+    pub hunk_lines: Vec<(usize, usize)>,
+}
+
+/// Parser state advanced line by line through a file's full text.
+struct Seed {
+    text: String,
+    /// Byte offset and 1-based number of the next unparsed line.
+    offset: usize,
+    line_no: usize,
+    parse_state: ParseState,
+    scopes: ScopeStack,
+}
+
+impl Seed {
+    fn new(text: String, syntax: &SyntaxReference) -> Self {
+        Self {
+            text,
+            offset: 0,
+            line_no: 1,
+            parse_state: ParseState::new(syntax),
+            scopes: ScopeStack::new(),
+        }
+    }
+
+    /// Parse forward to just before `line_no` and return the state there.
+    /// Hunks are visited in order, so each call only parses the gap since the last.
+    fn state_before(&mut self, line_no: usize, syntax_set: &SyntaxSet) -> (ParseState, ScopeStack) {
+        while self.line_no < line_no && self.offset < self.text.len() {
+            let rest = &self.text[self.offset..];
+            let len = rest.find('\n').map(|i| i + 1).unwrap_or(rest.len());
+            if let Ok(ops) = self.parse_state.parse_line(&rest[..len], syntax_set) {
+                for (_, op) in &ops {
+                    let _ = self.scopes.apply(op);
+                }
+            }
+            self.offset += len;
+            self.line_no += 1;
+        }
+        (self.parse_state.clone(), self.scopes.clone())
+    }
+}
+
+/// Parser and highlight state for the start of a hunk: seeded from the full
+/// file when available, top level otherwise.
+fn hunk_start_state(
+    seed: &mut Option<Seed>,
+    line_no: usize,
+    syntax: &SyntaxReference,
+    syntax_set: &SyntaxSet,
+    highlighter: &Highlighter,
+) -> (ParseState, HighlightState) {
+    let (parse, scopes) = match seed {
+        Some(seed) => seed.state_before(line_no, syntax_set),
+        None => (ParseState::new(syntax), ScopeStack::new()),
+    };
+    (parse, HighlightState::new(highlighter, scopes))
 }
 
 impl HighlightCache {
@@ -124,13 +201,32 @@ impl SyntaxHighlighter {
         }
         let syntax_set = builder.build();
         let theme_set = ThemeSet::load_defaults();
-        let theme = theme_set
+        let (theme_name, theme) = theme_set
             .themes
-            .get("base16-eighties.dark")
-            .cloned()
-            .or_else(|| theme_set.themes.values().next().cloned())
+            .get_key_value(DEFAULT_THEME)
+            .or_else(|| theme_set.themes.iter().next())
+            .map(|(name, theme)| (name.clone(), theme.clone()))
             .expect("No themes available in ThemeSet");
-        Self { syntax_set, theme }
+        Self { syntax_set, theme, theme_name }
+    }
+
+    /// Names of the bundled syntax themes.
+    pub fn theme_names() -> Vec<String> {
+        ThemeSet::load_defaults().themes.into_keys().collect()
+    }
+
+    pub fn theme_name(&self) -> &str {
+        &self.theme_name
+    }
+
+    /// Switch to one of the bundled themes. Existing caches keep their colors.
+    pub fn set_theme(&mut self, name: &str) -> Result<()> {
+        let Some(theme) = ThemeSet::load_defaults().themes.remove(name) else {
+            bail!("Unknown theme: {name}");
+        };
+        self.theme = theme;
+        self.theme_name = name.to_string();
+        Ok(())
     }
 
     /// Find the syntax definition for a file path by extension,
@@ -157,13 +253,9 @@ impl SyntaxHighlighter {
         left_lines: Vec<String>,
         right_lines: Vec<String>,
         hunk_starts: Vec<usize>,
+        sources: FileSources,
     ) -> Option<HighlightCache> {
-        // Use first non-header line for first-line syntax detection
-        let first_content = left_lines
-            .iter()
-            .enumerate()
-            .find(|(i, s)| !hunk_starts.contains(i) && !s.is_empty())
-            .map(|(_, s)| s.as_str());
+        let first_content = first_content_line(&left_lines, &right_lines, &hunk_starts);
         let syntax = self.find_syntax(file_path, first_content)?;
         let highlighter = Highlighter::new(&self.theme);
         Some(HighlightCache {
@@ -179,36 +271,48 @@ impl SyntaxHighlighter {
                 left_lines,
                 right_lines,
                 hunk_starts,
+                hunk_lines: sources.hunk_lines,
+                left_seed: sources.left.map(|t| Seed::new(t, syntax)),
+                right_seed: sources.right.map(|t| Seed::new(t, syntax)),
             }),
         })
     }
 
-    /// Extend the cache to cover at least `up_to` rows.
+    /// Extend the cache to cover at least `up_to` rows, giving up after
+    /// `EXTEND_BUDGET` so a big jump doesn't freeze the UI; the next frame
+    /// carries on. Returns whether `up_to` is covered.
     /// Only processes rows not yet highlighted (incremental).
     /// No-op for pre-computed caches.
     /// Resets parser state at hunk boundaries so unclosed strings/comments
-    /// in one hunk don't corrupt highlighting in the next.
-    pub fn extend_cache(&self, cache: &mut HighlightCache, up_to: usize) {
+    /// in one hunk don't corrupt highlighting in the next; the reset state is
+    /// seeded from the full file when its text is known.
+    pub fn extend_cache(&self, cache: &mut HighlightCache, up_to: usize) -> bool {
         let inc = match &mut cache.incremental {
             Some(inc) => inc,
-            None => return, // pre-computed, nothing to extend
+            None => return true, // pre-computed, nothing to extend
         };
         let target = up_to.min(inc.left_lines.len());
         if cache.processed_up_to >= target {
-            return;
+            return true;
         }
 
+        let started = Instant::now();
         let highlighter = Highlighter::new(&self.theme);
         for i in cache.processed_up_to..target {
+            if started.elapsed() > EXTEND_BUDGET {
+                cache.processed_up_to = i;
+                return false;
+            }
             // Reset parser state at hunk boundaries
-            if inc.hunk_starts.contains(&i) {
+            if let Some(h) = inc.hunk_starts.iter().position(|&s| s == i) {
                 if let Some(syntax) = self.find_syntax(&cache.file_path, None) {
-                    inc.left_parse_state = ParseState::new(syntax);
-                    inc.left_highlight_state =
-                        HighlightState::new(&highlighter, ScopeStack::new());
-                    inc.right_parse_state = ParseState::new(syntax);
-                    inc.right_highlight_state =
-                        HighlightState::new(&highlighter, ScopeStack::new());
+                    let (old_line, new_line) = inc.hunk_lines.get(h).copied().unwrap_or((1, 1));
+                    (inc.left_parse_state, inc.left_highlight_state) = hunk_start_state(
+                        &mut inc.left_seed, old_line, syntax, &self.syntax_set, &highlighter,
+                    );
+                    (inc.right_parse_state, inc.right_highlight_state) = hunk_start_state(
+                        &mut inc.right_seed, new_line, syntax, &self.syntax_set, &highlighter,
+                    );
                 }
                 cache.left_colors.push(Vec::new());
                 cache.right_colors.push(Vec::new());
@@ -236,22 +340,20 @@ impl SyntaxHighlighter {
             cache.right_colors.push(right);
         }
         cache.processed_up_to = target;
+        true
     }
 
     /// Highlight all lines of a file at once. Used by background thread.
-    /// Resets parser state at each hunk boundary.
+    /// Resets parser state at each hunk boundary, seeded from `sources`.
     pub fn highlight_all_lines(
         &self,
         file_path: &str,
         left_lines: &[String],
         right_lines: &[String],
         hunk_starts: &[usize],
+        sources: FileSources,
     ) -> Option<(Vec<Vec<Color>>, Vec<Vec<Color>>)> {
-        let first_content = left_lines
-            .iter()
-            .enumerate()
-            .find(|(i, s)| !hunk_starts.contains(i) && !s.is_empty())
-            .map(|(_, s)| s.as_str());
+        let first_content = first_content_line(left_lines, right_lines, hunk_starts);
         let syntax = self.find_syntax(file_path, first_content)?;
         let highlighter = Highlighter::new(&self.theme);
 
@@ -260,16 +362,22 @@ impl SyntaxHighlighter {
         let mut right_parse = ParseState::new(syntax);
         let mut right_hl = HighlightState::new(&highlighter, ScopeStack::new());
 
+        let mut left_seed = sources.left.map(|t| Seed::new(t, syntax));
+        let mut right_seed = sources.right.map(|t| Seed::new(t, syntax));
+
         let mut left_colors = Vec::with_capacity(left_lines.len());
         let mut right_colors = Vec::with_capacity(right_lines.len());
 
         for (i, (l, r)) in left_lines.iter().zip(right_lines.iter()).enumerate() {
-            if hunk_starts.contains(&i) {
+            if let Some(h) = hunk_starts.iter().position(|&s| s == i) {
                 // Reset parser state at hunk boundary
-                left_parse = ParseState::new(syntax);
-                left_hl = HighlightState::new(&highlighter, ScopeStack::new());
-                right_parse = ParseState::new(syntax);
-                right_hl = HighlightState::new(&highlighter, ScopeStack::new());
+                let (old_line, new_line) = sources.hunk_lines.get(h).copied().unwrap_or((1, 1));
+                (left_parse, left_hl) = hunk_start_state(
+                    &mut left_seed, old_line, syntax, &self.syntax_set, &highlighter,
+                );
+                (right_parse, right_hl) = hunk_start_state(
+                    &mut right_seed, new_line, syntax, &self.syntax_set, &highlighter,
+                );
                 left_colors.push(Vec::new());
                 right_colors.push(Vec::new());
                 continue;
@@ -316,3 +424,82 @@ fn highlight_line_colors(
 fn syntect_to_ratatui_color(c: syntect::highlighting::Color) -> Color {
     Color::Rgb(c.r, c.g, c.b)
 }
+
+/// First non-header line for first-line syntax detection. New files have an
+/// empty left side, so fall back to the right side (e.g. a shebang).
+fn first_content_line<'a>(
+    left_lines: &'a [String],
+    right_lines: &'a [String],
+    hunk_starts: &[usize],
+) -> Option<&'a str> {
+    let first = |lines: &'a [String]| {
+        lines
+            .iter()
+            .enumerate()
+            .find(|(i, s)| !hunk_starts.contains(i) && !s.is_empty())
+            .map(|(_, s)| s.as_str())
+    };
+    first(left_lines).or_else(|| first(right_lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extensionless_script_is_detected_by_shebang() {
+        let highlighter = SyntaxHighlighter::new();
+        let syntax = highlighter.find_syntax("scripts/deploy", Some("#!/usr/bin/env python3\n"));
+        assert_eq!(syntax.map(|s| s.name.as_str()), Some("Python"));
+        assert!(highlighter.find_syntax("scripts/deploy", Some("just some notes\n")).is_none());
+    }
+
+    #[test]
+    fn new_extensionless_script_is_highlighted() {
+        let highlighter = SyntaxHighlighter::new();
+        // A new file: the left side is empty, the shebang is on the right
+        let right: Vec<String> = ["@@ -0,0 +1,2 @@", "#!/bin/bash", "echo \"hi\""]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let left = vec![String::new(); right.len()];
+        let mut cache = highlighter
+            .create_cache("deploy", left, right, vec![0], FileSources::default())
+            .expect("shebang should select a syntax");
+        // Each call highlights for a limited time; keep going until all rows are done
+        while !highlighter.extend_cache(&mut cache, 3) {}
+
+        let echo = &cache.right_colors[2];
+        assert_eq!(echo.len(), "echo \"hi\"".len());
+        // The command and the string literal get different colors
+        assert_ne!(echo[0], echo[6]);
+    }
+
+    #[test]
+    fn hunk_inside_block_comment_is_highlighted_as_comment() {
+        let highlighter = SyntaxHighlighter::new();
+        let source = "fn main() {}\n/*\nlet a = 1;\nlet b = 2;\n*/\n";
+        // One hunk starting at line 3, inside the comment opened on line 2
+        let lines: Vec<String> = ["@@ -3,2 +3,2 @@", "let a = 1;", "let b = 2;"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let sources = FileSources {
+            left: Some(source.to_string()),
+            right: Some(source.to_string()),
+            hunk_lines: vec![(3, 3)],
+        };
+        let (_, seeded) = highlighter
+            .highlight_all_lines("main.rs", &lines, &lines, &[0], sources)
+            .unwrap();
+        let (_, top_level) = highlighter
+            .highlight_all_lines("main.rs", &lines, &lines, &[0], FileSources::default())
+            .unwrap();
+
+        // Inside the comment the whole line takes one color
+        let row = &seeded[1];
+        assert!(row.iter().all(|c| *c == row[0]), "{row:?}");
+        // Parsed as top level, `let` and the number stand out
+        assert_ne!(top_level[1], seeded[1]);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::app::App;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+const BG: Color = Color::Rgb(30, 30, 30);
+const SELECTED_BG: Color = Color::DarkGray;
+
+/// Diff base picker: the query on top, matching refs below with the matched
+/// characters highlighted. The current base is marked.
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let picker = match &app.base_picker {
+        Some(picker) => picker,
+        None => return,
+    };
+
+    let width = 60u16.min(area.width.saturating_sub(4));
+    let height = 20u16.min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+    let picker_area = Rect::new(x, y, width, height);
+
+    f.render_widget(Clear, picker_area);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(" > ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::styled(
+                format!("{}\u{2588}", picker.query),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        match &picker.error {
+            Some(error) => Line::from(Span::styled(format!(" {error}"), Style::default().fg(Color::Red))),
+            None => Line::from(""),
+        },
+    ];
+
+    // Keep the selection in view
+    let visible = height.saturating_sub(4) as usize;
+    let first = picker.selected.saturating_sub(visible.saturating_sub(1));
+    for (row, (ref_idx, positions)) in picker.matches.iter().enumerate().skip(first).take(visible) {
+        let name = &picker.refs[*ref_idx];
+        let bg = if row == picker.selected { SELECTED_BG } else { BG };
+        let current = app.diff_base_ref.as_deref() == Some(name.as_str());
+        let mut spans = vec![Span::styled(
+            if current { " * " } else { "   " },
+            Style::default().fg(Color::Green).bg(bg),
+        )];
+        for (idx, c) in name.chars().enumerate() {
+            let style = if positions.contains(&idx) {
+                Style::default().fg(Color::Yellow).bg(bg).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White).bg(bg)
+            };
+            spans.push(Span::styled(c.to_string(), style));
+        }
+        lines.push(Line::from(spans));
+    }
+    if picker.matches.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "   No matching refs (Enter tries the text as typed)",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let block = Block::default()
+        .title(format!(" Diff base {}/{} ", picker.matches.len(), picker.refs.len()))
+        .title_bottom(Line::from(" Enter: compare  Ctrl+n/p: move  Esc: cancel ").right_aligned())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan).bg(BG))
+        .style(Style::default().bg(BG));
+
+    f.render_widget(Paragraph::new(lines).block(block), picker_area);
+}
@@ -0,0 +1,78 @@
+use crate::app::App;
+use crate::git::repository::SignatureStatus;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+const BG: Color = Color::Rgb(30, 30, 30);
+
+/// Signature check result of the blamed commit; nothing when unsigned or
+/// still being checked.
+fn signature_badge(status: Option<SignatureStatus>) -> Span<'static> {
+    match status {
+        Some(SignatureStatus::Verified) => {
+            Span::styled("\u{2713} verified ", Style::default().fg(Color::Green).bg(BG))
+        }
+        Some(SignatureStatus::Unverified) => {
+            Span::styled("? unverified ", Style::default().fg(Color::Yellow).bg(BG))
+        }
+        Some(SignatureStatus::Unsigned) | None => Span::raw(""),
+    }
+}
+
+/// One-line blame for the cursor line, drawn in the diff pane `area` just
+/// below the cursor, or above it near the bottom edge.
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let popup = match &app.blame_popup {
+        Some(popup) => popup,
+        None => return,
+    };
+
+    let spans = match &popup.commit {
+        Some(commit) => vec![
+            Span::styled(format!(" {} ", commit.short_hash), Style::default().fg(Color::Yellow).bg(BG)),
+            Span::styled(
+                format!("{} ", commit.author_label(app.git_log.show_email)),
+                Style::default().fg(Color::Cyan).bg(BG),
+            ),
+            Span::styled(format!("{} ", commit.date), Style::default().fg(Color::DarkGray).bg(BG)),
+            signature_badge(app.signature_of(&commit.full_hash)),
+            Span::styled(format!("{} ", commit.message), Style::default().fg(Color::White).bg(BG)),
+        ],
+        None => vec![Span::styled(
+            " Not committed yet ",
+            Style::default().fg(Color::DarkGray).bg(BG),
+        )],
+    };
+    let text_width: usize = spans.iter().map(|s| s.content.width()).sum();
+
+    let max_width = area.width.saturating_sub(2);
+    let width = (text_width as u16 + 2).min(max_width);
+    if width < 4 || area.height < 5 {
+        return;
+    }
+    // Rows of the pane below its top border, before the status line
+    let cursor_y = area.y + 1 + app.cursor_pos.row.saturating_sub(app.diff_scroll_y as usize) as u16;
+    let y = if cursor_y + 4 < area.y + area.height {
+        cursor_y + 1
+    } else {
+        cursor_y.saturating_sub(3)
+    };
+    let popup_area = Rect::new(area.x + 1, y, width, 3);
+
+    f.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" Blame L{} ", popup.line_no),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(BG));
+    f.render_widget(Paragraph::new(Line::from(spans)).block(block), popup_area);
+}
@@ -15,14 +15,22 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         Color::DarkGray
     };
 
-    let block = Block::default()
+    let mut block = Block::default()
         .title(" Branches ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color));
+    if let Some(label) = app.search_match_label(SearchOrigin::BranchList) {
+        block = block.title_bottom(Line::from(format!(" {label} ")).right_aligned());
+    }
 
     if app.branch_list.branches.is_empty() {
+        let message = if app.repo.is_unborn() {
+            "  No commits yet"
+        } else {
+            "  No branches"
+        };
         let items: Vec<ListItem> = vec![ListItem::new(Line::from(Span::styled(
-            "  No branches",
+            message,
             Style::default().fg(Color::DarkGray),
         )))];
         let list = List::new(items).block(block);
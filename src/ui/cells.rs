@@ -0,0 +1,166 @@
+use unicode_width::UnicodeWidthChar;
+
+/// One screen cell of a laid-out line.
+#[derive(Debug, Clone, Copy)]
+pub struct Cell<'a> {
+    /// Text drawn in the cell: a char plus any zero-width chars (combining
+    /// marks, controls) attached to it. Empty for the right half of a wide char.
+    pub text: &'a str,
+    /// Index of the first content char the cell belongs to.
+    pub col: usize,
+    /// Number of content chars the cell belongs to.
+    pub len: usize,
+}
+
+impl Cell<'_> {
+    pub fn contains(&self, col: usize) -> bool {
+        col >= self.col && col < self.col + self.len
+    }
+}
+
+/// Screen cells of a line. Tabs expand to the next multiple of `tabstop`,
+/// wide (CJK, emoji) chars take two cells and zero-width chars share the
+/// cell of the char before them, matching how the terminal draws the text.
+pub fn layout_cells(content: &str, tabstop: usize) -> Vec<Cell<'_>> {
+    let mut cells: Vec<Cell> = Vec::with_capacity(content.len());
+    // Cells and start byte of the last drawn char, for attaching zero-width chars
+    let mut last_char: Option<(usize, usize)> = None;
+    // Zero-width chars with no char before them join the next char
+    let mut orphan: Option<(usize, usize)> = None;
+    for (idx, (byte, ch)) in content.char_indices().enumerate() {
+        let end = byte + ch.len_utf8();
+        if ch == '\t' {
+            let n = tabstop - cells.len() % tabstop;
+            cells.extend(std::iter::repeat_n(Cell { text: " ", col: idx, len: 1 }, n));
+            last_char = None;
+            continue;
+        }
+        let width = ch.width().unwrap_or(0);
+        if width == 0 {
+            match last_char {
+                Some((first, start)) => {
+                    for cell in &mut cells[first..] {
+                        cell.len += 1;
+                    }
+                    cells[first].text = &content[start..end];
+                }
+                None => {
+                    orphan.get_or_insert((byte, idx));
+                }
+            }
+            continue;
+        }
+        let (start, col) = orphan.take().unwrap_or((byte, idx));
+        let len = idx - col + 1;
+        last_char = Some((cells.len(), start));
+        cells.push(Cell { text: &content[start..end], col, len });
+        for _ in 1..width {
+            cells.push(Cell { text: "", col, len });
+        }
+    }
+    cells
+}
+
+/// `width` cells of a laid-out line starting at screen column `scroll_x`.
+/// Past the end, blank cells keep counting char indices so the cursor can sit
+/// after the text; wide chars cut in half by either edge show as a blank.
+pub fn visible_cells<'a>(
+    cells: &[Cell<'a>],
+    char_count: usize,
+    scroll_x: usize,
+    width: usize,
+) -> Vec<Cell<'a>> {
+    let mut visible: Vec<Cell> = (scroll_x..scroll_x + width)
+        .map(|i| match cells.get(i) {
+            Some(&cell) => cell,
+            None => Cell { text: " ", col: char_count + (i - cells.len()), len: 1 },
+        })
+        .collect();
+    if let Some(first) = visible.first_mut() {
+        if first.text.is_empty() {
+            first.text = " ";
+        }
+    }
+    let cut_after = cells.get(scroll_x + width).is_some_and(|c| c.text.is_empty());
+    if let (true, Some(last)) = (cut_after, visible.last_mut()) {
+        last.text = " ";
+    }
+    visible
+}
+
+/// First screen column of content char `col`.
+pub fn display_col(content: &str, col: usize, tabstop: usize) -> usize {
+    let cells = layout_cells(content, tabstop);
+    match cells.iter().position(|c| c.col + c.len > col) {
+        Some(pos) => pos,
+        None => cells.len() + col.saturating_sub(content.chars().count()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(cells: &[Cell]) -> String {
+        cells.iter().map(|c| if c.text.is_empty() { "" } else { c.text }).collect()
+    }
+
+    #[test]
+    fn tabs_align_to_the_next_tabstop() {
+        let cells = layout_cells("a\tbc\td", 4);
+        assert_eq!(screen(&cells), "a   bc  d");
+        // Each tab column belongs to the tab char
+        assert!(cells[1..4].iter().all(|c| c.col == 1));
+        assert!(cells[6..8].iter().all(|c| c.col == 4));
+        assert_eq!(cells[8].col, 5);
+    }
+
+    #[test]
+    fn cursor_on_a_tab_lands_on_its_first_cell() {
+        let line = "\tx\ty";
+        assert_eq!(display_col(line, 0, 4), 0);
+        assert_eq!(display_col(line, 1, 4), 4);
+        assert_eq!(display_col(line, 2, 4), 5);
+        assert_eq!(display_col(line, 3, 4), 8);
+        // Past the end the cursor keeps moving one cell per column
+        assert_eq!(display_col(line, 5, 4), 10);
+
+        // Scrolled halfway into the tab, its remaining cells still map to char 0
+        let cells = layout_cells(line, 4);
+        let visible = visible_cells(&cells, 4, 2, 4);
+        assert_eq!(screen(&visible), "  x ");
+        assert!(visible[0].contains(0) && !visible[2].contains(0));
+    }
+
+    #[test]
+    fn wide_chars_take_two_cells() {
+        let line = "a漢字b";
+        let cells = layout_cells(line, 4);
+        assert_eq!(cells.len(), 6);
+        assert_eq!(screen(&cells), "a漢字b");
+        assert_eq!((cells[1].col, cells[2].col, cells[2].text), (1, 1, ""));
+        assert_eq!(display_col(line, 2, 4), 3);
+        assert_eq!(display_col(line, 3, 4), 5);
+
+        // A wide char cut by either edge shows as a blank
+        let visible = visible_cells(&cells, 4, 2, 3);
+        assert_eq!(screen(&visible), " 字");
+        assert_eq!(visible.iter().map(|c| c.col).collect::<Vec<_>>(), [1, 2, 2]);
+        let visible = visible_cells(&cells, 4, 0, 4);
+        assert_eq!(screen(&visible), "a漢 ");
+    }
+
+    #[test]
+    fn emoji_take_two_cells_and_combining_marks_none() {
+        let line = "x🎉e\u{301}y";
+        let cells = layout_cells(line, 4);
+        assert_eq!(screen(&cells), line);
+        assert_eq!(cells.len(), 5);
+        assert_eq!(display_col(line, 1, 4), 1); // 🎉
+        assert_eq!(display_col(line, 2, 4), 3); // e
+        // The accent shares the cell of its e
+        assert_eq!(display_col(line, 3, 4), 3);
+        assert_eq!((cells[3].text, cells[3].len), ("e\u{301}", 2));
+        assert_eq!(display_col(line, 4, 4), 4); // y
+    }
+}
@@ -0,0 +1,65 @@
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+
+/// Color capability of the terminal. RGB colors (UI and syntax highlighting)
+/// are quantized to the xterm 256-color palette when true color is unavailable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor,
+    Indexed,
+}
+
+impl ColorDepth {
+    /// `COLORTERM=truecolor` or `24bit` means RGB is supported; anything else
+    /// is treated as a 256-color terminal.
+    pub fn detect() -> Self {
+        match std::env::var("COLORTERM").as_deref() {
+            Ok("truecolor") | Ok("24bit") => ColorDepth::TrueColor,
+            _ => ColorDepth::Indexed,
+        }
+    }
+
+    pub fn adapt(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::Indexed, Color::Rgb(r, g, b)) => Color::Indexed(rgb_to_ansi256(r, g, b)),
+            _ => color,
+        }
+    }
+
+    /// Quantize every RGB color in a rendered frame.
+    pub fn apply(self, buf: &mut Buffer) {
+        if self == ColorDepth::TrueColor {
+            return;
+        }
+        for cell in buf.content.iter_mut() {
+            cell.fg = self.adapt(cell.fg);
+            cell.bg = self.adapt(cell.bg);
+        }
+    }
+}
+
+/// Nearest xterm 256-color index: the 6x6x6 cube (16–231) or the gray ramp (232–255).
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let cube_idx = |v: u8| match v {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (v - 35) / 40,
+    };
+    let (ri, gi, bi) = (cube_idx(r), cube_idx(g), cube_idx(b));
+    let cube = (LEVELS[ri as usize], LEVELS[gi as usize], LEVELS[bi as usize]);
+
+    let avg = (r as u16 + g as u16 + b as u16) / 3;
+    let gray_idx = if avg > 238 { 23 } else { avg.saturating_sub(3) / 10 } as u8;
+    let gray = 8 + gray_idx * 10;
+
+    let dist = |(cr, cg, cb): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(cr, r) + d(cg, g) + d(cb, b)
+    };
+    if dist((gray, gray, gray)) < dist(cube) {
+        232 + gray_idx
+    } else {
+        16 + 36 * ri + 6 * gi + bi
+    }
+}
@@ -1,27 +1,51 @@
 use crate::app::{App, FocusedPane, SearchMatch, SearchOrigin};
+use crate::git::repository::{epoch_to_relative, SignatureStatus};
 use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState},
+    widgets::{Block, Borders, Cell, List, ListItem, Row, Table, TableState},
     Frame,
 };
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const RANGE_BG: Color = Color::Rgb(60, 60, 100);
+const AUTHOR_WIDTH: u16 = 12;
+/// Author column width with emails shown (`a`)
+const AUTHOR_EMAIL_WIDTH: u16 = 32;
 
 pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
     app.git_log.view_height = area.height.saturating_sub(2); // minus borders
+    app.clamp_log_selection();
     let border_color = if app.focused_pane == FocusedPane::GitLog {
         Color::Cyan
     } else {
         Color::DarkGray
     };
 
-    let block = Block::default()
-        .title(" Git Log ")
+    let log = &app.git_log;
+    let mut title = " Git Log ".to_string();
+    if !log.ref_name.is_empty() {
+        title.push_str(&format!("({}) ", log.ref_name));
+    }
+    let total = app.log_len();
+    if total > 0 {
+        title.push_str(&format!("[{}/{}] ", log.selected_idx + 1, total));
+    }
+    if let Some((first, last)) = log.range() {
+        title.push_str(&format!("{} selected ", last - first + 1));
+    }
+    let mut block = Block::default()
+        .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color));
+    if let Some(label) = app.search_match_label(SearchOrigin::CommitLog) {
+        block = block.title_bottom(Line::from(format!(" {label} ")).right_aligned());
+    }
 
-    if app.git_log.commits.is_empty() {
+    if total == 0 {
         let items: Vec<ListItem> = vec![ListItem::new(Line::from(Span::styled(
             "  No commits",
             Style::default().fg(Color::DarkGray),
@@ -53,10 +77,29 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
         (HashSet::new(), None)
     };
 
-    let items: Vec<ListItem> = app
-        .git_log
-        .commits
-        .iter()
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    let relative = app.git_log.relative_dates;
+    let show_email = app.git_log.show_email;
+    let author_width = if show_email { AUTHOR_EMAIL_WIDTH } else { AUTHOR_WIDTH };
+    let range = app.git_log.range();
+
+    let hash_width = app
+        .log_commits()
+        .map(|c| c.short_hash.width())
+        .max()
+        .unwrap_or(7) as u16
+        + 1; // leading space
+    // "3 months ago" fits in 15; absolute dates are `YYYY-MM-DD HH:MM`
+    let date_width: u16 = if relative { 15 } else { 16 };
+    // Borders, the signature mark and the gaps between the five columns
+    let message_width = area
+        .width
+        .saturating_sub(2 + hash_width + 1 + date_width + author_width + 4) as usize;
+
+    let rows: Vec<Row> = app
+        .log_commits()
         .enumerate()
         .map(|(idx, commit)| {
             let is_current = current_match_idx == Some(idx);
@@ -65,39 +108,37 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
                 Some(Color::Rgb(200, 120, 0))
             } else if is_match {
                 Some(Color::Rgb(60, 60, 0))
+            } else if range.is_some_and(|(first, last)| (first..=last).contains(&idx)) {
+                Some(RANGE_BG)
             } else {
                 None
             };
             let fg_override = if is_current { Some(Color::Black) } else { None };
+            let fg = |color: Color| Style::default().fg(fg_override.unwrap_or(color));
 
-            let hash_style = {
-                let mut s = Style::default().fg(fg_override.unwrap_or(Color::Yellow));
-                if let Some(bg) = bg { s = s.bg(bg); }
-                s
-            };
-            let date_style = {
-                let mut s = Style::default().fg(fg_override.unwrap_or(Color::DarkGray));
-                if let Some(bg) = bg { s = s.bg(bg); }
-                s
-            };
-            let author_style = {
-                let mut s = Style::default().fg(fg_override.unwrap_or(Color::Cyan));
-                if let Some(bg) = bg { s = s.bg(bg); }
-                s
-            };
-            let msg_style = {
-                let mut s = Style::default();
-                if let Some(fg) = fg_override { s = s.fg(fg); }
-                if let Some(bg) = bg { s = s.bg(bg); }
-                s
+            let date = if relative {
+                epoch_to_relative(commit.epoch, now)
+            } else {
+                format!("{} {}", commit.date, commit.time)
             };
-
-            ListItem::new(Line::from(vec![
-                Span::styled(format!(" {} ", commit.short_hash), hash_style),
-                Span::styled(format!("{} ", commit.date), date_style),
-                Span::styled(format!("{:<12} ", commit.author), author_style),
-                Span::styled(commit.message.clone(), msg_style),
-            ]))
+            let mut msg_style = Style::default();
+            if let Some(fg) = fg_override {
+                msg_style = msg_style.fg(fg);
+            }
+            let mut row = Row::new(vec![
+                Cell::from(Span::styled(format!(" {}", commit.short_hash), fg(Color::Yellow))),
+                Cell::from(signature_mark(app.signature_of(&commit.full_hash), fg_override)),
+                Cell::from(Span::styled(date, fg(Color::DarkGray))),
+                Cell::from(Span::styled(
+                    truncate_end(&commit.author_label(show_email), author_width as usize),
+                    fg(Color::Cyan),
+                )),
+                Cell::from(Span::styled(truncate_end(&commit.message, message_width), msg_style)),
+            ]);
+            if let Some(bg) = bg {
+                row = row.style(Style::default().bg(bg));
+            }
+            row
         })
         .collect();
 
@@ -112,9 +153,51 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
             .add_modifier(Modifier::BOLD)
     };
 
-    let list = List::new(items).block(block).highlight_style(highlight_style);
+    let widths = [
+        Constraint::Length(hash_width),
+        Constraint::Length(1),
+        Constraint::Length(date_width),
+        Constraint::Length(author_width),
+        Constraint::Fill(1),
+    ];
+    let table = Table::new(rows, widths)
+        .column_spacing(1)
+        .block(block)
+        .row_highlight_style(highlight_style);
 
-    let mut state = ListState::default();
+    let mut state = TableState::default();
     state.select(Some(selected));
-    f.render_stateful_widget(list, area, &mut state);
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+/// `✓` for a verified signature, `?` for one that didn't verify, blank for
+/// unsigned commits and ones not checked yet.
+fn signature_mark(status: Option<SignatureStatus>, fg_override: Option<Color>) -> Span<'static> {
+    let (mark, color) = match status {
+        Some(SignatureStatus::Verified) => ("\u{2713}", Color::Green),
+        Some(SignatureStatus::Unverified) => ("?", Color::Yellow),
+        Some(SignatureStatus::Unsigned) | None => (" ", Color::Reset),
+    };
+    Span::styled(mark, Style::default().fg(fg_override.unwrap_or(color)))
+}
+
+/// Cut `text` to at most `max_width` columns, ending in `…` when shortened.
+fn truncate_end(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > max_width {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    if max_width > 0 {
+        out.push('…');
+    }
+    out
 }
@@ -58,13 +58,32 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         Some(d) => d,
         None => return,
     };
+    render_dialog(f, area, &dialog.title, &dialog.message, " Press any key to dismiss", Color::Red);
+}
+
+/// Yes/no prompt for an action that needs confirming (e.g. quit).
+pub fn render_confirm(f: &mut Frame, app: &App, area: Rect) {
+    let dialog = match &app.confirm_dialog {
+        Some(d) => d,
+        None => return,
+    };
+    render_dialog(
+        f,
+        area,
+        &dialog.title,
+        &dialog.message,
+        " y / Enter: confirm   any other key: cancel",
+        Color::Yellow,
+    );
+}
 
+fn render_dialog(f: &mut Frame, area: Rect, title: &str, message: &str, hint: &str, accent: Color) {
     let dialog_width = 54u16.min(area.width.saturating_sub(4));
     let inner_w = dialog_width.saturating_sub(2) as usize;
     let text_w = inner_w.saturating_sub(2); // 1 char padding each side
 
-    let msg_lines = wrap_text(&dialog.message, text_w);
-    let total_lines = 1 + 1 + msg_lines.len() + 1 + 1; // title, blank, msg..., blank, dismiss
+    let msg_lines = wrap_text(message, text_w);
+    let total_lines = 1 + 1 + msg_lines.len() + 1 + 1; // title, blank, msg..., blank, hint
     let dialog_height = (total_lines as u16 + 2).min(area.height.saturating_sub(4));
     let x = (area.width.saturating_sub(dialog_width)) / 2;
     let y = (area.height.saturating_sub(dialog_height)) / 2;
@@ -77,9 +96,9 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
     // Title
     lines.push(pad_line(
         Line::from(Span::styled(
-            format!(" {}", dialog.title),
+            format!(" {title}"),
             Style::default()
-                .fg(Color::Red)
+                .fg(accent)
                 .bg(BG)
                 .add_modifier(Modifier::BOLD),
         )),
@@ -109,10 +128,10 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         inner_w,
     ));
 
-    // Dismiss hint
+    // Key hint
     lines.push(pad_line(
         Line::from(Span::styled(
-            " Press any key to dismiss".to_string(),
+            hint.to_string(),
             Style::default().fg(Color::DarkGray).bg(BG),
         )),
         inner_w,
@@ -129,7 +148,7 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
 
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Red).bg(BG))
+        .border_style(Style::default().fg(accent).bg(BG))
         .style(Style::default().bg(BG));
 
     let para = Paragraph::new(lines).block(block);
@@ -1,21 +1,84 @@
-use crate::app::{App, CursorPos, DiffSide, DiffViewMode, FocusedPane, SearchMatch};
-use crate::git::diff::{FileDiff, LineType, SideBySideRow};
+use crate::app::{
+    App, CursorPos, DiffSide, DiffViewMode, FocusedPane, SearchMatch, SearchOrigin, UnifiedLineKind,
+};
+use crate::git::diff::{FileDiff, FileStatus, LineType, SideBySideRow};
+use crate::ui::cells::{layout_cells, visible_cells};
+use crate::ui::file_tree;
+use crate::ui::github::ellipsize_middle;
+use crate::ui::image_preview::{self, Placement};
+use serde::Deserialize;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+use unicode_width::UnicodeWidthChar;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 
-const GUTTER_WIDTH: usize = 5; // "1234 "
+const GUTTER_WIDTH: usize = 5; // "1234 " or "1234+"
+const MIN_SIDE_TEXT_WIDTH: u16 = 10;
 const SELECTION_BG: Color = Color::Rgb(60, 60, 100);
 const CURSOR_FG: Color = Color::Black;
 const CURSOR_BG: Color = Color::White;
 const SEARCH_MATCH_BG: Color = Color::Rgb(60, 60, 0);
 const SEARCH_CURRENT_BG: Color = Color::Rgb(200, 120, 0);
 const SEARCH_CURRENT_FG: Color = Color::Black;
+const CONFLICT_MARKER_BG: Color = Color::Rgb(70, 55, 0);
+const CONFLICT_OURS_BG: Color = Color::Rgb(0, 35, 70);
+const CONFLICT_BASE_BG: Color = Color::Rgb(45, 45, 45);
+const CONFLICT_THEIRS_BG: Color = Color::Rgb(55, 0, 60);
+const MINIMAP_VIEWPORT_BG: Color = Color::Rgb(70, 70, 70);
+
+/// Part of a merge conflict a line of a conflicted file belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConflictRegion {
+    Marker,
+    Ours,
+    /// Common ancestor, with `merge.conflictStyle = diff3`
+    Base,
+    Theirs,
+}
+
+/// Conflict region of each display row, from the markers on the new side.
+fn conflict_regions(file: &FileDiff) -> Vec<Option<ConflictRegion>> {
+    let is_marker = |line: &str, marker: &str| {
+        line.strip_prefix(marker)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    };
+    let mut regions = Vec::with_capacity(file.row_count());
+    let mut current = None;
+    for hunk in &file.hunks {
+        regions.push(None);
+        for row in &hunk.rows {
+            let Some(line) = row.right.as_ref().map(|r| r.content.as_str()) else {
+                regions.push(None);
+                continue;
+            };
+            let region = if is_marker(line, "<<<<<<<") {
+                current = Some(ConflictRegion::Ours);
+                Some(ConflictRegion::Marker)
+            } else if current.is_some() && is_marker(line, "|||||||") {
+                current = Some(ConflictRegion::Base);
+                Some(ConflictRegion::Marker)
+            } else if current.is_some() && line == "=======" {
+                current = Some(ConflictRegion::Theirs);
+                Some(ConflictRegion::Marker)
+            } else if current.is_some() && is_marker(line, ">>>>>>>") {
+                current = None;
+                Some(ConflictRegion::Marker)
+            } else {
+                current
+            };
+            regions.push(region);
+        }
+    }
+    regions
+}
 
 /// Pre-computed search highlight info for the current file
 struct SearchHighlightInfo {
@@ -52,6 +115,29 @@ impl SearchHighlightInfo {
         Some(Self { row_matches })
     }
 
+    /// Matches in the unified view, keyed by line, with columns counted after
+    /// the line's `+` / `-` / ` ` prefix.
+    fn from_unified(app: &App) -> Option<Self> {
+        let view = app.unified_view.as_ref()?;
+        if app.search.origin != SearchOrigin::UnifiedView || app.search.query.is_none() {
+            return None;
+        }
+        let current_idx = app.search.current_match_idx;
+        let mut row_matches: HashMap<usize, Vec<(usize, usize, bool, DiffSide)>> = HashMap::new();
+        for (i, m) in app.search.matches.iter().enumerate() {
+            if let SearchMatch::UnifiedLine { line, col_start, col_end } = *m {
+                let prefix = view.lines.get(line).map_or(0, |l| l.kind.prefix_len());
+                row_matches.entry(line).or_default().push((
+                    col_start.saturating_sub(prefix),
+                    col_end.saturating_sub(prefix),
+                    current_idx == Some(i),
+                    DiffSide::Left,
+                ));
+            }
+        }
+        Some(Self { row_matches })
+    }
+
     /// Check if a character at (row, col) on the given side has a search highlight.
     /// Returns Some(is_current) if highlighted, None otherwise.
     fn get_highlight(&self, row_idx: usize, col: usize, is_left: bool) -> Option<bool> {
@@ -67,12 +153,70 @@ impl SearchHighlightInfo {
     }
 }
 
+/// Old and new versions of a changed image side by side. Only the labels are
+/// drawn here; the images themselves are written after the frame.
+fn render_image_preview(f: &mut Frame, app: &mut App, file: &FileDiff, inner: Rect) {
+    let key = format!(
+        "{}:{}:{}:{}",
+        app.image_preview.generation,
+        app.diff_scope.label(),
+        app.diff_base_ref.as_deref().unwrap_or(""),
+        file.path
+    );
+    let (left_bytes, right_bytes) = match app.image_preview.cached(&key) {
+        Some(p) => (
+            p.left.as_ref().map(|(_, b)| b.clone()),
+            p.right.as_ref().map(|(_, b)| b.clone()),
+        ),
+        None => {
+            let (left, right) = app.file_bytes(file);
+            (left.map(Rc::from), right.map(Rc::from))
+        }
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(50),
+            Constraint::Length(1),
+            Constraint::Percentage(50),
+        ])
+        .split(inner);
+    let sep: Vec<Line> = (0..chunks[1].height).map(|_| Line::from("│")).collect();
+    f.render_widget(
+        Paragraph::new(sep).style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+
+    let mut side = |area: Rect, label: &str, bytes: Option<Rc<[u8]>>| {
+        let text = if bytes.is_some() {
+            format!(" {label}")
+        } else {
+            format!(" {label} (none)")
+        };
+        f.render_widget(
+            Paragraph::new(Line::from(Span::styled(text, Style::default().fg(Color::DarkGray)))),
+            area,
+        );
+        let image_area = Rect {
+            y: area.y + 1,
+            height: area.height.saturating_sub(1),
+            ..area
+        };
+        bytes.map(|b| (image_area, b))
+    };
+    let left = side(chunks[0], "old", left_bytes);
+    let right = side(chunks[2], "new", right_bytes);
+    app.image_preview.requested = Some(Placement { key, left, right });
+}
+
 /// Selection range info passed to rendering functions
 struct SelectionInfo {
     start: CursorPos,
     end: CursorPos,
     mode: DiffViewMode,
     cursor: CursorPos,
+    relative_numbers: bool,
 }
 
 pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
@@ -82,15 +226,43 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
         Color::DarkGray
     };
 
+    // The tree may only show the file name (or scroll it away), so keep the
+    // full path, and any rename, in the title
+    let title = match app.selected_file() {
+        Some(file) => {
+            // Binary files read as text with `T` are flagged as such
+            let forced = app.forced_text.contains(&file.path).then_some(" FORCED TEXT ");
+            let budget = (area.width as usize)
+                .saturating_sub(" Diff: M  ".len() + 2 + forced.map_or(0, str::len));
+            let mut spans = vec![
+                Span::raw(" Diff: "),
+                Span::styled(
+                    file.status.icon(),
+                    Style::default()
+                        .fg(file_tree::status_color(file.status))
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!(" {} ", ellipsize_middle(&file.display_path(), budget))),
+            ];
+            if let Some(label) = forced {
+                spans.push(Span::styled(
+                    label,
+                    Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD),
+                ));
+            }
+            Line::from(spans)
+        }
+        None => Line::from(" Diff "),
+    };
     let block = Block::default()
-        .title(" Diff ")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color));
 
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    let file = match app.selected_file() {
+    let full = match app.selected_file() {
         Some(f) => f.clone(),
         None => {
             let msg = Paragraph::new(Line::from(Span::styled(
@@ -102,10 +274,21 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
             return;
         }
     };
+    // With context hidden, rows are those of the filtered file; highlighting
+    // still covers the whole file, so its rows are looked up through `shown_rows`
+    let (file, shown_rows) = match app.changes_only {
+        true => (Cow::Owned(full.changes_only()), Some(full.change_rows())),
+        false => (Cow::Borrowed(&full), None),
+    };
 
     if file.is_binary {
+        if app.image_preview.protocol.is_some() && image_preview::is_previewable(&file.raw_path) {
+            render_image_preview(f, app, &file, inner);
+            app.diff_total_lines = 0;
+            return;
+        }
         let msg = Paragraph::new(Line::from(Span::styled(
-            "  Binary file",
+            "  Binary file (O to open, T to show as text)",
             Style::default().fg(Color::DarkGray),
         )));
         f.render_widget(msg, inner);
@@ -126,16 +309,54 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
 
     // Ensure syntax highlighting covers the visible range (incremental)
     let visible_end = (app.diff_scroll_y as usize) + (content_area.height as usize) + 1;
-    app.ensure_file_highlight(&file, visible_end);
+    let highlight_end = match &shown_rows {
+        Some(rows) => rows.get(visible_end).map_or(full.row_count(), |row| row + 1),
+        None => visible_end,
+    };
+    app.ensure_file_highlight(&full, highlight_end);
+    let large_file = app.is_large_file(&full);
 
-    // Split content area: left half | separator | right half
-    let left_width = (content_area.width.saturating_sub(1)) / 2;
-    let right_width = content_area.width.saturating_sub(left_width + 1);
+    // Only the visible window is turned into spans; the row count is derived
+    // from the hunks so huge diffs don't pay for off-screen rows.
+    let total_lines = file.row_count().max(1).min(u16::MAX as usize) as u16;
+    app.diff_total_lines = total_lines;
+    app.diff_view_height = content_area.height;
+    let visible = (app.diff_scroll_y as usize)..(app.diff_scroll_y as usize + content_area.height as usize);
+
+    // Diffs taller than the pane get a one-column change minimap on the right
+    let (content_area, minimap_area) = if total_lines > content_area.height && content_area.width > 1 {
+        let width = content_area.width - 1;
+        (
+            Rect { width, ..content_area },
+            Some(Rect { x: content_area.x + width, width: 1, ..content_area }),
+        )
+    } else {
+        (content_area, None)
+    };
+
+    // Split content area: left | separator | right, keeping both sides wider
+    // than the gutter. Files with nothing on the old side get the full width.
+    let single_column = app.single_column();
+    let gutter_width = if app.show_line_numbers { GUTTER_WIDTH } else { 0 };
+    let (left_width, sep_width, right_width) = if single_column {
+        (0, 0, content_area.width)
+    } else {
+        let available = content_area.width.saturating_sub(1);
+        let min_side = (gutter_width as u16 + MIN_SIDE_TEXT_WIDTH).min(available / 2);
+        let left_width = ((available as f32 * app.diff_split_ratio).round() as u16)
+            .clamp(min_side, available - min_side);
+        (left_width, 1, content_area.width.saturating_sub(left_width + 1))
+    };
+    app.diff_content_width = if single_column {
+        right_width.saturating_sub(gutter_width as u16)
+    } else {
+        left_width.min(right_width).saturating_sub(gutter_width as u16)
+    };
     let panes = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
             Constraint::Length(left_width),
-            Constraint::Length(1),
+            Constraint::Length(sep_width),
             Constraint::Length(right_width),
         ])
         .split(content_area);
@@ -153,8 +374,24 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
             Some(c) => (&c.left_colors, &c.right_colors),
             None => (&empty, &empty),
         };
+        // Only rows in the window need their colors moved over
+        let shown_colors = |colors: &Vec<Vec<Color>>| -> Vec<Vec<Color>> {
+            let rows = shown_rows.as_deref().unwrap_or_default();
+            rows.iter()
+                .enumerate()
+                .map(|(idx, row)| match visible.contains(&idx) {
+                    true => colors.get(*row).cloned().unwrap_or_default(),
+                    false => Vec::new(),
+                })
+                .collect()
+        };
+        let (lc, rc) = match shown_rows {
+            Some(_) => (&shown_colors(lc), &shown_colors(rc)),
+            None => (lc, rc),
+        };
         build_side_by_side_lines(
             &file,
+            visible,
             left_width as usize,
             right_width as usize,
             app.diff_scroll_x,
@@ -162,14 +399,13 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
             lc,
             rc,
             &search_hl,
+            app.config.tabstop,
+            gutter_width,
+            app.diff_palette,
         )
     };
 
-    let total_lines = left_lines.len() as u16;
-    app.diff_total_lines = total_lines;
-    app.diff_view_height = content_area.height;
-
-    let left_para = Paragraph::new(left_lines).scroll((app.diff_scroll_y, 0));
+    let left_para = Paragraph::new(left_lines);
     f.render_widget(left_para, panes[0]);
 
     // Separator
@@ -179,14 +415,140 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
     let sep = Paragraph::new(sep_lines).scroll((0, 0));
     f.render_widget(sep, panes[1]);
 
-    let right_para = Paragraph::new(right_lines).scroll((app.diff_scroll_y, 0));
+    let right_para = Paragraph::new(right_lines);
     f.render_widget(right_para, panes[2]);
 
+    if let Some(area) = minimap_area {
+        render_minimap(f, &file, app.diff_palette, app.diff_scroll_y as usize, area);
+    }
+
     // Status line
-    render_diff_statusline(f, app, &file.path, total_lines, statusline_area);
+    render_diff_statusline(f, app, &file.path, total_lines, large_file, statusline_area);
+}
+
+/// How added and deleted lines are told apart. The alternatives to red/green
+/// are for readers who have trouble distinguishing those two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffPalette {
+    #[default]
+    RedGreen,
+    BlueOrange,
+    /// No tint; `+` / `-` in the line-number gutter mark changed lines
+    Symbols,
+}
+
+impl DiffPalette {
+    pub fn next(self) -> Self {
+        match self {
+            DiffPalette::RedGreen => DiffPalette::BlueOrange,
+            DiffPalette::BlueOrange => DiffPalette::Symbols,
+            DiffPalette::Symbols => DiffPalette::RedGreen,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DiffPalette::RedGreen => "red-green",
+            DiffPalette::BlueOrange => "blue-orange",
+            DiffPalette::Symbols => "symbols",
+        }
+    }
+
+    /// Foreground and background of added and of deleted lines.
+    fn colors(self) -> ((Color, Option<Color>), (Color, Option<Color>)) {
+        match self {
+            DiffPalette::RedGreen => (
+                (Color::Green, Some(Color::Rgb(0, 40, 0))),
+                (Color::Red, Some(Color::Rgb(40, 0, 0))),
+            ),
+            DiffPalette::BlueOrange => (
+                (Color::Rgb(100, 170, 255), Some(Color::Rgb(0, 30, 65))),
+                (Color::Rgb(255, 160, 60), Some(Color::Rgb(60, 30, 0))),
+            ),
+            DiffPalette::Symbols => ((Color::Reset, None), (Color::Reset, None)),
+        }
+    }
+
+    /// Minimap cell for a band whose rows mostly make `change`.
+    fn minimap_cell(self, change: RowChange) -> (&'static str, Color) {
+        let ((added, _), (deleted, _)) = self.colors();
+        match (self, change) {
+            (DiffPalette::Symbols, RowChange::Added) => ("+", Color::Reset),
+            (DiffPalette::Symbols, RowChange::Deleted) => ("-", Color::Reset),
+            (DiffPalette::Symbols, RowChange::Modified) => ("~", Color::Reset),
+            (DiffPalette::BlueOrange, RowChange::Modified) => ("▐", Color::Magenta),
+            (_, RowChange::Added) => ("▐", added),
+            (_, RowChange::Deleted) => ("▐", deleted),
+            (_, RowChange::Modified) => ("▐", Color::Yellow),
+        }
+    }
+}
+
+/// What a display row changes, for the minimap.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RowChange {
+    Added,
+    Deleted,
+    Modified,
+}
+
+/// A one-column overview of the whole file: each cell covers a proportional
+/// band of rows and is colored by the change most common in it, and the bands
+/// currently on screen are shaded.
+fn render_minimap(f: &mut Frame, file: &FileDiff, palette: DiffPalette, scroll: usize, area: Rect) {
+    let mut changes = Vec::with_capacity(file.row_count());
+    for hunk in &file.hunks {
+        changes.push(None); // hunk header
+        for row in &hunk.rows {
+            changes.push(match (row.line_type, row.left.is_some(), row.right.is_some()) {
+                (LineType::Context | LineType::HunkHeader, _, _) => None,
+                (_, true, true) => Some(RowChange::Modified),
+                (_, true, false) => Some(RowChange::Deleted),
+                (_, false, _) => Some(RowChange::Added),
+            });
+        }
+    }
+    let total = changes.len();
+    let height = area.height as usize;
+    if total == 0 || height == 0 {
+        return;
+    }
+    let viewport = scroll..scroll + height;
+
+    let lines: Vec<Line> = (0..height)
+        .map(|y| {
+            let start = y * total / height;
+            let end = ((y + 1) * total / height).max(start + 1).min(total);
+            let count = |kind| changes[start..end].iter().filter(|c| **c == Some(kind)).count();
+            let dominant = [RowChange::Added, RowChange::Deleted, RowChange::Modified]
+                .into_iter()
+                .map(|kind| (count(kind), kind))
+                .filter(|(n, _)| *n > 0)
+                .max_by_key(|(n, _)| *n)
+                .map(|(_, kind)| kind);
+            let (symbol, fg) = match dominant {
+                Some(change) => palette.minimap_cell(change),
+                None => ("│", Color::DarkGray),
+            };
+            let mut style = Style::default().fg(fg);
+            if start < viewport.end && viewport.start < end {
+                style = style.bg(MINIMAP_VIEWPORT_BG);
+            }
+            Line::from(Span::styled(symbol, style))
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), area);
 }
 
-fn render_diff_statusline(f: &mut Frame, app: &App, file_path: &str, total_lines: u16, area: Rect) {
+fn render_diff_statusline(
+    f: &mut Frame,
+    app: &App,
+    file_path: &str,
+    total_lines: u16,
+    large_file: bool,
+    area: Rect,
+) {
     let width = area.width as usize;
 
     // Mode badge
@@ -195,6 +557,7 @@ fn render_diff_statusline(f: &mut Frame, app: &App, file_path: &str, total_lines
         DiffViewMode::Normal => ("NORMAL", Style::default().fg(Color::Black).bg(Color::Cyan)),
         DiffViewMode::Visual => ("VISUAL", Style::default().fg(Color::Black).bg(Color::Magenta)),
         DiffViewMode::VisualLine => ("V-LINE", Style::default().fg(Color::Black).bg(Color::Magenta)),
+        DiffViewMode::VisualBlock => ("V-BLOCK", Style::default().fg(Color::Black).bg(Color::Magenta)),
     };
 
     // File type from extension
@@ -249,6 +612,24 @@ fn render_diff_statusline(f: &mut Frame, app: &App, file_path: &str, total_lines
             Style::default().fg(Color::White).bg(Color::Rgb(50, 50, 50)),
         ));
     }
+    if large_file {
+        spans.push(Span::styled(
+            " syntax off (large file) ",
+            Style::default().fg(Color::Yellow).bg(Color::Rgb(50, 50, 50)),
+        ));
+    }
+    if app.highlight_pending {
+        spans.push(Span::styled(
+            " highlighting\u{2026} ",
+            Style::default().fg(Color::DarkGray).bg(Color::Rgb(50, 50, 50)),
+        ));
+    }
+    if app.changes_only {
+        spans.push(Span::styled(
+            " changes only ",
+            Style::default().fg(Color::Cyan).bg(Color::Rgb(50, 50, 50)),
+        ));
+    }
 
     // Calculate left part width
     let left_len: usize = spans.iter().map(|s| s.content.chars().count()).sum();
@@ -266,8 +647,11 @@ fn render_diff_statusline(f: &mut Frame, app: &App, file_path: &str, total_lines
         }
     }
 
-    // Right-aligned showcmd + position info
-    let right_part = format!(" {position_info} ");
+    // Right-aligned showcmd + search match count + position info
+    let right_part = match app.search_match_label(SearchOrigin::DiffView) {
+        Some(label) => format!(" {label}  {position_info} "),
+        None => format!(" {position_info} "),
+    };
     let right_len = right_part.chars().count();
     let showcmd_part = if showcmd.is_empty() {
         String::new()
@@ -303,6 +687,7 @@ fn build_selection_info(app: &App) -> Option<SelectionInfo> {
             end: app.cursor_pos,
             mode: DiffViewMode::Normal,
             cursor: app.cursor_pos,
+            relative_numbers: app.relative_numbers,
         }),
         DiffViewMode::Visual => {
             let anchor = app.visual_anchor?;
@@ -318,6 +703,7 @@ fn build_selection_info(app: &App) -> Option<SelectionInfo> {
                 end,
                 mode: DiffViewMode::Visual,
                 cursor: app.cursor_pos,
+                relative_numbers: app.relative_numbers,
             })
         }
         DiffViewMode::VisualLine => {
@@ -329,14 +715,36 @@ fn build_selection_info(app: &App) -> Option<SelectionInfo> {
                 end: CursorPos { row: end_row, col: usize::MAX, side: app.cursor_pos.side },
                 mode: DiffViewMode::VisualLine,
                 cursor: app.cursor_pos,
+                relative_numbers: app.relative_numbers,
+            })
+        }
+        DiffViewMode::VisualBlock => {
+            let anchor = app.visual_anchor?;
+            let side = app.cursor_pos.side;
+            Some(SelectionInfo {
+                start: CursorPos {
+                    row: anchor.row.min(app.cursor_pos.row),
+                    col: anchor.col.min(app.cursor_pos.col),
+                    side,
+                },
+                end: CursorPos {
+                    row: anchor.row.max(app.cursor_pos.row),
+                    col: anchor.col.max(app.cursor_pos.col),
+                    side,
+                },
+                mode: DiffViewMode::VisualBlock,
+                cursor: app.cursor_pos,
+                relative_numbers: app.relative_numbers,
             })
         }
         DiffViewMode::Scroll => None,
     }
 }
 
+/// Build display lines for the rows of `file` that fall inside `visible`.
 fn build_side_by_side_lines<'a>(
     file: &FileDiff,
+    visible: Range<usize>,
     left_width: usize,
     right_width: usize,
     scroll_x: u16,
@@ -344,64 +752,88 @@ fn build_side_by_side_lines<'a>(
     left_colors: &[Vec<Color>],
     right_colors: &[Vec<Color>],
     search_hl: &Option<SearchHighlightInfo>,
+    tabstop: usize,
+    gutter_width: usize,
+    palette: DiffPalette,
 ) -> (Vec<Line<'a>>, Vec<Line<'a>>) {
     let mut left_lines = Vec::new();
     let mut right_lines = Vec::new();
     let mut row_idx: usize = 0;
+    let conflicts = match file.status {
+        FileStatus::Conflicted => conflict_regions(file),
+        _ => Vec::new(),
+    };
 
     for hunk in &file.hunks {
-        // Hunk header — no syntax highlighting for headers
-        let header_style = Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD);
-        left_lines.push(Line::from(Span::styled(
-            pad_to_width(&hunk.header, left_width),
-            header_style,
-        )));
-        right_lines.push(Line::from(Span::styled(
-            pad_to_width(&hunk.header, right_width),
-            header_style,
-        )));
+        // Skip whole hunks above the window, stop once past it
+        let hunk_len = hunk.rows.len() + 1;
+        if row_idx + hunk_len <= visible.start {
+            row_idx += hunk_len;
+            continue;
+        }
+        if row_idx >= visible.end {
+            break;
+        }
+
+        if visible.contains(&row_idx) {
+            // Hunk header — no syntax highlighting for headers
+            let header_style = Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD);
+            left_lines.push(Line::from(Span::styled(
+                pad_to_width(&hunk.header, left_width),
+                header_style,
+            )));
+            right_lines.push(Line::from(Span::styled(
+                pad_to_width(&hunk.header, right_width),
+                header_style,
+            )));
 
-        // Apply selection/search to hunk header if needed
-        if let Some(sel) = selection {
-            if sel.cursor.side == DiffSide::Left {
+            // Apply selection/search to hunk header if needed
+            if let Some(sel) = selection {
+                if sel.cursor.side == DiffSide::Left {
+                    let idx = left_lines.len() - 1;
+                    left_lines[idx] = apply_selection_to_line(
+                        &hunk.header, row_idx, left_width, scroll_x as usize, sel, header_style, None,
+                        search_hl, true, tabstop,
+                    );
+                }
+                if sel.cursor.side == DiffSide::Right {
+                    let idx = right_lines.len() - 1;
+                    right_lines[idx] = apply_selection_to_line(
+                        &hunk.header, row_idx, right_width, scroll_x as usize, sel, header_style, None,
+                        search_hl, false, tabstop,
+                    );
+                }
+            } else if search_hl.is_some() {
+                // No selection but search highlights may apply
                 let idx = left_lines.len() - 1;
-                left_lines[idx] = apply_selection_to_line(
-                    &hunk.header, row_idx, left_width, scroll_x as usize, sel, header_style, None,
-                    search_hl, true,
+                left_lines[idx] = apply_search_to_line(
+                    &hunk.header, row_idx, left_width, scroll_x as usize, header_style, None,
+                    search_hl, true, tabstop,
                 );
-            }
-            if sel.cursor.side == DiffSide::Right {
                 let idx = right_lines.len() - 1;
-                right_lines[idx] = apply_selection_to_line(
-                    &hunk.header, row_idx, right_width, scroll_x as usize, sel, header_style, None,
-                    search_hl, false,
+                right_lines[idx] = apply_search_to_line(
+                    &hunk.header, row_idx, right_width, scroll_x as usize, header_style, None,
+                    search_hl, false, tabstop,
                 );
             }
-        } else if search_hl.is_some() {
-            // No selection but search highlights may apply
-            let idx = left_lines.len() - 1;
-            left_lines[idx] = apply_search_to_line(
-                &hunk.header, row_idx, left_width, scroll_x as usize, header_style, None,
-                search_hl, true,
-            );
-            let idx = right_lines.len() - 1;
-            right_lines[idx] = apply_search_to_line(
-                &hunk.header, row_idx, right_width, scroll_x as usize, header_style, None,
-                search_hl, false,
-            );
         }
-
         row_idx += 1;
 
-        for row in &hunk.rows {
+        let skip = visible.start.saturating_sub(row_idx).min(hunk.rows.len());
+        row_idx += skip;
+        for row in hunk.rows.iter().skip(skip) {
+            if row_idx >= visible.end {
+                break;
+            }
             // Colors are pre-expanded in cache; just get a slice reference
             let left_syntax = left_colors.get(row_idx).map(|v| v.as_slice());
             let right_syntax = right_colors.get(row_idx).map(|v| v.as_slice());
+            let conflict = conflicts.get(row_idx).copied().flatten();
             let (left, right) = render_row(
                 row, left_width, right_width, scroll_x as usize, row_idx, selection,
-                left_syntax, right_syntax, search_hl,
+                left_syntax, right_syntax, search_hl, tabstop, conflict, gutter_width, palette,
             );
             left_lines.push(left);
             right_lines.push(right);
@@ -409,7 +841,7 @@ fn build_side_by_side_lines<'a>(
         }
     }
 
-    if left_lines.is_empty() {
+    if file.hunks.is_empty() {
         left_lines.push(Line::from(Span::styled(
             "  No changes",
             Style::default().fg(Color::DarkGray),
@@ -430,21 +862,37 @@ fn render_row<'a>(
     left_syntax: Option<&[Color]>,
     right_syntax: Option<&[Color]>,
     search_hl: &Option<SearchHighlightInfo>,
+    tabstop: usize,
+    conflict: Option<ConflictRegion>,
+    gutter_width: usize,
+    palette: DiffPalette,
 ) -> (Line<'a>, Line<'a>) {
+    // Symbols mode marks both sides of a changed row in the gutter
+    let (left_marker, right_marker) = match (palette, row.line_type) {
+        (DiffPalette::Symbols, LineType::Added | LineType::Deleted) => ('-', '+'),
+        _ => (' ', ' '),
+    };
+    let right_colors = match conflict {
+        Some(ConflictRegion::Marker) => (Color::Yellow, Some(CONFLICT_MARKER_BG)),
+        Some(ConflictRegion::Ours) => (Color::Reset, Some(CONFLICT_OURS_BG)),
+        Some(ConflictRegion::Base) => (Color::Reset, Some(CONFLICT_BASE_BG)),
+        Some(ConflictRegion::Theirs) => (Color::Reset, Some(CONFLICT_THEIRS_BG)),
+        None => line_colors(palette, row.line_type, false),
+    };
     let left = render_side_with_selection(
-        row.left.as_ref(), row.line_type, true, left_width, scroll_x, row_idx, selection,
-        left_syntax, search_hl,
+        row.left.as_ref(), line_colors(palette, row.line_type, true), true, left_width, scroll_x, row_idx, selection,
+        left_syntax, search_hl, tabstop, gutter_width, left_marker,
     );
     let right = render_side_with_selection(
-        row.right.as_ref(), row.line_type, false, right_width, scroll_x, row_idx, selection,
-        right_syntax, search_hl,
+        row.right.as_ref(), right_colors, false, right_width, scroll_x, row_idx, selection,
+        right_syntax, search_hl, tabstop, gutter_width, right_marker,
     );
     (left, right)
 }
 
 fn render_side_with_selection<'a>(
     side: Option<&crate::git::diff::SideLine>,
-    line_type: LineType,
+    (fg, bg): (Color, Option<Color>),
     is_left: bool,
     width: usize,
     scroll_x: usize,
@@ -452,12 +900,26 @@ fn render_side_with_selection<'a>(
     selection: &Option<SelectionInfo>,
     syntax_colors: Option<&[Color]>,
     search_hl: &Option<SearchHighlightInfo>,
+    tabstop: usize,
+    gutter_width: usize,
+    marker: char,
 ) -> Line<'a> {
     match side {
         Some(line) => {
-            let content_width = width.saturating_sub(GUTTER_WIDTH);
-            let gutter = format!("{:>4} ", line.line_no);
-            let (fg, bg) = line_colors(line_type, is_left);
+            let content_width = width.saturating_sub(gutter_width);
+            let line_no = match selection {
+                Some(sel) if sel.relative_numbers => sel.cursor.row.abs_diff(row_idx) as u32,
+                _ => line.line_no,
+            };
+            // An empty span when the gutter is hidden keeps the span layout the same
+            let gutter = if gutter_width == 0 {
+                vec![Span::raw("")]
+            } else {
+                vec![
+                    Span::styled(format!("{:>4}", line_no), Style::default().fg(Color::DarkGray)),
+                    Span::styled(marker.to_string(), Style::default().add_modifier(Modifier::BOLD)),
+                ]
+            };
             let base_style = style_for(fg, bg);
 
             let sel_side = selection.as_ref().map(|s| s.cursor.side);
@@ -473,7 +935,9 @@ fn render_side_with_selection<'a>(
                     // other rows can use the cheaper syntax-only path (unless search highlights exist).
                     let needs_highlight = match sel.mode {
                         DiffViewMode::Normal => sel.cursor.row == row_idx,
-                        DiffViewMode::Visual | DiffViewMode::VisualLine => true,
+                        DiffViewMode::Visual
+                        | DiffViewMode::VisualLine
+                        | DiffViewMode::VisualBlock => true,
                         DiffViewMode::Scroll => false,
                     };
                     let has_search = search_hl.as_ref().is_some_and(|sh| sh.row_matches.contains_key(&row_idx));
@@ -481,11 +945,9 @@ fn render_side_with_selection<'a>(
                         let content = &line.content;
                         let spans = build_highlighted_spans(
                             content, row_idx, content_width, scroll_x, sel, base_style,
-                            syntax_colors, search_hl, is_left,
+                            syntax_colors, search_hl, is_left, tabstop,
                         );
-                        let mut all_spans = vec![
-                            Span::styled(gutter, Style::default().fg(Color::DarkGray)),
-                        ];
+                        let mut all_spans = gutter;
                         all_spans.extend(spans);
                         return Line::from(all_spans);
                     }
@@ -498,20 +960,17 @@ fn render_side_with_selection<'a>(
                 let syn_colors = syntax_colors.unwrap_or(&[]);
                 let spans = build_syntax_spans(
                     &line.content, content_width, scroll_x, base_style, syn_colors,
-                    search_hl, row_idx, is_left,
+                    search_hl, row_idx, is_left, tabstop,
                 );
-                let mut all_spans = vec![
-                    Span::styled(gutter, Style::default().fg(Color::DarkGray)),
-                ];
+                let mut all_spans = gutter;
                 all_spans.extend(spans);
                 return Line::from(all_spans);
             }
 
-            let content = scroll_content(&line.content, scroll_x, content_width);
-            Line::from(vec![
-                Span::styled(gutter, Style::default().fg(Color::DarkGray)),
-                Span::styled(pad_to_width(&content, content_width), base_style),
-            ])
+            let content = scroll_content(&line.content, scroll_x, content_width, tabstop);
+            let mut all_spans = gutter;
+            all_spans.push(Span::styled(pad_to_width(&content, content_width), base_style));
+            Line::from(all_spans)
         }
         None => {
             Line::from(Span::styled(pad_to_width("", width), Style::default()))
@@ -529,19 +988,15 @@ fn build_syntax_spans<'a>(
     search_hl: &Option<SearchHighlightInfo>,
     row_idx: usize,
     is_left: bool,
+    tabstop: usize,
 ) -> Vec<Span<'a>> {
-    let chars: Vec<char> = content.chars().collect();
-    let start = scroll_x.min(chars.len());
+    let cells = layout_cells(content, tabstop);
+    let display = visible_cells(&cells, content.chars().count(), scroll_x, content_width);
 
     let mut spans = Vec::new();
     let mut i = 0;
-    while i < content_width {
-        let content_idx = start + i;
-        let ch = if content_idx < chars.len() {
-            chars[content_idx]
-        } else {
-            ' '
-        };
+    while i < display.len() {
+        let content_idx = display[i].col;
         let fg = if content_idx < syntax_colors.len() {
             syntax_colors[content_idx]
         } else {
@@ -551,11 +1006,9 @@ fn build_syntax_spans<'a>(
 
         // Batch consecutive chars with same fg and same search state
         let mut j = i + 1;
-        let mut run = String::new();
-        run.push(ch);
-        while j < content_width {
-            let cidx = start + j;
-            let next_ch = if cidx < chars.len() { chars[cidx] } else { ' ' };
+        let mut run = String::from(display[i].text);
+        while j < display.len() {
+            let cidx = display[j].col;
             let next_fg = if cidx < syntax_colors.len() {
                 syntax_colors[cidx]
             } else {
@@ -565,7 +1018,7 @@ fn build_syntax_spans<'a>(
             if next_fg != fg || next_search != search_highlight {
                 break;
             }
-            run.push(next_ch);
+            run.push_str(display[j].text);
             j += 1;
         }
 
@@ -596,25 +1049,18 @@ fn build_highlighted_spans<'a>(
     syntax_colors: Option<&[Color]>,
     search_hl: &Option<SearchHighlightInfo>,
     is_left: bool,
+    tabstop: usize,
 ) -> Vec<Span<'a>> {
-    let chars: Vec<char> = content.chars().collect();
-    // Pad to content_width
-    let mut display: Vec<char> = Vec::with_capacity(content_width);
-    let start = scroll_x.min(chars.len());
-    for i in start..(start + content_width) {
-        if i < chars.len() {
-            display.push(chars[i]);
-        } else {
-            display.push(' ');
-        }
-    }
+    let cells = layout_cells(content, tabstop);
+    // Visible cells padded to content_width
+    let display = visible_cells(&cells, content.chars().count(), scroll_x, content_width);
 
     // Determine which columns (in content coords, pre-scroll) are selected
     let mut spans = Vec::new();
     let mut i = 0;
     while i < display.len() {
-        let content_col = i + scroll_x;
-        let is_cursor = sel.cursor.row == row_idx && sel.cursor.col == content_col;
+        let content_col = display[i].col;
+        let is_cursor = sel.cursor.row == row_idx && display[i].contains(sel.cursor.col);
         let is_selected = is_in_selection(row_idx, content_col, sel);
         let search_highlight = search_hl.as_ref().and_then(|sh| sh.get_highlight(row_idx, content_col, is_left));
         // Get syntax fg for this character
@@ -623,8 +1069,8 @@ fn build_highlighted_spans<'a>(
         // Find run of chars with same highlight state AND same syntax color
         let mut j = i + 1;
         while j < display.len() {
-            let cc = j + scroll_x;
-            let next_cursor = sel.cursor.row == row_idx && sel.cursor.col == cc;
+            let cc = display[j].col;
+            let next_cursor = sel.cursor.row == row_idx && display[j].contains(sel.cursor.col);
             let next_selected = is_in_selection(row_idx, cc, sel);
             let next_search = search_hl.as_ref().and_then(|sh| sh.get_highlight(row_idx, cc, is_left));
             let next_syn_fg = syntax_colors.and_then(|sc| sc.get(cc).copied());
@@ -634,7 +1080,7 @@ fn build_highlighted_spans<'a>(
             j += 1;
         }
 
-        let text: String = display[i..j].iter().collect();
+        let text: String = display[i..j].iter().map(|c| c.text).collect();
         let syn_fg_or_default = syn_fg.unwrap_or(base_style.fg.unwrap_or(Color::Reset));
         let style = if is_cursor {
             base_style.fg(CURSOR_FG).bg(CURSOR_BG)
@@ -660,6 +1106,10 @@ fn is_in_selection(row: usize, col: usize, sel: &SelectionInfo) -> bool {
     match sel.mode {
         DiffViewMode::Normal => false,
         DiffViewMode::VisualLine => row >= sel.start.row && row <= sel.end.row,
+        DiffViewMode::VisualBlock => {
+            (sel.start.row..=sel.end.row).contains(&row)
+                && (sel.start.col..=sel.end.col).contains(&col)
+        }
         DiffViewMode::Visual => {
             if row < sel.start.row || row > sel.end.row {
                 return false;
@@ -688,8 +1138,9 @@ fn apply_selection_to_line<'a>(
     syntax_colors: Option<&[Color]>,
     search_hl: &Option<SearchHighlightInfo>,
     is_left: bool,
+    tabstop: usize,
 ) -> Line<'a> {
-    let spans = build_highlighted_spans(content, row_idx, width, scroll_x, sel, base_style, syntax_colors, search_hl, is_left);
+    let spans = build_highlighted_spans(content, row_idx, width, scroll_x, sel, base_style, syntax_colors, search_hl, is_left, tabstop);
     Line::from(spans)
 }
 
@@ -703,29 +1154,20 @@ fn apply_search_to_line<'a>(
     syntax_colors: Option<&[Color]>,
     search_hl: &Option<SearchHighlightInfo>,
     is_left: bool,
+    tabstop: usize,
 ) -> Line<'a> {
     let syn_colors = syntax_colors.unwrap_or(&[]);
-    let spans = build_syntax_spans(content, width, scroll_x, base_style, syn_colors, search_hl, row_idx, is_left);
+    let spans = build_syntax_spans(content, width, scroll_x, base_style, syn_colors, search_hl, row_idx, is_left, tabstop);
     Line::from(spans)
 }
 
-fn line_colors(line_type: LineType, is_left: bool) -> (Color, Option<Color>) {
+fn line_colors(palette: DiffPalette, line_type: LineType, is_left: bool) -> (Color, Option<Color>) {
+    let (added, deleted) = palette.colors();
     match line_type {
         LineType::Context => (Color::Reset, None),
-        LineType::Added => {
-            if is_left {
-                (Color::Reset, Some(Color::Rgb(0, 40, 0)))
-            } else {
-                (Color::Green, Some(Color::Rgb(0, 40, 0)))
-            }
-        }
-        LineType::Deleted => {
-            if is_left {
-                (Color::Red, Some(Color::Rgb(40, 0, 0)))
-            } else {
-                (Color::Green, Some(Color::Rgb(0, 40, 0)))
-            }
-        }
+        LineType::Added if is_left => (Color::Reset, added.1),
+        LineType::Deleted if is_left => deleted,
+        LineType::Added | LineType::Deleted => added,
         LineType::HunkHeader => (Color::Cyan, None),
     }
 }
@@ -738,20 +1180,200 @@ fn style_for(fg: Color, bg: Option<Color>) -> Style {
     s
 }
 
-fn scroll_content(content: &str, scroll_x: usize, width: usize) -> String {
-    let chars: Vec<char> = content.chars().collect();
-    let start = scroll_x.min(chars.len());
-    let end = (start + width).min(chars.len());
-    chars[start..end].iter().collect()
+fn scroll_content(content: &str, scroll_x: usize, width: usize, tabstop: usize) -> String {
+    let cells = layout_cells(content, tabstop);
+    let start = scroll_x.min(cells.len());
+    let end = (start + width).min(cells.len());
+    visible_cells(&cells, content.chars().count(), start, end - start)
+        .iter()
+        .map(|c| c.text)
+        .collect()
 }
 
+/// Truncate or pad `s` to exactly `width` screen columns.
 fn pad_to_width(s: &str, width: usize) -> String {
-    let char_count = s.chars().count();
-    if char_count >= width {
-        s.chars().take(width).collect()
-    } else {
-        let mut result = s.to_string();
-        result.extend(std::iter::repeat(' ').take(width - char_count));
-        result
+    let mut result = String::with_capacity(width);
+    let mut used = 0;
+    for ch in s.chars() {
+        let w = ch.width().unwrap_or(0);
+        if used + w > width {
+            break;
+        }
+        result.push(ch);
+        used += w;
+    }
+    result.extend(std::iter::repeat_n(' ', width - used));
+    result
+}
+
+/// The `U` view: every file's unified diff in one buffer, drawn over the
+/// panes between the header and the status bar.
+pub fn render_unified_view(f: &mut Frame, app: &mut App, area: Rect) {
+    let palette = app.diff_palette;
+    let tabstop = app.config.tabstop;
+    let file_count = app.diff_state.files.len();
+    let search_label = app.search_match_label(SearchOrigin::UnifiedView);
+    let search_hl = SearchHighlightInfo::from_unified(app);
+    let Some(view) = app.unified_view.as_mut() else {
+        return;
+    };
+
+    let title = format!(" All files ({file_count}) ");
+    let footer = match search_label {
+        Some(label) => format!(" {label}  n/N: next/prev  Esc: clear "),
+        None => format!(
+            " {}/{}  ]/[: file  v: select  y/Y: yank  /: search  q: close ",
+            view.cursor + 1,
+            view.lines.len()
+        ),
+    };
+    let block = Block::default()
+        .title(title)
+        .title_bottom(Line::from(footer).right_aligned())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    // One column for the cursor marker
+    view.view_height = inner.height as usize;
+    view.view_width = (inner.width as usize).saturating_sub(1);
+    view.scroll_to_cursor();
+    let selection = view.anchor.map(|_| view.selected_range());
+
+    let lines: Vec<Line> = view
+        .lines
+        .iter()
+        .enumerate()
+        .skip(view.scroll)
+        .take(view.view_height)
+        .map(|(idx, line)| {
+            let (fg, bg) = match line.kind {
+                UnifiedLineKind::Context => line_colors(palette, LineType::Context, false),
+                UnifiedLineKind::Deleted => line_colors(palette, LineType::Deleted, true),
+                UnifiedLineKind::Added => line_colors(palette, LineType::Added, false),
+                UnifiedLineKind::HunkHeader => line_colors(palette, LineType::HunkHeader, false),
+                UnifiedLineKind::FileHeader => (Color::White, None),
+                UnifiedLineKind::Note => (Color::DarkGray, None),
+            };
+            let mut base = style_for(fg, bg);
+            if line.kind == UnifiedLineKind::FileHeader {
+                base = base.add_modifier(Modifier::BOLD);
+            }
+            let marker = if idx == view.cursor {
+                Span::styled("\u{258c}", Style::default().fg(Color::Cyan))
+            } else {
+                Span::raw(" ")
+            };
+            // The prefix stays put while the text scrolls sideways
+            let (prefix, text) = line.text.split_at(line.kind.prefix_len());
+            let mut spans = vec![marker, Span::styled(prefix.to_string(), base.add_modifier(Modifier::BOLD))];
+            spans.extend(build_syntax_spans(
+                text,
+                view.view_width.saturating_sub(prefix.len()),
+                view.scroll_x,
+                base,
+                &line.colors,
+                &search_hl,
+                idx,
+                true,
+                tabstop,
+            ));
+            let selected = selection.is_some_and(|(start, end)| (start..=end).contains(&idx));
+            if selected {
+                for span in spans.iter_mut().skip(1) {
+                    span.style = span.style.bg(SELECTION_BG);
+                }
+            }
+            let line = Line::from(spans);
+            match bg {
+                Some(bg) if !selected => line.style(Style::default().bg(bg)),
+                _ => line,
+            }
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::diff::{DiffHunk, SideLine};
+    use std::path::PathBuf;
+
+    fn context_row(n: u32, content: &str) -> SideBySideRow {
+        let side = SideLine { line_no: n, content: content.to_string() };
+        SideBySideRow { left: Some(side.clone()), right: Some(side), line_type: LineType::Context }
+    }
+
+    fn file_with_rows(rows: Vec<SideBySideRow>) -> FileDiff {
+        FileDiff {
+            path: "big.txt".to_string(),
+            raw_path: PathBuf::from("big.txt"),
+            old_path: None,
+            status: FileStatus::Modified,
+            hunks: vec![DiffHunk {
+                header: "@@ -1 +1 @@".to_string(),
+                old_start: 1,
+                new_start: 1,
+                rows,
+            }],
+            is_binary: false,
+        }
+    }
+
+    #[test]
+    fn large_diff_builds_only_the_visible_window() {
+        let rows = (1..=50_000).map(|n| context_row(n, &format!("line {n}"))).collect();
+        let file = file_with_rows(rows);
+
+        let (left, right) = build_side_by_side_lines(
+            &file, 25_000..25_050, 80, 80, 0, &None, &[], &[], &None, 4, GUTTER_WIDTH,
+            DiffPalette::RedGreen,
+        );
+
+        assert_eq!(left.len(), 50);
+        assert_eq!(right.len(), 50);
+        let text = |line: &Line<'_>| line.spans.iter().map(|s| s.content.as_ref()).collect::<String>();
+        // Row 0 is the hunk header, so row N shows line N
+        assert!(text(&left[0]).contains("line 25000"), "{:?}", text(&left[0]));
+        assert!(text(&right[0]).contains("line 25000"), "{:?}", text(&right[0]));
+        assert!(text(&right[49]).contains("line 25049"), "{:?}", text(&right[49]));
+    }
+
+    /// Screen column and text of the cursor span in `line`.
+    fn cursor_cell(line: &Line<'_>) -> Option<(usize, String)> {
+        let mut col = 0;
+        for span in &line.spans {
+            if span.style.fg == Some(CURSOR_FG) && span.style.bg == Some(CURSOR_BG) {
+                return Some((col, span.content.to_string()));
+            }
+            col += span.width();
+        }
+        None
+    }
+
+    #[test]
+    fn cursor_column_maps_with_the_gutter_hidden() {
+        let row = context_row(7, "let x = 1;");
+        let cursor = CursorPos { row: 3, col: 4, side: DiffSide::Right };
+        let selection = Some(SelectionInfo {
+            start: cursor,
+            end: cursor,
+            mode: DiffViewMode::Normal,
+            cursor,
+            relative_numbers: false,
+        });
+        let render = |gutter_width| {
+            let (_, right) = render_row(
+                &row, 30, 30, 0, 3, &selection, None, None, &None, 4, None, gutter_width,
+                DiffPalette::RedGreen,
+            );
+            cursor_cell(&right)
+        };
+
+        assert_eq!(render(0), Some((4, "x".to_string())));
+        assert_eq!(render(GUTTER_WIDTH), Some((GUTTER_WIDTH + 4, "x".to_string())));
     }
 }
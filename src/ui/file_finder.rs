@@ -0,0 +1,81 @@
+use crate::app::App;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+const BG: Color = Color::Rgb(30, 30, 30);
+const SELECTED_BG: Color = Color::DarkGray;
+
+/// Fuzzy file picker: the query on top, ranked matches below with the
+/// matched characters highlighted.
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let finder = match &app.file_finder {
+        Some(finder) => finder,
+        None => return,
+    };
+
+    let width = (area.width * 7 / 10).max(40).min(area.width.saturating_sub(4));
+    let height = 20u16.min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+    let finder_area = Rect::new(x, y, width, height);
+
+    f.render_widget(Clear, finder_area);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(" > ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::styled(
+                format!("{}\u{2588}", finder.query),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    // Keep the selection in view
+    let visible = height.saturating_sub(4) as usize;
+    let first = finder.selected.saturating_sub(visible.saturating_sub(1));
+    for (row, (file_idx, positions)) in finder.matches.iter().enumerate().skip(first).take(visible) {
+        let Some(file) = app.diff_state.files.get(*file_idx) else {
+            continue;
+        };
+        let bg = if row == finder.selected { SELECTED_BG } else { BG };
+        let mut spans = vec![Span::styled(
+            format!(" {} ", file.status.icon()),
+            Style::default().fg(Color::DarkGray).bg(bg),
+        )];
+        for (idx, c) in file.path.chars().enumerate() {
+            let style = if positions.contains(&idx) {
+                Style::default().fg(Color::Yellow).bg(bg).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White).bg(bg)
+            };
+            spans.push(Span::styled(c.to_string(), style));
+        }
+        lines.push(Line::from(spans));
+    }
+    if finder.matches.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "   No matching files",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let block = Block::default()
+        .title(format!(
+            " Files {}/{} ",
+            finder.matches.len(),
+            app.diff_state.files.len()
+        ))
+        .title_bottom(Line::from(" Enter: open  Ctrl+n/p: move  Esc: cancel ").right_aligned())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan).bg(BG))
+        .style(Style::default().bg(BG));
+
+    f.render_widget(Paragraph::new(lines).block(block), finder_area);
+}
@@ -1,6 +1,7 @@
 use crate::app::{App, FocusedPane, SearchMatch, SearchOrigin, TreeEntry};
 use crate::git::diff::FileStatus;
 use std::collections::HashSet;
+use unicode_width::UnicodeWidthStr;
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -16,12 +17,16 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         Color::DarkGray
     };
 
-    let block = Block::default()
+    let mut block = Block::default()
         .title(" Files ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color));
+    if let Some(label) = app.search_match_label(SearchOrigin::FileTree) {
+        block = block.title_bottom(Line::from(format!(" {label} ")).right_aligned());
+    }
 
     let entries = app.build_tree_entries();
+    let inner_width = block.inner(area).width as usize;
 
     if entries.is_empty() {
         let items: Vec<ListItem> = vec![ListItem::new(Line::from(Span::styled(
@@ -66,6 +71,8 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                 path,
                 depth,
                 collapsed,
+                files,
+                stats,
             } => {
                 let indent = " ".repeat(depth * 2);
                 let icon = if *collapsed { "▶" } else { "▼" };
@@ -77,28 +84,54 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                 } else {
                     Style::default().fg(Color::DarkGray)
                 };
-                let line = Line::from(vec![
-                    Span::raw(format!(" {indent}  ")),
-                    Span::styled(format!("{icon} {dir_name}/"), name_style),
-                ]);
-                ListItem::new(line)
+                let prefix = format!(" {indent}  ");
+                let name = format!("{icon} {dir_name}/");
+                let mut spans = vec![Span::raw(prefix.clone()), Span::styled(name.clone(), name_style)];
+                // Right-aligned totals; the file count is dropped first when space is short
+                let used = prefix.width() + name.width();
+                let changes = format!("+{} -{}", stats.additions, stats.deletions);
+                let count = format!("{files} file{} ", if *files == 1 { "" } else { "s" });
+                let available = inner_width.saturating_sub(used + 1);
+                let count = if count.len() + changes.len() <= available { count } else { String::new() };
+                if count.len() + changes.len() <= available {
+                    let pad = inner_width - used - count.len() - changes.len();
+                    spans.push(Span::raw(" ".repeat(pad)));
+                    spans.push(Span::styled(count, Style::default().fg(Color::DarkGray)));
+                    spans.push(Span::styled(
+                        format!("+{}", stats.additions),
+                        Style::default().fg(Color::Green),
+                    ));
+                    spans.push(Span::raw(" "));
+                    spans.push(Span::styled(
+                        format!("-{}", stats.deletions),
+                        Style::default().fg(Color::Red),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
             }
             TreeEntry::File { file_idx, depth } => {
                 let file = &app.diff_state.files[*file_idx];
                 let indent = " ".repeat(depth * 2);
-                let icon_color = match file.status {
-                    FileStatus::Modified => Color::Yellow,
-                    FileStatus::Added => Color::Green,
-                    FileStatus::Deleted => Color::Red,
-                    FileStatus::Renamed => Color::Blue,
-                    FileStatus::Untracked => Color::DarkGray,
-                };
+                let icon_color = status_color(file.status);
                 // For depth > 0, show only filename; for depth 0, show full path
                 let display_name = if *depth > 0 {
                     file.path.rsplit('/').next().unwrap_or(&file.path)
                 } else {
                     &file.path
                 };
+                // Renames within the same directory only need the old file name
+                let display_name = match &file.old_path {
+                    Some(old) => {
+                        let parent = |p: &str| p.rsplit_once('/').map(|(d, _)| d.to_string());
+                        let old_name = if *depth > 0 && parent(old) == parent(&file.path) {
+                            old.rsplit('/').next().unwrap_or(old)
+                        } else {
+                            old
+                        };
+                        format!("{old_name} → {display_name}")
+                    }
+                    None => display_name.to_string(),
+                };
                 let name_style = if is_current {
                     Style::default().fg(Color::Black).bg(Color::Rgb(200, 120, 0))
                 } else if is_match {
@@ -106,17 +139,32 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                 } else {
                     Style::default()
                 };
-                let line = Line::from(vec![
+                let submodule = app.submodules.iter().find(|s| s.path == file.path);
+                let (icon, icon_color) = match submodule {
+                    Some(_) => ("◆", Color::Magenta),
+                    None => (file.status.icon(), icon_color),
+                };
+                let mut spans = vec![
                     Span::raw(format!(" {indent}")),
                     Span::styled(
-                        format!("{} ", file.status.icon()),
+                        format!("{icon} "),
                         Style::default()
                             .fg(icon_color)
                             .add_modifier(Modifier::BOLD),
                     ),
-                    Span::styled(display_name.to_string(), name_style),
-                ]);
-                ListItem::new(line)
+                    Span::styled(display_name, name_style),
+                ];
+                if let Some(sm) = submodule {
+                    let mut detail = String::new();
+                    if let (true, Some(old), Some(new)) = (sm.moved, &sm.head_id, &sm.workdir_id) {
+                        detail.push_str(&format!(" {old}→{new}"));
+                    }
+                    if sm.dirty {
+                        detail.push_str(" (dirty)");
+                    }
+                    spans.push(Span::styled(detail, Style::default().fg(Color::DarkGray)));
+                }
+                ListItem::new(Line::from(spans))
             }
         }})
         .collect();
@@ -141,3 +189,15 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
     state.select(Some(selected));
     f.render_stateful_widget(list, area, &mut state);
 }
+
+/// Color of a file's status icon, shared with the diff title.
+pub fn status_color(status: FileStatus) -> Color {
+    match status {
+        FileStatus::Modified => Color::Yellow,
+        FileStatus::Added => Color::Green,
+        FileStatus::Deleted => Color::Red,
+        FileStatus::Renamed => Color::Blue,
+        FileStatus::Untracked => Color::DarkGray,
+        FileStatus::Conflicted => Color::LightRed,
+    }
+}
@@ -1,4 +1,4 @@
-use crate::app::App;
+use crate::app::{App, SearchMatch, SearchOrigin};
 use crate::github::state::{GhDetailContent, GhDetailPane, GhFocusedPane};
 use crate::github::types::*;
 use ratatui::{
@@ -13,10 +13,13 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
     let is_focused = app.github.focused_pane == GhFocusedPane::Detail;
     let border_color = if is_focused { Color::Cyan } else { Color::DarkGray };
 
-    let block = Block::default()
+    let mut block = Block::default()
         .title(" Detail ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color));
+    if let Some(label) = app.search_match_label(SearchOrigin::GitHubDetail) {
+        block = block.title_bottom(Line::from(format!(" {label} ")).right_aligned());
+    }
 
     let inner = block.inner(area);
     f.render_widget(block, area);
@@ -86,7 +89,8 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
         GhDetailContent::Pr(detail) => build_body_lines(&detail.body),
         _ => unreachable!(),
     };
-    render_pane(
+    let body_lines = highlight_lines(body_lines, &pane_matches(app, GhDetailPane::Body));
+    let body_height = render_pane(
         f,
         cols[0],
         "Body",
@@ -95,6 +99,9 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
         is_focused,
         app.github.detail_scroll_body,
     );
+    if active_pane == GhDetailPane::Body {
+        app.github.detail_max_scroll = body_height.saturating_sub(cols[0].height);
+    }
 
     // Right side
     match &app.github.detail {
@@ -102,13 +109,13 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
             // Issue: single Comments pane on the right
             let count = detail.comments.len();
             let title = format!("Comments ({count})");
-            if active_pane == GhDetailPane::Body {
-                app.github.detail_view_height = cols[0].height;
-            } else {
-                app.github.detail_view_height = cols[1].height;
-            }
-            let (comments_lines, sel_scroll) = build_comments_lines(&detail.comments, app.github.detail_comment_idx);
-            render_pane(
+            let active_area = if active_pane == GhDetailPane::Body { cols[0] } else { cols[1] };
+            app.github.detail_view_height = active_area.height;
+            app.github.detail_text_width = active_area.width.saturating_sub(2);
+            let (comments_lines, offsets) = build_comments_lines(&detail.comments, app.github.detail_comment_idx);
+            let comments_lines = highlight_lines(comments_lines, &pane_matches(app, GhDetailPane::Comments));
+            let sel_scroll = item_scroll(&comments_lines, &offsets, app.github.detail_comment_idx, cols[1]);
+            let comments_height = render_pane(
                 f,
                 cols[1],
                 &title,
@@ -117,6 +124,11 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
                 is_focused,
                 sel_scroll + app.github.detail_scroll_comments,
             );
+            if active_pane == GhDetailPane::Comments {
+                app.github.detail_max_scroll = comments_height
+                    .saturating_sub(cols[1].height)
+                    .saturating_sub(sel_scroll);
+            }
         }
         GhDetailContent::Pr(detail) => {
             // PR: split right into Checks / Reviews / Comments
@@ -127,12 +139,14 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
             ])
             .split(cols[1]);
 
-            app.github.detail_view_height = match active_pane {
-                GhDetailPane::Body => cols[0].height,
-                GhDetailPane::Status => right_rows[0].height,
-                GhDetailPane::Reviews => right_rows[1].height,
-                GhDetailPane::Comments => right_rows[2].height,
+            let active_area = match active_pane {
+                GhDetailPane::Body => cols[0],
+                GhDetailPane::Status => right_rows[0],
+                GhDetailPane::Reviews => right_rows[1],
+                GhDetailPane::Comments => right_rows[2],
             };
+            app.github.detail_view_height = active_area.height;
+            app.github.detail_text_width = active_area.width.saturating_sub(2);
 
             let checks_count = detail
                 .status_check_rollup
@@ -155,8 +169,10 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
                 .filter(|r| !r.body.is_empty() || r.state != "COMMENTED")
                 .count();
             let reviews_title = format!("Reviews ({review_count})");
-            let (reviews_lines, rev_scroll) = build_reviews_lines(&detail.reviews, app.github.detail_review_idx);
-            render_pane(
+            let (reviews_lines, offsets) = build_reviews_lines(&detail.reviews, app.github.detail_review_idx);
+            let reviews_lines = highlight_lines(reviews_lines, &pane_matches(app, GhDetailPane::Reviews));
+            let rev_scroll = item_scroll(&reviews_lines, &offsets, app.github.detail_review_idx, right_rows[1]);
+            let reviews_height = render_pane(
                 f,
                 right_rows[1],
                 &reviews_title,
@@ -168,8 +184,10 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
 
             let comments_count = detail.comments.len();
             let comments_title = format!("Comments ({comments_count})");
-            let (comments_lines, cmt_scroll) = build_comments_lines(&detail.comments, app.github.detail_comment_idx);
-            render_pane(
+            let (comments_lines, offsets) = build_comments_lines(&detail.comments, app.github.detail_comment_idx);
+            let comments_lines = highlight_lines(comments_lines, &pane_matches(app, GhDetailPane::Comments));
+            let cmt_scroll = item_scroll(&comments_lines, &offsets, app.github.detail_comment_idx, right_rows[2]);
+            let comments_height = render_pane(
                 f,
                 right_rows[2],
                 &comments_title,
@@ -178,6 +196,17 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
                 is_focused,
                 cmt_scroll + app.github.detail_scroll_comments,
             );
+
+            app.github.detail_max_scroll = match active_pane {
+                GhDetailPane::Body => app.github.detail_max_scroll,
+                GhDetailPane::Status => 0,
+                GhDetailPane::Reviews => reviews_height
+                    .saturating_sub(right_rows[1].height)
+                    .saturating_sub(rev_scroll),
+                GhDetailPane::Comments => comments_height
+                    .saturating_sub(right_rows[2].height)
+                    .saturating_sub(cmt_scroll),
+            };
         }
         _ => unreachable!(),
     }
@@ -191,7 +220,7 @@ fn render_pane(
     is_active: bool,
     is_detail_focused: bool,
     scroll: u16,
-) {
+) -> u16 {
     let block = Block::default()
         .title(pane_title(title, is_active, is_detail_focused))
         .borders(Borders::ALL)
@@ -200,7 +229,24 @@ fn render_pane(
         .block(block)
         .wrap(Wrap { trim: false })
         .scroll((scroll, 0));
+    // Rendered height including borders, so callers can bound scrolling
+    let height = para.line_count(area.width.saturating_sub(2)).min(u16::MAX as usize) as u16;
     f.render_widget(para, area);
+    height
+}
+
+/// Rows `lines` take once wrapped to `width`, the way `render_pane` wraps them.
+pub fn wrapped_height(lines: &[Line<'_>], width: u16) -> usize {
+    if lines.is_empty() {
+        return 0;
+    }
+    Paragraph::new(lines.to_vec()).wrap(Wrap { trim: false }).line_count(width)
+}
+
+/// Scroll that puts the header of item `idx` at the top of a pane in `area`.
+fn item_scroll(lines: &[Line<'_>], offsets: &[u16], idx: usize, area: Rect) -> u16 {
+    let start = offsets.get(idx).map_or(0, |&o| (o as usize).min(lines.len()));
+    wrapped_height(&lines[..start], area.width.saturating_sub(2)).min(u16::MAX as usize) as u16
 }
 
 fn pane_title(label: &str, is_active: bool, is_detail_focused: bool) -> Line<'static> {
@@ -224,11 +270,19 @@ fn pane_border_style(is_active: bool, is_detail_focused: bool) -> Style {
 
 // --- Helpers ---
 
-fn format_date(iso: &str) -> &str {
-    if iso.len() >= 10 {
-        &iso[..10]
-    } else {
-        iso
+/// `2024-01-15T10:30:00Z` → `2024-01-15 10:30 UTC`, keeping a numeric
+/// offset (`+09:00`) as given.
+fn format_date(iso: &str) -> String {
+    let Some((date, rest)) = iso.split_once('T') else {
+        return iso.get(..10).unwrap_or(iso).to_string();
+    };
+    let Some(time) = rest.get(..5) else {
+        return date.to_string();
+    };
+    match rest.find(['Z', '+', '-']).map(|i| &rest[i..]) {
+        Some("Z") => format!("{date} {time} UTC"),
+        Some(offset) => format!("{date} {time} {offset}"),
+        None => format!("{date} {time}"),
     }
 }
 
@@ -289,7 +343,7 @@ fn build_issue_header(detail: &GhIssueDetail) -> Vec<Line<'static>> {
     let mut spans = vec![Span::raw(" ")];
     spans.push(badge(author, Color::Rgb(31, 111, 139)));
     spans.push(Span::raw(" "));
-    spans.push(badge(format_date(&detail.created_at), Color::Rgb(68, 71, 78)));
+    spans.push(badge(&format_date(&detail.created_at), Color::Rgb(68, 71, 78)));
     spans.push(Span::raw(" "));
     spans.push(state_badge(&detail.state));
     for s in build_label_spans(&detail.labels) {
@@ -320,7 +374,7 @@ fn build_pr_header(detail: &GhPrDetail) -> Vec<Line<'static>> {
     let mut spans = vec![Span::raw(" ")];
     spans.push(badge(author, Color::Rgb(31, 111, 139)));
     spans.push(Span::raw(" "));
-    spans.push(badge(format_date(&detail.created_at), Color::Rgb(68, 71, 78)));
+    spans.push(badge(&format_date(&detail.created_at), Color::Rgb(68, 71, 78)));
     spans.push(Span::raw(" "));
     spans.push(state_badge(&detail.state));
     spans.push(Span::raw(" "));
@@ -347,6 +401,11 @@ fn build_pr_header(detail: &GhPrDetail) -> Vec<Line<'static>> {
         }
     }
 
+    if let Some((label, color)) = merge_badge(detail) {
+        spans.push(Span::raw(" "));
+        spans.push(badge(label, color));
+    }
+
     for s in build_label_spans(&detail.labels) {
         spans.push(s);
     }
@@ -354,6 +413,29 @@ fn build_pr_header(detail: &GhPrDetail) -> Vec<Line<'static>> {
     vec![title_line, Line::from(spans)]
 }
 
+/// Whether an open PR can be merged right now. `UNKNOWN` means GitHub is
+/// still computing it, shown as pending until the next refresh.
+fn merge_badge(detail: &GhPrDetail) -> Option<(&'static str, Color)> {
+    if detail.state != "OPEN" {
+        return None;
+    }
+    let pending = ("… CHECKING MERGE", Color::Rgb(110, 119, 129));
+    match detail.mergeable.as_deref() {
+        Some("CONFLICTING") => return Some(("✗ CONFLICTING", Color::Rgb(218, 54, 51))),
+        Some("UNKNOWN") => return Some(pending),
+        _ => {}
+    }
+    match detail.merge_state_status.as_deref()? {
+        "CLEAN" | "HAS_HOOKS" => Some(("✓ MERGEABLE", Color::Rgb(35, 134, 54))),
+        "UNSTABLE" => Some(("✓ MERGEABLE (checks failing)", Color::Rgb(187, 128, 9))),
+        "BLOCKED" => Some(("⊘ BLOCKED", Color::Rgb(218, 54, 51))),
+        "BEHIND" => Some(("↓ BEHIND BASE", Color::Rgb(187, 128, 9))),
+        "DIRTY" => Some(("✗ CONFLICTING", Color::Rgb(218, 54, 51))),
+        "UNKNOWN" => Some(pending),
+        _ => None,
+    }
+}
+
 fn badge(text: &str, bg: Color) -> Span<'static> {
     let fg = badge_fg(bg);
     Span::styled(
@@ -542,10 +624,14 @@ fn parse_check_name(name: &str) -> (&str, &str) {
     }
 }
 
+/// Conclusions of a check that finished without passing.
+const FAILED_CONCLUSIONS: [&str; 5] =
+    ["FAILURE", "TIMED_OUT", "CANCELLED", "ACTION_REQUIRED", "STARTUP_FAILURE"];
+
 fn check_icon(check: &GhStatusCheck) -> (&'static str, Color) {
     match check.conclusion.as_deref() {
         Some("SUCCESS") => ("✓", Color::Green),
-        Some("FAILURE") => ("✗", Color::Red),
+        Some(c) if FAILED_CONCLUSIONS.contains(&c) => ("✗", Color::Red),
         Some("NEUTRAL") | Some("SKIPPED") => ("○", Color::DarkGray),
         _ => match check.status.as_str() {
             "IN_PROGRESS" => ("◐", Color::Yellow),
@@ -555,6 +641,16 @@ fn check_icon(check: &GhStatusCheck) -> (&'static str, Color) {
     }
 }
 
+/// Whether a check failed or is still running — the ones `]` / `[` jump
+/// between in the Status pane.
+pub fn check_needs_attention(check: &GhStatusCheck) -> bool {
+    match check.conclusion.as_deref() {
+        Some(c) if FAILED_CONCLUSIONS.contains(&c) => true,
+        Some("SUCCESS" | "NEUTRAL" | "SKIPPED") => false,
+        _ => check.status == "IN_PROGRESS",
+    }
+}
+
 fn review_icon(review: &GhReview) -> (&'static str, Color) {
     match review.state.as_str() {
         "APPROVED" => ("✓", Color::Green),
@@ -573,8 +669,8 @@ pub fn meaningful_reviews(reviews: &[GhReview]) -> Vec<&GhReview> {
         .collect()
 }
 
-/// Returns (lines, selected_header_line_offset).
-fn build_reviews_lines(reviews: &[GhReview], selected_idx: usize) -> (Vec<Line<'static>>, u16) {
+/// Returns (lines, header line offset of each review).
+fn build_reviews_lines(reviews: &[GhReview], selected_idx: usize) -> (Vec<Line<'static>>, Vec<u16>) {
     let meaningful = meaningful_reviews(reviews);
     if meaningful.is_empty() {
         return (
@@ -582,20 +678,18 @@ fn build_reviews_lines(reviews: &[GhReview], selected_idx: usize) -> (Vec<Line<'
                 "  (no reviews)",
                 Style::default().fg(Color::DarkGray),
             ))],
-            0,
+            Vec::new(),
         );
     }
     let sel_bg = Style::default().bg(Color::DarkGray);
     let mut lines = Vec::new();
-    let mut sel_offset: u16 = 0;
+    let mut offsets = Vec::new();
     for (i, review) in meaningful.iter().enumerate() {
         if i > 0 {
             lines.push(Line::from(""));
         }
         let is_sel = i == selected_idx;
-        if is_sel {
-            sel_offset = lines.len() as u16;
-        }
+        offsets.push(lines.len() as u16);
         let (icon, color) = review_icon(review);
         let author = review
             .author
@@ -618,31 +712,29 @@ fn build_reviews_lines(reviews: &[GhReview], selected_idx: usize) -> (Vec<Line<'
             lines.extend(markdown_to_lines(&review.body, "    "));
         }
     }
-    (lines, sel_offset)
+    (lines, offsets)
 }
 
-/// Returns (lines, selected_header_line_offset).
-fn build_comments_lines(comments: &[GhComment], selected_idx: usize) -> (Vec<Line<'static>>, u16) {
+/// Returns (lines, header line offset of each comment).
+fn build_comments_lines(comments: &[GhComment], selected_idx: usize) -> (Vec<Line<'static>>, Vec<u16>) {
     if comments.is_empty() {
         return (
             vec![Line::from(Span::styled(
                 "  (no comments)",
                 Style::default().fg(Color::DarkGray),
             ))],
-            0,
+            Vec::new(),
         );
     }
     let sel_bg = Style::default().bg(Color::DarkGray);
     let mut lines = Vec::new();
-    let mut sel_offset: u16 = 0;
+    let mut offsets = Vec::new();
     for (i, comment) in comments.iter().enumerate() {
         if i > 0 {
             lines.push(Line::from(""));
         }
         let is_sel = i == selected_idx;
-        if is_sel {
-            sel_offset = lines.len() as u16;
-        }
+        offsets.push(lines.len() as u16);
         let author = comment
             .author
             .as_ref()
@@ -659,7 +751,97 @@ fn build_comments_lines(comments: &[GhComment], selected_idx: usize) -> (Vec<Lin
         lines.push(header);
         lines.extend(markdown_to_lines(&comment.body, "    "));
     }
-    (lines, sel_offset)
+    (lines, offsets)
+}
+
+/// Plain text of each line `pane` renders, plus the first line of each
+/// comment / review / check (empty for the body). Used by detail search.
+pub fn pane_text(detail: &GhDetailContent, pane: GhDetailPane) -> (Vec<String>, Vec<u16>) {
+    let (lines, offsets) = match (detail, pane) {
+        (GhDetailContent::Issue(d), GhDetailPane::Body) => (build_body_lines(&d.body), Vec::new()),
+        (GhDetailContent::Pr(d), GhDetailPane::Body) => (build_body_lines(&d.body), Vec::new()),
+        (GhDetailContent::Issue(d), GhDetailPane::Comments) => build_comments_lines(&d.comments, usize::MAX),
+        (GhDetailContent::Pr(d), GhDetailPane::Comments) => build_comments_lines(&d.comments, usize::MAX),
+        (GhDetailContent::Pr(d), GhDetailPane::Reviews) => build_reviews_lines(&d.reviews, usize::MAX),
+        (GhDetailContent::Pr(d), GhDetailPane::Status) => {
+            // One table row per check
+            let text: Vec<String> = sorted_checks(d)
+                .iter()
+                .map(|c| format!("{} {}", c.workflow_name.as_deref().unwrap_or(""), c.name))
+                .collect();
+            let offsets = (0..text.len() as u16).collect();
+            return (text, offsets);
+        }
+        _ => return (Vec::new(), Vec::new()),
+    };
+    let text = lines
+        .iter()
+        .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+        .collect();
+    (text, offsets)
+}
+
+/// Search matches in `pane` as (line, col_start, col_end, is_current).
+fn pane_matches(app: &App, pane: GhDetailPane) -> Vec<(usize, usize, usize, bool)> {
+    if app.search.origin != SearchOrigin::GitHubDetail || app.search.query.is_none() {
+        return Vec::new();
+    }
+    app.search
+        .matches
+        .iter()
+        .enumerate()
+        .filter_map(|(i, m)| match m {
+            SearchMatch::DetailLine { pane: p, line, col_start, col_end } if *p == pane => {
+                Some((*line, *col_start, *col_end, app.search.current_match_idx == Some(i)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn highlight_lines(
+    mut lines: Vec<Line<'static>>,
+    matches: &[(usize, usize, usize, bool)],
+) -> Vec<Line<'static>> {
+    for &(idx, start, end, current) in matches {
+        if let Some(line) = lines.get_mut(idx) {
+            *line = highlight_range(std::mem::take(line), start, end, current);
+        }
+    }
+    lines
+}
+
+/// Restyle bytes `start..end` of the line's text, splitting spans as needed.
+fn highlight_range(line: Line<'static>, start: usize, end: usize, current: bool) -> Line<'static> {
+    let hl = if current {
+        Style::default().fg(Color::Black).bg(Color::Rgb(200, 120, 0))
+    } else {
+        Style::default().bg(Color::Rgb(60, 60, 0))
+    };
+    let mut spans = Vec::with_capacity(line.spans.len() + 2);
+    let mut pos = 0;
+    for span in line.spans {
+        let text = span.content.as_ref();
+        let s = start.saturating_sub(pos).min(text.len());
+        let e = end.saturating_sub(pos).min(text.len());
+        pos += text.len();
+        if s >= e || !text.is_char_boundary(s) || !text.is_char_boundary(e) {
+            spans.push(span);
+            continue;
+        }
+        for (piece, style) in [
+            (&text[..s], span.style),
+            (&text[s..e], span.style.patch(hl)),
+            (&text[e..], span.style),
+        ] {
+            if !piece.is_empty() {
+                spans.push(Span::styled(piece.to_string(), style));
+            }
+        }
+    }
+    let mut out = Line::from(spans).style(line.style);
+    out.alignment = line.alignment;
+    out
 }
 
 fn markdown_to_lines(text: &str, padding: &str) -> Vec<Line<'static>> {
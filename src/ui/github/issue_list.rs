@@ -1,3 +1,4 @@
+use super::{ellipsize_middle, pin_marker};
 use crate::app::App;
 use crate::github::state::GhFocusedPane;
 use ratatui::{
@@ -37,6 +38,7 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         return;
     }
 
+    let inner_width = area.width.saturating_sub(2) as usize;
     let items: Vec<ListItem> = app
         .github
         .issues
@@ -49,8 +51,8 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                 Color::Red
             };
 
-            ListItem::new(Line::from(vec![
-                Span::raw(" "),
+            let mut line = Line::from(vec![
+                pin_marker(app.github.pinned_issues.contains(&issue.number)),
                 Span::styled(icon, Style::default().fg(icon_color)),
                 Span::raw(" "),
                 Span::styled(
@@ -58,8 +60,10 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                     Style::default().fg(Color::Yellow),
                 ),
                 Span::raw(" "),
-                Span::raw(&issue.title),
-            ]))
+            ]);
+            let title_width = inner_width.saturating_sub(line.width());
+            line.push_span(Span::raw(ellipsize_middle(&issue.title, title_width)));
+            ListItem::new(line)
         })
         .collect();
 
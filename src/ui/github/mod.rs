@@ -2,3 +2,52 @@ pub mod detail_view;
 pub mod issue_list;
 pub mod layout;
 pub mod pr_list;
+pub mod raw_json;
+
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Leading cell of a list row: a bar for items pinned with `p`.
+pub fn pin_marker(pinned: bool) -> Span<'static> {
+    if pinned {
+        Span::styled("▌", Style::default().fg(Color::LightBlue))
+    } else {
+        Span::raw(" ")
+    }
+}
+
+/// Shorten `text` to at most `max_width` columns by replacing its middle
+/// with `…`, so both the start and the end stay readable.
+pub fn ellipsize_middle(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+    let budget = max_width - 1;
+    let take = |chars: &mut dyn Iterator<Item = char>, limit: usize| {
+        let mut used = 0;
+        let mut out = Vec::new();
+        for c in chars {
+            let w = c.width().unwrap_or(0);
+            if used + w > limit {
+                break;
+            }
+            used += w;
+            out.push(c);
+        }
+        out
+    };
+    let head = take(&mut text.chars(), budget.div_ceil(2));
+    let mut tail = take(&mut text.chars().rev(), budget / 2);
+    tail.reverse();
+    format!(
+        "{}…{}",
+        head.into_iter().collect::<String>(),
+        tail.into_iter().collect::<String>()
+    )
+}
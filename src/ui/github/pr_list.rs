@@ -1,3 +1,4 @@
+use super::{ellipsize_middle, pin_marker};
 use crate::app::App;
 use crate::github::state::GhFocusedPane;
 use ratatui::{
@@ -37,6 +38,7 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         return;
     }
 
+    let inner_width = area.width.saturating_sub(2) as usize;
     let items: Vec<ListItem> = app
         .github
         .prs
@@ -48,8 +50,8 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                 _ => ("●", Color::Green), // OPEN
             };
 
-            let mut spans = vec![
-                Span::raw(" "),
+            let prefix = vec![
+                pin_marker(app.github.pinned_prs.contains(&pr.number)),
                 Span::styled(icon, Style::default().fg(icon_color)),
                 Span::raw(" "),
                 Span::styled(
@@ -57,19 +59,19 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                     Style::default().fg(Color::Yellow),
                 ),
                 Span::raw(" "),
-                Span::raw(&pr.title),
             ];
+            let mut badges = Vec::new();
 
             // Review badge
             if let Some(ref decision) = pr.review_decision {
                 match decision.as_str() {
                     "APPROVED" => {
-                        spans.push(Span::raw(" "));
-                        spans.push(Span::styled("✓", Style::default().fg(Color::Green)));
+                        badges.push(Span::raw(" "));
+                        badges.push(Span::styled("✓", Style::default().fg(Color::Green)));
                     }
                     "CHANGES_REQUESTED" => {
-                        spans.push(Span::raw(" "));
-                        spans.push(Span::styled("✗", Style::default().fg(Color::Red)));
+                        badges.push(Span::raw(" "));
+                        badges.push(Span::styled("✗", Style::default().fg(Color::Red)));
                     }
                     _ => {}
                 }
@@ -77,13 +79,20 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
 
             // Draft badge
             if pr.is_draft {
-                spans.push(Span::raw(" "));
-                spans.push(Span::styled(
+                badges.push(Span::raw(" "));
+                badges.push(Span::styled(
                     "[draft]",
                     Style::default().fg(Color::DarkGray),
                 ));
             }
 
+            // Shorten only the title so the number and badges stay visible
+            let fixed: usize = prefix.iter().chain(&badges).map(Span::width).sum();
+            let title = ellipsize_middle(&pr.title, inner_width.saturating_sub(fixed));
+            let mut spans = prefix;
+            spans.push(Span::raw(title));
+            spans.extend(badges);
+
             ListItem::new(Line::from(spans))
         })
         .collect();
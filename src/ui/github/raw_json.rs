@@ -0,0 +1,42 @@
+use crate::app::App;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// The JSON `gh` returned for the shown issue / PR (`--debug`), scrolled with
+/// `j` / `k`.
+pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
+    let Some(view) = app.raw_json_view.as_mut() else {
+        return;
+    };
+
+    let width = (area.width * 4 / 5).max(40).min(area.width);
+    let height = (area.height * 4 / 5).max(6).min(area.height);
+    let x = area.x + (area.width - width) / 2;
+    let y = area.y + (area.height - height) / 2;
+    let popup = Rect::new(x, y, width, height);
+
+    let lines: Vec<Line> = view.text.lines().map(Line::raw).collect();
+    let visible = height.saturating_sub(2);
+    view.view_height = visible;
+    view.max_scroll = (lines.len() as u16).saturating_sub(visible);
+    view.scroll = view.scroll.min(view.max_scroll);
+
+    let footer = format!(
+        " {}/{}  j/k: scroll  y: copy  q: close ",
+        (view.scroll + visible).min(lines.len() as u16),
+        lines.len()
+    );
+    let block = Block::default()
+        .title(view.title.as_str())
+        .title_bottom(Line::from(footer).right_aligned())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta));
+
+    f.render_widget(Clear, popup);
+    f.render_widget(Paragraph::new(lines).block(block).scroll((view.scroll, 0)), popup);
+}
@@ -0,0 +1,180 @@
+use base64::Engine;
+use ratatui::layout::Rect;
+use std::io::{Cursor, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+/// Terminal graphics protocol used for inline image previews.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// kitty graphics protocol (kitty, Ghostty)
+    Kitty,
+    /// iTerm2 inline images (iTerm2, WezTerm)
+    ITerm2,
+}
+
+impl GraphicsProtocol {
+    /// Guess support from the environment. Multiplexers would need escape
+    /// passthrough, so previews are off inside tmux and screen.
+    pub fn detect() -> Option<Self> {
+        if std::env::var_os("TMUX").is_some() || std::env::var_os("STY").is_some() {
+            return None;
+        }
+        let term = std::env::var("TERM").unwrap_or_default();
+        let program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+        if std::env::var_os("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || term.contains("ghostty")
+            || program == "ghostty"
+        {
+            Some(GraphicsProtocol::Kitty)
+        } else if program == "iTerm.app" || program == "WezTerm" {
+            Some(GraphicsProtocol::ITerm2)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether `path` looks like an image the preview can decode.
+pub fn is_previewable(path: &Path) -> bool {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    matches!(ext.as_deref(), Some("png" | "jpg" | "jpeg"))
+}
+
+/// Old and new image of the previewed file and where to draw them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+    /// Identifies the file and diff base the bytes were loaded for.
+    pub key: String,
+    pub left: Option<(Rect, Rc<[u8]>)>,
+    pub right: Option<(Rect, Rc<[u8]>)>,
+}
+
+/// Inline image state. The diff view requests a placement while drawing;
+/// images are written to the terminal after the frame, since ratatui's
+/// buffer can only hold text.
+pub struct ImagePreview {
+    pub protocol: Option<GraphicsProtocol>,
+    pub requested: Option<Placement>,
+    shown: Option<Placement>,
+    /// Bumped when the diff is reloaded so cached bytes aren't reused.
+    pub generation: u64,
+}
+
+impl ImagePreview {
+    pub fn new(protocol: Option<GraphicsProtocol>) -> Self {
+        Self {
+            protocol,
+            requested: None,
+            shown: None,
+            generation: 0,
+        }
+    }
+
+    /// Bytes of the placement already requested or shown for `key`, so the
+    /// diff view doesn't reload blobs every frame.
+    pub fn cached(&self, key: &str) -> Option<&Placement> {
+        [&self.requested, &self.shown]
+            .into_iter()
+            .flatten()
+            .find(|p| p.key == key)
+    }
+
+    /// Bring the terminal in line with this frame's request. Returns true
+    /// when a previous image was removed and the screen must be fully
+    /// redrawn to clear what it covered.
+    pub fn flush(&mut self, out: &mut impl Write) -> std::io::Result<bool> {
+        let Some(protocol) = self.protocol else {
+            return Ok(false);
+        };
+        let requested = self.requested.take();
+        if requested == self.shown {
+            return Ok(false);
+        }
+        if self.shown.take().is_some() {
+            if protocol == GraphicsProtocol::Kitty {
+                write!(out, "\x1b_Ga=d,d=A,q=2\x1b\\")?;
+                out.flush()?;
+            }
+            return Ok(true);
+        }
+        if let Some(placement) = &requested {
+            for (area, bytes) in [&placement.left, &placement.right].into_iter().flatten() {
+                write_image(out, protocol, *area, bytes)?;
+            }
+            out.flush()?;
+        }
+        self.shown = requested;
+        Ok(false)
+    }
+}
+
+/// Draw `bytes` at the top-left of `area`, scaled to fit while keeping the
+/// aspect ratio (cells are assumed to be twice as tall as wide).
+fn write_image(
+    out: &mut impl Write,
+    protocol: GraphicsProtocol,
+    area: Rect,
+    bytes: &[u8],
+) -> std::io::Result<()> {
+    let Ok(reader) = image::ImageReader::new(Cursor::new(bytes)).with_guessed_format() else {
+        return Ok(());
+    };
+    let format = reader.format();
+    let Ok((width, height)) = reader.into_dimensions() else {
+        return Ok(());
+    };
+    if width == 0 || height == 0 || area.width == 0 || area.height == 0 {
+        return Ok(());
+    }
+    // Fit in the area without upscaling past roughly 8x16 pixel cells
+    let max_cols = (area.width as f64).min(width as f64 / 8.0).max(1.0);
+    let max_rows = (area.height as f64).min(height as f64 / 16.0).max(1.0);
+    let scale = (max_cols / width as f64).min(max_rows * 2.0 / height as f64);
+    let cols = ((width as f64 * scale).round() as u16).clamp(1, area.width);
+    let rows = ((height as f64 * scale / 2.0).round() as u16).clamp(1, area.height);
+
+    let engine = base64::engine::general_purpose::STANDARD;
+    write!(out, "\x1b[{};{}H", area.y + 1, area.x + 1)?;
+    match protocol {
+        GraphicsProtocol::Kitty => {
+            // kitty only takes PNG directly; convert anything else
+            let png = if format == Some(image::ImageFormat::Png) {
+                bytes.to_vec()
+            } else {
+                let Ok(img) = image::load_from_memory(bytes) else {
+                    return Ok(());
+                };
+                let mut png = Vec::new();
+                if img.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png).is_err() {
+                    return Ok(());
+                }
+                png
+            };
+            let data = engine.encode(png);
+            let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+            for (i, chunk) in chunks.iter().enumerate() {
+                let more = u8::from(i + 1 < chunks.len());
+                let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+                if i == 0 {
+                    write!(out, "\x1b_Gf=100,a=T,q=2,c={cols},r={rows},m={more};{chunk}\x1b\\")?;
+                } else {
+                    write!(out, "\x1b_Gm={more};{chunk}\x1b\\")?;
+                }
+            }
+        }
+        GraphicsProtocol::ITerm2 => {
+            write!(
+                out,
+                "\x1b]1337;File=inline=1;size={};width={cols};height={rows};preserveAspectRatio=1:{}\x07",
+                bytes.len(),
+                engine.encode(bytes)
+            )?;
+        }
+    }
+    Ok(())
+}
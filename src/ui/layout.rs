@@ -6,6 +6,8 @@ pub struct AppLayout {
     pub branch_list: Rect,
     pub reflog: Rect,
     pub main_pane: Rect,
+    /// Everything between the header and the status bar
+    pub body: Rect,
     pub status_bar: Rect,
 }
 
@@ -35,6 +37,7 @@ pub fn compute_layout(area: Rect) -> AppLayout {
         branch_list: top_row[1],
         reflog: top_row[2],
         main_pane: vertical[2],
+        body: vertical[1].union(vertical[2]),
         status_bar: vertical[3],
     }
 }
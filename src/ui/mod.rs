@@ -1,10 +1,18 @@
+pub mod base_picker;
+pub mod blame_popup;
 pub mod branch_action_menu;
 pub mod branch_selector;
+pub mod cells;
+pub mod color;
 pub mod confirm_dialog;
+pub mod file_finder;
 pub mod commit_log;
 pub mod diff_view;
 pub mod file_tree;
 pub mod github;
+pub mod image_preview;
 pub mod layout;
+pub mod ref_picker;
 pub mod reflog;
+pub mod reset_menu;
 pub mod status_bar;
@@ -0,0 +1,61 @@
+use crate::app::App;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+const BG: Color = Color::Rgb(30, 30, 30);
+
+/// Two-field prompt for the old and new ref to compare.
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let picker = match &app.ref_picker {
+        Some(p) => p,
+        None => return,
+    };
+
+    let width = 54u16.min(area.width.saturating_sub(4));
+    let height = 8u16.min(area.height.saturating_sub(2));
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+    let picker_area = Rect::new(x, y, width, height);
+
+    f.render_widget(Clear, picker_area);
+
+    let mut lines = vec![Line::from("")];
+    for (idx, label) in ["Old", "New"].iter().enumerate() {
+        let active = idx == picker.active;
+        let cursor = if active { "\u{2588}" } else { "" };
+        let label_style = if active {
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {label}: "), label_style),
+            Span::styled(
+                format!("{}{cursor}", picker.fields[idx]),
+                Style::default().fg(Color::White),
+            ),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(match &picker.error {
+        Some(error) => Line::from(Span::styled(format!(" {error}"), Style::default().fg(Color::Red))),
+        None => Line::from(""),
+    });
+    lines.push(Line::from(Span::styled(
+        " Tab: switch  Enter: compare (both empty: working tree)  Esc: cancel",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let block = Block::default()
+        .title(" Compare refs ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan).bg(BG))
+        .style(Style::default().bg(BG));
+
+    f.render_widget(Paragraph::new(lines).block(block), picker_area);
+}
@@ -16,10 +16,13 @@ pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
         Color::DarkGray
     };
 
-    let block = Block::default()
+    let mut block = Block::default()
         .title(" Reflog ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color));
+    if let Some(label) = app.search_match_label(SearchOrigin::Reflog) {
+        block = block.title_bottom(Line::from(format!(" {label} ")).right_aligned());
+    }
 
     if app.reflog.entries.is_empty() {
         let items: Vec<ListItem> = vec![ListItem::new(Line::from(Span::styled(
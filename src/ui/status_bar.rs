@@ -1,4 +1,5 @@
 use crate::app::{App, ViewMode};
+use crate::git::diff::{DiffScope, FileStatus};
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -47,17 +48,70 @@ pub fn render_header(f: &mut Frame, app: &App, area: Rect) {
             Style::default().fg(Color::Black).bg(Color::Magenta),
         ),
     ];
+    if let Some((ahead, behind)) = app.ahead_behind {
+        spans.push(Span::styled(
+            format!(" ↑{ahead}↓{behind}"),
+            Style::default().fg(Color::Cyan),
+        ));
+    }
+    // Only the default working-tree diff says whether there's anything to
+    // commit; a base or compared refs show changes that may be committed
+    if app.compare_refs.is_none() && app.diff_base_ref.is_none() && !app.diff_state.files.is_empty() {
+        spans.push(Span::styled(" ●dirty", Style::default().fg(Color::Yellow)));
+    }
 
     {
-        let base_label = match &app.diff_base_ref {
-            Some(base) => format!(" vs {base} "),
-            None => " vs HEAD ".to_string(),
+        let base = match (&app.diff_base_label, &app.diff_base_ref) {
+            (Some(label), Some(_)) => label.as_str(),
+            (None, Some(base)) => base.as_str(),
+            _ => "HEAD",
+        };
+        // Unstaged changes are relative to the index, whatever the base
+        let base_label = match (&app.compare_refs, app.diff_scope) {
+            (Some((old, new)), _) => format!(" {old}..{new} "),
+            (None, DiffScope::All) => format!(" vs {base} "),
+            (None, DiffScope::Unstaged) => " unstaged (vs index) ".to_string(),
+            (None, DiffScope::Staged) => format!(" staged (vs {base}) "),
         };
         spans.push(Span::raw(" "));
         spans.push(Span::styled(
             base_label,
             Style::default().fg(Color::Black).bg(Color::Yellow),
         ));
+        if app.repo.ignores_whitespace() {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                " -w ",
+                Style::default().fg(Color::Black).bg(Color::Gray),
+            ));
+        }
+    }
+
+    let conflicted = app
+        .diff_state
+        .files
+        .iter()
+        .filter(|f| f.status == FileStatus::Conflicted)
+        .count();
+    if let Some(operation) = app.repo.state().label() {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            format!(" {operation} "),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    if conflicted > 0 {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            format!(" {conflicted} conflicted "),
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        ));
     }
 
     spans.extend(view_tab_spans(app.view_mode));
@@ -90,6 +144,14 @@ pub fn render_gh_header(f: &mut Frame, app: &App, area: Rect) {
         ),
     ];
 
+    if let Some(name) = &app.github.repo_name {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            format!(" {name} "),
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        ));
+    }
+
     spans.extend(view_tab_spans(app.view_mode));
 
     spans.push(Span::raw("  "));
@@ -112,6 +174,14 @@ pub fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
         f.render_widget(Paragraph::new(line), area);
         return;
     }
+    if let Some(ref input) = app.command_line {
+        let line = Line::from(Span::styled(
+            format!(" :{input}\u{2588}"),
+            Style::default().fg(Color::White),
+        ));
+        f.render_widget(Paragraph::new(line), area);
+        return;
+    }
 
     let file_count = app.diff_state.files.len();
     let adds = app.diff_state.stats.additions;
@@ -144,6 +214,15 @@ pub fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
 }
 
 pub fn render_gh_status_bar(f: &mut Frame, app: &App, area: Rect) {
+    if app.search.active {
+        let prompt = format!("/{}\u{2588}", app.search.input);
+        let line = Line::from(Span::styled(
+            format!(" {prompt}"),
+            Style::default().fg(Color::White),
+        ));
+        f.render_widget(Paragraph::new(line), area);
+        return;
+    }
     if let Some(ref err) = app.github.gh_error {
         let line = Line::from(Span::styled(
             format!(" {err}"),
@@ -176,16 +255,19 @@ pub fn render_gh_status_bar(f: &mut Frame, app: &App, area: Rect) {
             format!("{} PR{}", pr_count, if pr_count == 1 { "" } else { "s" }),
             Style::default().fg(Color::White),
         ));
+        if app.github.prs_mine_only {
+            spans.push(Span::styled(" (mine)", Style::default().fg(Color::Cyan)));
+        }
     }
 
     let line = Line::from(spans);
     f.render_widget(Paragraph::new(line), area);
 }
 
-pub fn render_help_overlay(f: &mut Frame, area: Rect, view_mode: ViewMode) {
-    use ratatui::widgets::{Block, Borders, Clear};
-
-    let keybindings = match view_mode {
+/// Key / description pairs listed in the help overlay. An empty key with a
+/// `── Name ──` description starts a section; empty pairs are spacers.
+fn help_entries(view_mode: ViewMode) -> Vec<(&'static str, &'static str)> {
+    match view_mode {
         ViewMode::Git => vec![
             ("1 / 2", "Switch to Git / GitHub"),
             ("j / ↓", "Next item / Scroll down"),
@@ -198,13 +280,45 @@ pub fn render_help_overlay(f: &mut Frame, area: Rect, view_mode: ViewMode) {
             ("g / G", "Top / Bottom"),
             ("h / l", "Scroll left / right"),
             ("i", "Normal mode (cursor)"),
+            ("]f / [f", "Next / Prev file (diff view)"),
+            ("c", "Toggle changes only (diff view)"),
+            ("< / >", "Move diff divider left / right"),
+            ("|", "Full width for new files on / off"),
+            ("w / b / e", "Word motions (Normal/Visual)"),
+            ("W / B / E", "WORD motions (whitespace only)"),
+            ("{ / }", "Prev / Next blank line or hunk"),
+            ("m{a-z}", "Set mark (Normal)"),
+            ("'{a-z} / `{a-z}", "Jump to mark row / position"),
+            ("Ctrl+o / Ctrl+i", "Jump list back / forward"),
             ("v / V", "Visual / Visual Line"),
+            ("Ctrl+v", "Visual Block (column selection)"),
+            ("R", "Toggle relative line numbers"),
+            ("K", "Blame the cursor line (Normal)"),
+            ("L", "Hide / show line numbers"),
+            ("P", "Cycle diff colors (red-green / blue-orange / symbols)"),
             ("y", "Yank (copy) selection"),
-            ("/", "Search"),
+            ("gy", "Yank selection as path:line: lines (Visual)"),
+            (".", "Repeat last yank"),
+            ("Y", "Copy file diff (unified) / matching lines while searching"),
+            ("C / S", "Copy hunk as diff / suggestion"),
+            ("a / u", "Stage / Unstage hunk (Normal)"),
+            ("/", "Search (in selection from Visual)"),
             ("n / N", "Next / Prev match"),
+            ("* / #", "Search word under cursor"),
             ("Esc", "Clear search / Back"),
-            ("e", "Open in $EDITOR"),
-            ("r", "Refresh diff + branches"),
+            ("e", "Open in $EDITOR at the top visible line"),
+            ("Enter", "Open in $EDITOR at the cursor (Normal)"),
+            ("O", "Open with default app"),
+            ("E", "Open in the configured diff_tool"),
+            ("T", "Show a binary file as text / as binary"),
+            ("U", "All files as one unified diff"),
+            ("r", "Refresh the diff"),
+            ("Ctrl+r", "Refresh diff, branches and reflog"),
+            ("s", "Cycle all / unstaged / staged"),
+            ("D", "Compare two refs"),
+            ("B", "Pick the diff base"),
+            ("Ctrl+p", "Find a changed file"),
+            (":", "Command line (:base :goto :theme :reload)"),
             ("?", "Toggle help"),
             ("q", "Quit"),
             ("", ""),
@@ -216,8 +330,13 @@ pub fn render_help_overlay(f: &mut Frame, area: Rect, view_mode: ViewMode) {
             ("j / k", "Navigate commits"),
             ("Ctrl+d/u", "Half page scroll"),
             ("g / G", "Top / Bottom"),
-            ("y", "Copy commit hash"),
+            ("y", "Copy commit hash (range: a^..b)"),
+            ("v", "Mark a range of commits"),
+            ("Y", "Copy the range's hashes"),
             ("o", "Open in GitHub"),
+            ("R", "Reset HEAD here"),
+            ("t", "Toggle relative dates"),
+            ("a", "Toggle author emails"),
             ("/", "Search commits"),
             ("", ""),
             ("", "── Reflog ──"),
@@ -225,54 +344,140 @@ pub fn render_help_overlay(f: &mut Frame, area: Rect, view_mode: ViewMode) {
             ("Ctrl+d/u", "Half page scroll"),
             ("g / G", "Top / Bottom"),
             ("Enter", "Set as diff base"),
+            ("R", "Reset HEAD here"),
             ("/", "Search reflog"),
         ],
         ViewMode::GitHub => vec![
-            ("1 / 2", "Switch to Git / GitHub"),
+            ("gt", "Switch to Git (1gt / 2gt: by number)"),
             ("h / l", "Issues ↔ PRs (list)"),
-            ("j / k", "Navigate list"),
+            ("j / k", "Navigate (count: 5j)"),
             ("i / Enter", "Open detail"),
             ("o", "Open in browser"),
+            ("y / Y", "Copy URL / #number"),
+            ("y (Status)", "Copy the selected check and its result"),
             ("Esc", "Back to list"),
             ("h / l", "Body ↔ Right pane (detail)"),
             ("Tab / S-Tab", "Cycle right panes (detail)"),
+            ("m", "Toggle my PRs only (PR list)"),
+            ("p", "Pin / unpin to the top (lists)"),
+            ("D", "Toggle PR draft / ready"),
+            ("] / [", "Next / Prev comment or review"),
+            ("] / [ (Status)", "Next / Prev failed or running check"),
+            ("/ n N", "Search active pane (detail)"),
             ("Ctrl+d", "Half page down (detail)"),
             ("Ctrl+u", "Half page up (detail)"),
-            ("g / G", "Top / Bottom"),
+            ("gg / G", "Top / Bottom (last item in lists)"),
             ("r", "Refresh data"),
+            ("J", "Raw gh JSON (detail, --debug)"),
             ("?", "Toggle help"),
             ("q", "Quit"),
         ],
-    };
+    }
+}
+
+const HELP_KEY_WIDTH: usize = 14;
 
-    let help_width = 50u16.min(area.width.saturating_sub(4));
-    let help_height = ((keybindings.len() as u16) + 2).min(area.height.saturating_sub(4));
+/// Help lines for `view_mode`, keeping only entries whose key or description
+/// contains `filter` (and the headers of their sections). Descriptions wrap at
+/// word boundaries under their own column.
+fn help_lines(view_mode: ViewMode, filter: &str, width: usize) -> Vec<Line<'static>> {
+    let filter = filter.to_lowercase();
+    let key_style = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+    let desc_width = width.saturating_sub(HELP_KEY_WIDTH).max(10);
+    let mut lines = Vec::new();
+    let mut pending_header: Option<&'static str> = None;
+    for (key, desc) in help_entries(view_mode) {
+        if key.is_empty() {
+            if filter.is_empty() {
+                lines.push(Line::from(Span::raw(format!("  {desc}"))));
+            } else if !desc.is_empty() {
+                pending_header = Some(desc);
+            }
+            continue;
+        }
+        if !filter.is_empty()
+            && !key.to_lowercase().contains(&filter)
+            && !desc.to_lowercase().contains(&filter)
+        {
+            continue;
+        }
+        if let Some(header) = pending_header.take() {
+            lines.push(Line::from(Span::raw(format!("  {header}"))));
+        }
+        for (idx, chunk) in wrap_words(desc, desc_width).into_iter().enumerate() {
+            let key_col = if idx == 0 { key } else { "" };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {key_col:<12}"), key_style),
+                Span::raw(chunk),
+            ]));
+        }
+    }
+    lines
+}
+
+/// Split `text` into lines of at most `width` columns, breaking between words.
+fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split(' ') {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    lines.push(current);
+    lines
+}
+
+/// Keybinding overlay, scrolled with `j` / `k` and filtered with `/`.
+pub fn render_help_overlay(f: &mut Frame, app: &mut App, area: Rect) {
+    use ratatui::widgets::{Block, Borders, Clear};
+
+    let help_width = 56u16.min(area.width.saturating_sub(4));
+    let inner_width = help_width.saturating_sub(2) as usize;
+    let lines = help_lines(app.view_mode, &app.help.filter, inner_width);
+    let help_height = ((lines.len() as u16).max(1) + 2).min(area.height.saturating_sub(4));
     let x = (area.width.saturating_sub(help_width)) / 2;
     let y = (area.height.saturating_sub(help_height)) / 2;
     let help_area = Rect::new(x, y, help_width, help_height);
 
-    f.render_widget(Clear, help_area);
+    let visible = help_height.saturating_sub(2);
+    app.help.view_height = visible;
+    app.help.max_scroll = (lines.len() as u16).saturating_sub(visible);
+    app.help.scroll = app.help.scroll.min(app.help.max_scroll);
 
-    let lines: Vec<Line> = keybindings
-        .into_iter()
-        .map(|(key, desc)| {
-            Line::from(vec![
-                Span::styled(
-                    format!("  {key:<12}"),
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                ),
-                Span::raw(desc),
-            ])
-        })
-        .collect();
+    f.render_widget(Clear, help_area);
 
-    let block = Block::default()
+    let mut block = Block::default()
         .title(" Keybindings ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
+    let footer = if app.help.filtering {
+        format!(" /{}\u{2588} ", app.help.filter)
+    } else if !app.help.filter.is_empty() {
+        format!(" /{}  Esc: clear ", app.help.filter)
+    } else if app.help.max_scroll > 0 {
+        format!(
+            " {}/{}  j/k: scroll  /: search  ?: close ",
+            app.help.scroll + visible,
+            lines.len()
+        )
+    } else {
+        " /: search  ?: close ".to_string()
+    };
+    block = block.title_bottom(Line::from(footer).right_aligned());
 
-    let para = Paragraph::new(lines).block(block);
+    let lines = if lines.is_empty() {
+        vec![Line::from(Span::styled(
+            "  No matching keys",
+            Style::default().fg(Color::DarkGray),
+        ))]
+    } else {
+        lines
+    };
+    let para = Paragraph::new(lines).block(block).scroll((app.help.scroll, 0));
     f.render_widget(para, help_area);
 }